@@ -0,0 +1,36 @@
+//! Compares `Geometry::intersects` with `fast_intersects` in a naive point
+//! in polygon join over random data. Run with
+//! `cargo bench --features testing --bench fast_intersects`.
+
+extern crate gdal;
+
+use std::time::{Duration, Instant};
+use gdal::vector::{Envelope, Geometry};
+use gdal::vector::testing::{Rng, random_points, random_polygons};
+
+fn join(points: &[Geometry], polygons: &[Geometry], predicate: fn(&Geometry, &Geometry) -> bool) -> (usize, Duration) {
+    let start = Instant::now();
+    let mut hits = 0;
+    for polygon in polygons {
+        for point in points {
+            if predicate(polygon, point) {
+                hits += 1;
+            }
+        }
+    }
+    return (hits, start.elapsed());
+}
+
+fn main() {
+    let envelope = Envelope::new(0., 1000., 0., 1000.);
+    let mut rng = Rng::new(42);
+    let points = random_points(&envelope, 10000, &mut rng);
+    let polygons = random_polygons(&envelope, 100, 20., &mut rng);
+
+    let (hits, slow) = join(&points, &polygons, Geometry::intersects);
+    let (fast_hits, fast) = join(&points, &polygons, Geometry::fast_intersects);
+    assert_eq!(hits, fast_hits);
+    println!("{} point in polygon tests, {} hits", points.len() * polygons.len(), hits);
+    println!("intersects:      {:?}", slow);
+    println!("fast_intersects: {:?}", fast);
+}
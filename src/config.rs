@@ -0,0 +1,164 @@
+//! GDAL configuration options
+//!
+//! Configuration options control global GDAL behaviour, e.g.
+//! `GDAL_PAM_ENABLED` or `GDAL_CACHEMAX`. See
+//! [the GDAL wiki](https://trac.osgeo.org/gdal/wiki/ConfigOptions) for the
+//! full list.
+//!
+//! ```
+//! use gdal::config;
+//!
+//! config::set_config_option("GDAL_CACHEMAX", "64").unwrap();
+//! assert_eq!(config::get_config_option("GDAL_CACHEMAX", "").unwrap(), "64");
+//! config::clear_config_option("GDAL_CACHEMAX").unwrap();
+//! ```
+
+use libc::c_char;
+use std::ptr::null;
+use utils::{_cstring, _string};
+use GdalError;
+
+#[link(name="gdal")]
+extern {
+    fn CPLSetConfigOption(pszKey: *const c_char, pszValue: *const c_char);
+    fn CPLGetConfigOption(pszKey: *const c_char, pszDefault: *const c_char) -> *const c_char;
+    fn CPLSetThreadLocalConfigOption(pszKey: *const c_char, pszValue: *const c_char);
+    fn CPLGetThreadLocalConfigOption(pszKey: *const c_char, pszDefault: *const c_char) -> *const c_char;
+}
+
+/// A number of worker threads, as accepted by `GDAL_NUM_THREADS` and the
+/// `NUM_THREADS` creation and warp options.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum NumThreads {
+    /// One thread per CPU core.
+    AllCpus,
+    Count(usize),
+}
+
+impl NumThreads {
+    /// The option value, `ALL_CPUS` or the thread count.
+    pub fn value(&self) -> String {
+        return match *self {
+            NumThreads::AllCpus => "ALL_CPUS".to_string(),
+            NumThreads::Count(count) => count.to_string(),
+        };
+    }
+}
+
+/// Set `GDAL_NUM_THREADS` globally. It's the default thread count of the
+/// warper (`align`, `reproject`, ...), of multi-threaded GeoTIFF
+/// compression and of some drivers' decoding.
+pub fn set_num_threads(threads: NumThreads) -> Result<(), GdalError> {
+    return set_config_option("GDAL_NUM_THREADS", &threads.value());
+}
+
+/// Run `f` with `GDAL_NUM_THREADS` set to `threads` for the current
+/// thread only, see `set_num_threads`.
+pub fn with_num_threads<T, F>(threads: NumThreads, f: F) -> Result<T, GdalError> where F: FnOnce() -> T {
+    return with_thread_local_config_option("GDAL_NUM_THREADS", &threads.value(), f);
+}
+
+/// Set a global configuration option.
+pub fn set_config_option(key: &str, value: &str) -> Result<(), GdalError> {
+    let c_key = _cstring(key)?;
+    let c_value = _cstring(value)?;
+    unsafe { CPLSetConfigOption(c_key.as_ptr(), c_value.as_ptr()) };
+    return Ok(());
+}
+
+/// Get the value of a configuration option, or `default` if it's not set.
+/// Thread local options take precedence over global ones.
+pub fn get_config_option(key: &str, default: &str) -> Result<String, GdalError> {
+    let c_key = _cstring(key)?;
+    let c_default = _cstring(default)?;
+    return Ok(_string(unsafe { CPLGetConfigOption(c_key.as_ptr(), c_default.as_ptr()) }));
+}
+
+/// Unset a global configuration option.
+pub fn clear_config_option(key: &str) -> Result<(), GdalError> {
+    let c_key = _cstring(key)?;
+    unsafe { CPLSetConfigOption(c_key.as_ptr(), null()) };
+    return Ok(());
+}
+
+/// Set a configuration option for the current thread only.
+pub fn set_thread_local_config_option(key: &str, value: &str) -> Result<(), GdalError> {
+    let c_key = _cstring(key)?;
+    let c_value = _cstring(value)?;
+    unsafe { CPLSetThreadLocalConfigOption(c_key.as_ptr(), c_value.as_ptr()) };
+    return Ok(());
+}
+
+/// Get the value of a thread local configuration option, ignoring global
+/// options.
+pub fn get_thread_local_config_option(key: &str) -> Result<Option<String>, GdalError> {
+    let c_key = _cstring(key)?;
+    let rv = unsafe { CPLGetThreadLocalConfigOption(c_key.as_ptr(), null()) };
+    return Ok(match rv.is_null() {
+        true  => None,
+        false => Some(_string(rv)),
+    });
+}
+
+/// Unset a configuration option for the current thread.
+pub fn clear_thread_local_config_option(key: &str) -> Result<(), GdalError> {
+    let c_key = _cstring(key)?;
+    unsafe { CPLSetThreadLocalConfigOption(c_key.as_ptr(), null()) };
+    return Ok(());
+}
+
+/// Run `f` with thread local `key` set to `value`, restoring the previous
+/// thread local value afterwards. Fails without running `f` if `key` or
+/// `value` contain a NUL byte.
+pub fn with_thread_local_config_option<T, F>(key: &str, value: &str, f: F) -> Result<T, GdalError> where F: FnOnce() -> T {
+    let previous = get_thread_local_config_option(key)?;
+    set_thread_local_config_option(key, value)?;
+    let rv = f();
+    match previous {
+        Some(previous) => set_thread_local_config_option(key, &previous)?,
+        None => clear_thread_local_config_option(key)?,
+    };
+    return Ok(rv);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config_options() {
+        assert_eq!(get_config_option("RUST_GDAL_TEST", "nope").unwrap(), "nope");
+        set_config_option("RUST_GDAL_TEST", "global").unwrap();
+        assert_eq!(get_config_option("RUST_GDAL_TEST", "nope").unwrap(), "global");
+
+        let inner = with_thread_local_config_option("RUST_GDAL_TEST", "local", || {
+            get_config_option("RUST_GDAL_TEST", "nope").unwrap()
+        }).unwrap();
+        assert_eq!(inner, "local");
+        assert_eq!(get_thread_local_config_option("RUST_GDAL_TEST").unwrap(), None);
+        assert_eq!(get_config_option("RUST_GDAL_TEST", "nope").unwrap(), "global");
+
+        clear_config_option("RUST_GDAL_TEST").unwrap();
+        assert_eq!(get_config_option("RUST_GDAL_TEST", "nope").unwrap(), "nope");
+    }
+
+    #[test]
+    fn test_config_options_with_nul() {
+        match set_config_option("RUST_GDAL_TEST", "a\0b") {
+            Err(GdalError::FfiNulError(_)) => (),
+            _ => panic!("expected a NUL error"),
+        }
+        assert!(get_config_option("RUST\0GDAL_TEST", "").is_err());
+        let mut ran = false;
+        assert!(with_thread_local_config_option("RUST_GDAL_TEST", "a\0b", || ran = true).is_err());
+        assert!(!ran);
+    }
+
+    #[test]
+    fn test_num_threads() {
+        assert_eq!(NumThreads::Count(4).value(), "4");
+        let inner = with_num_threads(NumThreads::AllCpus, || get_config_option("GDAL_NUM_THREADS", "1").unwrap()).unwrap();
+        assert_eq!(inner, "ALL_CPUS");
+        assert_eq!(get_thread_local_config_option("GDAL_NUM_THREADS").unwrap(), None);
+    }
+}
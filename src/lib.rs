@@ -11,8 +11,8 @@
 //! let mut dataset = Dataset::open(Path::new("fixtures/roads.geojson")).unwrap();
 //! let layer = dataset.layer(0).unwrap();
 //! for feature in layer.features() {
-//!     let highway_field = feature.field("highway").unwrap();
-//!     let geometry = feature.geometry();
+//!     let highway_field = feature.field("highway").unwrap().unwrap();
+//!     let geometry = feature.geometry().unwrap();
 //!     println!("{} {}", highway_field.as_string(), geometry.wkt());
 //! }
 //! ```
@@ -22,15 +22,53 @@
 
 extern crate libc;
 extern crate geo;
+#[cfg(feature = "chrono")]
+extern crate chrono;
+
+use std::error::Error;
+use std::ffi::NulError;
+use std::fmt;
 
 pub use version::version_info;
+pub use open_flags::OpenFlags;
+pub use raster::{align, expand_palette, rgb_to_paletted, stack_bands, validate_cog, zonal_stats};
 
 mod utils;
+mod open_flags;
+pub mod config;
 pub mod version;
 pub mod raster;
+pub mod spatial_ref;
 pub mod vector;
 
-#[derive(Clone, Copy, PartialEq, Debug)]
-pub struct GdalError {
-    pub desc: &'static str,
+#[derive(Clone, PartialEq, Debug)]
+pub enum GdalError {
+    /// A GDAL call failed, or was given arguments it can't handle.
+    CallFailed{desc: &'static str},
+    /// A string passed to GDAL contains a NUL byte, which C strings can't
+    /// represent.
+    FfiNulError(NulError),
+    /// A value doesn't fit into the width or type of field `field`.
+    FieldOverflow{field: String},
+    /// An SQL statement failed, with the message reported by GDAL.
+    SqlFailed{message: String},
+}
+
+impl From<NulError> for GdalError {
+    fn from(err: NulError) -> GdalError {
+        return GdalError::FfiNulError(err);
+    }
 }
+
+impl fmt::Display for GdalError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        return match *self {
+            GdalError::CallFailed{desc} => write!(f, "{}", desc),
+            GdalError::FfiNulError(ref err) => write!(f, "{}", err),
+            GdalError::FieldOverflow{ref field} => write!(f, "value doesn't fit into field {}", field),
+            GdalError::SqlFailed{ref message} => write!(f, "SQL statement failed: {}", message),
+        };
+    }
+}
+
+impl Error for GdalError {}
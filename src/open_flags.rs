@@ -0,0 +1,49 @@
+//! Access mode and kind of data of an open dataset
+use std::ops::BitOr;
+use libc::c_uint;
+
+/// The `GDAL_OF_*` flags a dataset was opened with: whether it can be
+/// written to, and whether it was opened for its raster bands, its vector
+/// layers or both.
+///
+/// ```
+/// use std::path::Path;
+/// use gdal::OpenFlags;
+/// use gdal::vector::Dataset;
+///
+/// let dataset = Dataset::open(Path::new("fixtures/roads.geojson")).unwrap();
+/// assert!(dataset.open_flags().contains(OpenFlags::VECTOR));
+/// assert!(!dataset.is_update());
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct OpenFlags {
+    bits: c_uint,
+}
+
+impl OpenFlags {
+    pub const READ_ONLY: OpenFlags = OpenFlags{bits: 0x00};
+    pub const UPDATE: OpenFlags = OpenFlags{bits: 0x01};
+    pub const RASTER: OpenFlags = OpenFlags{bits: 0x02};
+    pub const VECTOR: OpenFlags = OpenFlags{bits: 0x04};
+    /// Open through GDAL's shared dataset list, see
+    /// `raster::Dataset::open_shared`.
+    pub const SHARED: OpenFlags = OpenFlags{bits: 0x20};
+
+    /// The flags as the `GDAL_OF_*` bits passed to `GDALOpenEx`.
+    pub fn bits(&self) -> c_uint {
+        return self.bits;
+    }
+
+    /// Whether all flags of `other` are set.
+    pub fn contains(&self, other: OpenFlags) -> bool {
+        return self.bits & other.bits == other.bits;
+    }
+}
+
+impl BitOr for OpenFlags {
+    type Output = OpenFlags;
+
+    fn bitor(self, other: OpenFlags) -> OpenFlags {
+        return OpenFlags{bits: self.bits | other.bits};
+    }
+}
@@ -0,0 +1,96 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use raster::Dataset;
+use OpenFlags;
+
+/// Cache of open raster datasets, keyed by path, access mode and open
+/// options.
+///
+/// Datasets are opened through GDAL's shared dataset list and handed out
+/// as reference-counted handles, so code that repeatedly opens the same
+/// files (e.g. a tile server) only pays for parsing the headers once.
+/// Opening a path with other flags or open options gives another handle.
+/// GDAL handles are not thread safe, which is why the cache hands out
+/// `Rc`s; use one cache per thread.
+///
+/// ```no_run
+/// use std::path::Path;
+/// use gdal::raster::DatasetCache;
+///
+/// let mut cache = DatasetCache::new();
+/// let first = cache.open(Path::new("fixtures/tinymarble.png")).unwrap();
+/// let again = cache.open(Path::new("fixtures/tinymarble.png")).unwrap();
+/// assert_eq!(cache.len(), 1);
+/// ```
+pub struct DatasetCache {
+    datasets: HashMap<CacheKey, Rc<Dataset>>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+struct CacheKey {
+    path: PathBuf,
+    flags: u32,
+    options: Vec<String>,
+}
+
+impl DatasetCache {
+    pub fn new() -> DatasetCache {
+        return DatasetCache{datasets: HashMap::new()};
+    }
+
+    /// Get the dataset at `path` opened read-only, opening it if it's not
+    /// cached yet.
+    pub fn open(&mut self, path: &Path) -> Option<Rc<Dataset>> {
+        return self.open_ex(path, OpenFlags::READ_ONLY, &[]);
+    }
+
+    /// Get the dataset at `path` opened with `flags` and driver specific
+    /// `options`, see `Dataset::open_ex`, opening it if it's not cached
+    /// yet. GDAL's shared dataset list doesn't tell open options apart, so
+    /// it's only used without options.
+    pub fn open_ex(&mut self, path: &Path, flags: OpenFlags, options: &[String]) -> Option<Rc<Dataset>> {
+        let key = CacheKey{path: path.to_path_buf(), flags: flags.bits(), options: options.to_vec()};
+        if let Some(dataset) = self.datasets.get(&key) {
+            return Some(dataset.clone());
+        }
+        let flags = match options.is_empty() {
+            true  => flags | OpenFlags::SHARED,
+            false => flags,
+        };
+        let dataset = Rc::new(Dataset::open_ex(path, flags, options)?);
+        self.datasets.insert(key, dataset.clone());
+        return Some(dataset);
+    }
+
+    /// Drop the cache's handles to `path`, whatever it was opened with.
+    /// The datasets are closed once the last outstanding `Rc` goes away.
+    pub fn remove(&mut self, path: &Path) -> bool {
+        let len = self.datasets.len();
+        self.datasets.retain(|key, _| key.path != path);
+        return self.datasets.len() != len;
+    }
+
+    /// Drop every cached handle that isn't used outside the cache anymore.
+    pub fn release_unused(&mut self) {
+        self.datasets.retain(|_, dataset| Rc::strong_count(dataset) > 1);
+    }
+
+    pub fn clear(&mut self) {
+        self.datasets.clear();
+    }
+
+    pub fn len(&self) -> usize {
+        return self.datasets.len();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return self.datasets.is_empty();
+    }
+}
+
+impl Default for DatasetCache {
+    fn default() -> DatasetCache {
+        return DatasetCache::new();
+    }
+}
@@ -0,0 +1,68 @@
+use raster::{Dataset, Buffer, Window, RasterSize};
+use raster::dataset::{apply_geo_transform, invert_geo_transform};
+use raster::types::GdalType;
+use vector::{FieldValue, Layer};
+use GdalError;
+
+/// A fixed-size window of raster data centered on a vector feature.
+pub struct Chip<T: GdalType> {
+    /// Georeferenced center of the feature the chip was cut around.
+    pub center: (f64, f64),
+    /// Position of the top left corner of the chip in the raster.
+    pub window: (isize, isize),
+    /// Value of the label field of the feature, if one was requested and
+    /// the feature has it, with invalid UTF-8 replaced by U+FFFD.
+    pub label: Option<FieldValue>,
+    /// One buffer per band, in band order.
+    pub bands: Vec<Buffer<T>>,
+}
+
+/// Cut a `chip_size` window out of every band of `dataset` around each
+/// feature of `layer`, e.g. to build training samples for machine
+/// learning. Feature geometries are reduced to their centroid, so both
+/// point and polygon layers work. Features whose chip would extend past
+/// the raster edges are skipped.
+///
+/// The layer must use the same coordinate system as the raster. Fails if
+/// the raster has no invertible geo-transform.
+pub fn extract_chips<T: Copy + GdalType>(
+    dataset: &Dataset,
+    layer: &Layer,
+    chip_size: (usize, usize),
+    label_field: Option<&str>,
+) -> Result<Vec<Chip<T>>, GdalError> {
+    let transform = match dataset.geo_transform() {
+        Some(transform) => transform,
+        None => return Err(GdalError::CallFailed{desc: "dataset has no geo-transform"}),
+    };
+    let inverse = match invert_geo_transform(&transform) {
+        Some(inverse) => inverse,
+        None => return Err(GdalError::CallFailed{desc: "geo-transform is not invertible"}),
+    };
+    let (size_x, size_y) = dataset.size();
+
+    let mut chips = vec!();
+    for feature in layer.features() {
+        let center = match feature.geometry().and_then(|geometry| geometry._centroid_xy()) {
+            Some(center) => center,
+            None => continue,
+        };
+        let (pixel_x, pixel_y) = apply_geo_transform(&inverse, center.0, center.1);
+        let window = (
+            pixel_x.floor() as isize - (chip_size.0 / 2) as isize,
+            pixel_y.floor() as isize - (chip_size.1 / 2) as isize,
+        );
+        if window.0 < 0 || window.1 < 0 ||
+           window.0 + chip_size.0 as isize > size_x ||
+           window.1 + chip_size.1 as isize > size_y {
+            continue;
+        }
+        let chip_window = Window::from_tuples(window, chip_size);
+        let bands = (1..dataset.count() + 1)
+            .map(|band_index| dataset.read_raster_as::<T>(band_index, chip_window, RasterSize::new(chip_size.0, chip_size.1)))
+            .collect::<Result<_, _>>()?;
+        let label = label_field.and_then(|name| feature.field_lossy(name));
+        chips.push(Chip{center: center, window: window, label: label, bands: bands});
+    }
+    return Ok(chips);
+}
@@ -0,0 +1,258 @@
+//! Cloud optimized GeoTIFF validation
+//!
+//! A COG is a GeoTIFF laid out so a client can fetch any part of it at any
+//! resolution with a few HTTP range requests: tiled, with internal
+//! overviews, and with the IFDs (the TIFF directories) at the start of the
+//! file, before the tile data of the smallest to the largest resolution.
+//!
+//! ```no_run
+//! use std::path::Path;
+//! use gdal::raster::Dataset;
+//!
+//! let dataset = Dataset::open(Path::new("output.tif")).unwrap();
+//! let report = gdal::validate_cog(&dataset);
+//! for error in &report.errors {
+//!     println!("not a COG: {}", error);
+//! }
+//! ```
+
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use raster::{Dataset, RasterBand};
+
+/// Rasters up to this size in both dimensions don't need tiles or
+/// overviews.
+const SMALL_SIZE: usize = 512;
+
+/// Widest strips accepted as tiles.
+const MAX_STRIP_WIDTH: usize = 1024;
+
+/// Outcome of `validate_cog`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CogReport {
+    /// Layout problems that make the file not cloud optimized.
+    pub errors: Vec<String>,
+    /// Deviations that keep the file valid but make remote access slower,
+    /// and checks that couldn't be done.
+    pub warnings: Vec<String>,
+    /// Block size of the full resolution image.
+    pub block_size: (usize, usize),
+    /// Number of internal overview levels.
+    pub overview_count: usize,
+    /// The `LAYOUT` GDAL reports for the file, `Some("COG")` when it found
+    /// the ghost area written by the COG driver. Needs GDAL 3.1.
+    pub layout: Option<String>,
+}
+
+impl CogReport {
+    /// Whether no error was found.
+    pub fn is_valid(&self) -> bool {
+        return self.errors.is_empty();
+    }
+}
+
+/// Check the layout of `dataset` against the COG requirements, like
+/// `validate_cloud_optimized_geotiff.py` does: a GeoTIFF with tiles and
+/// internal overviews, the main IFD first, overview IFDs after it in
+/// order of decreasing size, and the tile data of the smallest overview
+/// first and of the full resolution last.
+///
+/// The offsets of the TIFF structures come from the `TIFF` metadata
+/// domain of the bands; checks whose offsets GDAL doesn't report are
+/// skipped.
+///
+/// When the ghost area at the start of the file announces block leaders
+/// (the size of every tile before its data) and trailers (its last 4
+/// bytes repeated after it), as the COG driver writes them, they are
+/// checked for the first tile of every resolution. This reads the file
+/// directly, so for files that aren't on the local file system, e.g. in
+/// `/vsicurl/`, it is skipped with a warning.
+pub fn validate_cog(dataset: &Dataset) -> CogReport {
+    let mut report = CogReport::default();
+    if dataset.driver().short_name() != "GTiff" {
+        report.errors.push("the file is not a GeoTIFF".to_string());
+        return report;
+    }
+    let band = match dataset.rasterband(1) {
+        Some(band) => band,
+        None => {
+            report.errors.push("the file has no band".to_string());
+            return report;
+        },
+    };
+    if dataset.file_list().iter().skip(1).any(|file| file.to_lowercase().ends_with(".ovr")) {
+        report.errors.push("overviews are in an external .ovr file, they should be internal".to_string());
+    }
+    report.block_size = band.block_size();
+    report.overview_count = band.overview_count() as usize;
+    report.layout = dataset.metadata_item("LAYOUT", "IMAGE_STRUCTURE");
+
+    let (size_x, size_y) = band.size();
+    if size_x > SMALL_SIZE || size_y > SMALL_SIZE {
+        if !is_tiled(&band) {
+            report.errors.push(format!("the file is larger than {0}x{0} but not tiled", SMALL_SIZE));
+        }
+        if report.overview_count == 0 {
+            report.warnings.push(format!("the file is larger than {0}x{0} but has no overviews", SMALL_SIZE));
+        }
+    }
+
+    let mut ifd_offsets = vec!(tiff_offset(&band, "IFD_OFFSET"));
+    let mut data_offsets = vec!(tiff_offset(&band, "BLOCK_OFFSET_0_0"));
+    if let Some(offset) = ifd_offsets[0] {
+        if offset != 8 && offset != 16 {
+            report.errors.push(format!(
+                "the main IFD is at offset {}, it should be at 8 (classic TIFF) or 16 (BigTIFF)", offset,
+            ));
+        }
+    }
+    let mut previous_size = (size_x, size_y);
+    for i in 0..report.overview_count {
+        let overview = band.overview(i as isize).unwrap();
+        let overview_size = overview.size();
+        if overview_size.0 > previous_size.0 || overview_size.1 > previous_size.1 {
+            report.errors.push(format!("overview {} is larger than the previous resolution", i));
+        }
+        if (overview_size.0 > SMALL_SIZE || overview_size.1 > SMALL_SIZE) && !is_tiled(&overview) {
+            report.errors.push(format!("overview {} is not tiled", i));
+        }
+        previous_size = overview_size;
+
+        let ifd_offset = tiff_offset(&overview, "IFD_OFFSET");
+        if let (Some(previous), Some(offset)) = (ifd_offsets[i], ifd_offset) {
+            if offset < previous {
+                report.errors.push(format!("the IFD of overview {} comes before the one of the previous resolution", i));
+            }
+        }
+        ifd_offsets.push(ifd_offset);
+        data_offsets.push(tiff_offset(&overview, "BLOCK_OFFSET_0_0"));
+    }
+
+    for i in 0..data_offsets.len().saturating_sub(1) {
+        if let (Some(offset), Some(next)) = (data_offsets[i], data_offsets[i + 1]) {
+            if offset < next {
+                let level = match i {
+                    0 => "the full resolution image".to_string(),
+                    _ => format!("overview {}", i - 1),
+                };
+                report.errors.push(format!("the tile data of {} comes before the one of overview {}", level, i));
+            }
+        }
+    }
+
+    let last_ifd = ifd_offsets.iter().filter_map(|&offset| offset).max();
+    let first_data = data_offsets.iter().filter_map(|&offset| offset).min();
+    if let (Some(last_ifd), Some(first_data)) = (last_ifd, first_data) {
+        if first_data < last_ifd {
+            report.warnings.push("some tile data comes before the last IFD, clients need more requests to read the header".to_string());
+        }
+    }
+
+    let mut levels = vec!(band);
+    for i in 0..report.overview_count {
+        levels.push(levels[0].overview(i as isize).unwrap());
+    }
+    check_block_markers(dataset, &levels, &mut report);
+    return report;
+}
+
+/// Check the block leader and trailer of the first tile of every level
+/// in `levels`, the full resolution band followed by its overviews, if
+/// the ghost area announces them.
+fn check_block_markers(dataset: &Dataset, levels: &[RasterBand], report: &mut CogReport) {
+    let mut file = match dataset.file_list().first().and_then(|path| File::open(path).ok()) {
+        Some(file) => file,
+        None => {
+            report.warnings.push("the file can't be read directly, block leaders and trailers were not checked".to_string());
+            return;
+        },
+    };
+    // unflushed files of datasets still being written may lack a header
+    let header = read_at(&mut file, 0, 16).unwrap_or_default();
+    let little_endian = header.starts_with(b"II");
+    let ghost_offset = match (little_endian, header.get(2..4)) {
+        (true, Some([42, 0])) | (false, Some([0, 42])) => 8,
+        (true, Some([43, 0])) | (false, Some([0, 43])) => 16,
+        _ => {
+            report.warnings.push("the TIFF header can't be read, block leaders and trailers were not checked".to_string());
+            return;
+        },
+    };
+    let ghost = read_ghost_area(&mut file, ghost_offset).unwrap_or_default();
+    let leader = ghost.contains("BLOCK_LEADER=SIZE_AS_UINT4");
+    let trailer = ghost.contains("BLOCK_TRAILER=LAST_4_BYTES_REPEATED");
+    if !leader && !trailer {
+        return;
+    }
+    let to_u32 = |bytes: &[u8]| {
+        let bytes = [bytes[0], bytes[1], bytes[2], bytes[3]];
+        match little_endian {
+            true  => u32::from_le_bytes(bytes),
+            false => u32::from_be_bytes(bytes),
+        }
+    };
+    for (i, level) in levels.iter().enumerate() {
+        let name = match i {
+            0 => "the full resolution image".to_string(),
+            _ => format!("overview {}", i - 1),
+        };
+        let (offset, size) = match (tiff_offset(level, "BLOCK_OFFSET_0_0"), tiff_offset(level, "BLOCK_SIZE_0_0")) {
+            (Some(offset), Some(size)) if offset >= 4 && size >= 4 => (offset, size),
+            _ => continue,
+        };
+        // the leader, the block and the trailer in one read
+        let bytes = match read_at(&mut file, offset - 4, size as usize + 8) {
+            Some(bytes) => bytes,
+            None => {
+                report.errors.push(format!("the first tile of {} extends past the end of the file", name));
+                continue;
+            },
+        };
+        if leader && to_u32(&bytes[..4]) as u64 != size {
+            report.errors.push(format!("the block leader of the first tile of {} doesn't match its size", name));
+        }
+        let end = bytes.len() - 4;
+        if trailer && bytes[end - 4..end] != bytes[end..] {
+            report.errors.push(format!("the block trailer of the first tile of {} doesn't repeat its last 4 bytes", name));
+        }
+    }
+}
+
+/// The text of the ghost area GDAL writes right after the TIFF header,
+/// `None` if there's none.
+fn read_ghost_area(file: &mut File, offset: u64) -> Option<String> {
+    const PREFIX: &str = "GDAL_STRUCTURAL_METADATA_SIZE=";
+    // e.g. "GDAL_STRUCTURAL_METADATA_SIZE=000140 bytes\n"
+    let head = read_at(file, offset, PREFIX.len() + 13)?;
+    let head = String::from_utf8(head).ok()?;
+    if !head.starts_with(PREFIX) {
+        return None;
+    }
+    let size: usize = head[PREFIX.len()..PREFIX.len() + 6].parse().ok()?;
+    let ghost = read_at(file, offset + head.len() as u64, size)?;
+    return String::from_utf8(ghost).ok();
+}
+
+fn read_at(file: &mut File, offset: u64, len: usize) -> Option<Vec<u8>> {
+    let mut buffer = vec![0; len];
+    file.seek(SeekFrom::Start(offset)).ok()?;
+    file.read_exact(&mut buffer).ok()?;
+    return Some(buffer);
+}
+
+/// Whether `band` is made of tiles rather than strips spanning its width.
+/// Like the reference script, strips up to `MAX_STRIP_WIDTH` pass as a
+/// single column of tiles.
+fn is_tiled(band: &RasterBand) -> bool {
+    let (block_x, _) = band.block_size();
+    return block_x != band.size().0 || block_x <= MAX_STRIP_WIDTH;
+}
+
+/// Offset `key` (e.g. `"IFD_OFFSET"`) of the `TIFF` metadata domain of
+/// `band`, `None` if GDAL doesn't report it or the block is sparse.
+fn tiff_offset(band: &RasterBand, key: &str) -> Option<u64> {
+    return match band.metadata_item(key, "TIFF")?.parse() {
+        Ok(0) | Err(_) => None,
+        Ok(offset) => Some(offset),
+    };
+}
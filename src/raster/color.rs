@@ -0,0 +1,207 @@
+//! Color tables and conversion between RGB and paletted rasters
+//!
+//! ```no_run
+//! use std::path::Path;
+//! use gdal::raster::{Dataset, Driver};
+//!
+//! let rgb = Dataset::open(Path::new("tile.tif")).unwrap();
+//! let paletted = gdal::rgb_to_paletted(&rgb, 64).unwrap();
+//! let png = Driver::get("PNG").unwrap();
+//! paletted.create_copy(png, "tile.png").unwrap();
+//! ```
+
+use std::ptr::null;
+use libc::{c_int, c_void};
+use raster::{gdal, Buffer, Dataset, Driver, Window, RasterSize};
+use raster::gdal_enums::GDALDataType;
+use GdalError;
+
+/// A color of a `ColorTable`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ColorEntry {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+    /// 255 for opaque colors.
+    pub a: u8,
+}
+
+impl ColorEntry {
+    /// An opaque color.
+    pub fn rgb(r: u8, g: u8, b: u8) -> ColorEntry {
+        return ColorEntry{r: r, g: g, b: b, a: 255};
+    }
+
+    fn from_c_entry(entry: &gdal::GDALColorEntry) -> ColorEntry {
+        let channel = |c: i16| c.clamp(0, 255) as u8;
+        return ColorEntry{r: channel(entry.c1), g: channel(entry.c2), b: channel(entry.c3), a: channel(entry.c4)};
+    }
+
+    fn to_c_entry(self) -> gdal::GDALColorEntry {
+        return gdal::GDALColorEntry{c1: self.r as i16, c2: self.g as i16, c3: self.b as i16, c4: self.a as i16};
+    }
+}
+
+/// The palette of a paletted band, mapping pixel values to RGB colors.
+pub struct ColorTable {
+    c_color_table: *const c_void,
+}
+
+impl ColorTable {
+    /// An empty RGB color table.
+    pub fn new() -> ColorTable {
+        let c_color_table = unsafe { gdal::GDALCreateColorTable(gdal::GPI_RGB) };
+        assert!(!c_color_table.is_null());
+        return ColorTable{c_color_table: c_color_table};
+    }
+
+    /// An RGB color table with `colors` for the pixel values from 0.
+    pub fn from_colors(colors: &[ColorEntry]) -> ColorTable {
+        let mut table = ColorTable::new();
+        for (i, &color) in colors.iter().enumerate() {
+            table.set_entry(i, color);
+        }
+        return table;
+    }
+
+    /// Copy a color table owned by someone else, e.g. a band.
+    pub unsafe fn _clone_from_c_table(c_color_table: *const c_void) -> ColorTable {
+        let c_clone = gdal::GDALCloneColorTable(c_color_table);
+        assert!(!c_clone.is_null());
+        return ColorTable{c_color_table: c_clone};
+    }
+
+    pub unsafe fn _c_ptr(&self) -> *const c_void {
+        return self.c_color_table;
+    }
+
+    /// Number of entries.
+    pub fn len(&self) -> usize {
+        return unsafe { gdal::GDALGetColorEntryCount(self.c_color_table) } as usize;
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return self.len() == 0;
+    }
+
+    /// The color of pixel value `i`, `None` past the last entry.
+    pub fn entry(&self, i: usize) -> Option<ColorEntry> {
+        let entry = unsafe { gdal::GDALGetColorEntry(self.c_color_table, i as c_int) };
+        return match entry.is_null() {
+            true  => None,
+            false => Some(ColorEntry::from_c_entry(unsafe { &*entry })),
+        };
+    }
+
+    /// Set the color of pixel value `i`. The table grows with transparent
+    /// black entries if `i` is past its end.
+    pub fn set_entry(&mut self, i: usize, color: ColorEntry) {
+        let entry = color.to_c_entry();
+        unsafe { gdal::GDALSetColorEntry(self.c_color_table, i as c_int, &entry) };
+    }
+
+    /// All entries, in order of pixel value.
+    pub fn entries(&self) -> Vec<ColorEntry> {
+        return (0..self.len()).filter_map(|i| self.entry(i)).collect();
+    }
+}
+
+impl Default for ColorTable {
+    fn default() -> ColorTable {
+        return ColorTable::new();
+    }
+}
+
+impl Clone for ColorTable {
+    fn clone(&self) -> ColorTable {
+        return unsafe { ColorTable::_clone_from_c_table(self.c_color_table) };
+    }
+}
+
+impl Drop for ColorTable {
+    fn drop(&mut self) {
+        unsafe { gdal::GDALDestroyColorTable(self.c_color_table) };
+    }
+}
+
+/// Reduce the first three bands of `dataset`, taken as red, green and blue,
+/// to a single band of at most `n_colors` colors, like `rgb2pct.py`: the
+/// palette is computed with the median cut algorithm and the pixels are
+/// dithered to it. The result is an in-memory dataset with the
+/// georeferencing of `dataset`; use `create_copy` to save it e.g. as a
+/// PNG. `n_colors` must be between 2 and 256.
+pub fn rgb_to_paletted(dataset: &Dataset, n_colors: usize) -> Result<Dataset, GdalError> {
+    if !(2..=256).contains(&n_colors) {
+        return Err(GdalError::CallFailed{desc: "the number of colors must be between 2 and 256"});
+    }
+    let bands = match (dataset.rasterband(1), dataset.rasterband(2), dataset.rasterband(3)) {
+        (Some(red), Some(green), Some(blue)) => unsafe { (red._c_ptr(), green._c_ptr(), blue._c_ptr()) },
+        _ => return Err(GdalError::CallFailed{desc: "an RGB dataset needs three bands"}),
+    };
+    let table = ColorTable::new();
+    let rv = unsafe { gdal::GDALComputeMedianCutPCT(
+        bands.0, bands.1, bands.2, null(), n_colors as c_int, table._c_ptr(), null(), null(),
+    ) };
+    if rv != 0 {
+        return Err(GdalError::CallFailed{desc: "GDALComputeMedianCutPCT failed"});
+    }
+    let paletted = create_like(dataset, 1)?;
+    let target = paletted.rasterband(1).unwrap();
+    let rv = unsafe { gdal::GDALDitherRGB2PCT(
+        bands.0, bands.1, bands.2, target._c_ptr(), table._c_ptr(), null(), null(),
+    ) };
+    if rv != 0 {
+        return Err(GdalError::CallFailed{desc: "GDALDitherRGB2PCT failed"});
+    }
+    target.set_color_table(&table)?;
+    return Ok(paletted);
+}
+
+/// Expand the first band of `dataset` through its color table to red,
+/// green and blue bands, plus an alpha band if `alpha` is set. Pixel
+/// values without an entry become transparent black. The result is an
+/// in-memory dataset with the georeferencing of `dataset`.
+pub fn expand_palette(dataset: &Dataset, alpha: bool) -> Result<Dataset, GdalError> {
+    let band = match dataset.rasterband(1) {
+        Some(band) => band,
+        None => return Err(GdalError::CallFailed{desc: "dataset has no bands"}),
+    };
+    let colors = match band.color_table() {
+        Some(table) => table.entries(),
+        None => return Err(GdalError::CallFailed{desc: "the band has no color table"}),
+    };
+    let size = band.size();
+    let window = Window::full(RasterSize::new(size.0, size.1));
+    let indices = band.read_as::<u16>(window, window.size())?.data;
+    let transparent = ColorEntry{r: 0, g: 0, b: 0, a: 0};
+    let pixels: Vec<ColorEntry> = indices.iter()
+        .map(|&i| colors.get(i as usize).cloned().unwrap_or(transparent))
+        .collect();
+
+    let expanded = create_like(dataset, if alpha { 4 } else { 3 })?;
+    let channels: &[fn(&ColorEntry) -> u8] = &[|c| c.r, |c| c.g, |c| c.b, |c| c.a];
+    for (i, channel) in channels.iter().enumerate().take(expanded.count() as usize) {
+        let data = pixels.iter().map(channel).collect();
+        expanded.write_raster(i as isize + 1, window, Buffer::new(size, data))?;
+    }
+    return Ok(expanded);
+}
+
+/// An in-memory byte dataset of `band_count` bands with the size and
+/// georeferencing of `dataset`.
+fn create_like(dataset: &Dataset, band_count: isize) -> Result<Dataset, GdalError> {
+    let driver = match Driver::get("MEM") {
+        Some(driver) => driver,
+        None => return Err(GdalError::CallFailed{desc: "MEM driver not available"}),
+    };
+    let (size_x, size_y) = dataset.size();
+    let created = match driver.create_with_data_type("", size_x, size_y, band_count, GDALDataType::GDT_Byte, &[]) {
+        Some(created) => created,
+        None => return Err(GdalError::CallFailed{desc: "failed to create in-memory raster"}),
+    };
+    if let Some(geo_transform) = dataset.geo_transform() {
+        created.set_geo_transform_checked(&geo_transform)?;
+    }
+    created.set_projection(&dataset.projection())?;
+    return Ok(created);
+}
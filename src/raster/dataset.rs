@@ -1,16 +1,91 @@
 use libc::{c_int, c_double, c_void};
-use std::ffi::CString;
 use std::path::Path;
-use utils::_string;
-use raster::{gdal, Driver};
+use std::ptr::null;
+use utils::{_cstring, _string, _string_array, CslStringList};
+use config;
+use raster::{gdal, vrt, Driver, RasterBand, WarpedView, BandSubsetView};
+use {GdalError, OpenFlags};
 use raster::driver::_register_drivers;
-use raster::gdal_enums::{GDALRWFlag, GDALAccess, GDALDataType};
+use raster::gdal_enums::{GDALRWFlag, GDALAccess, GDALDataType, GDALResampleAlg};
 use raster::types::GdalType;
+use raster::window::{Window, RasterSize, BufferLayout};
+use raster::strategy::{Interleave, RasterLayout, ReadStrategy};
+use std::mem;
+use raster::metadata::{Exif, Rpc, Imd};
+use spatial_ref::SpatialRef;
+use vector::Envelope;
 
 pub type GeoTransform = [c_double; 6];
 
+/// Apply a geo-transform to pixel/line coordinates, returning the
+/// corresponding georeferenced coordinates. Using the inverse transform
+/// goes the other way.
+pub fn apply_geo_transform(tr: &GeoTransform, x: f64, y: f64) -> (f64, f64) {
+    return (
+        tr[0] + x * tr[1] + y * tr[2],
+        tr[3] + x * tr[4] + y * tr[5],
+    );
+}
+
+/// Invert a geo-transform, e.g. to go from georeferenced coordinates to
+/// pixel/line. Returns `None` if the transform isn't invertible.
+pub fn invert_geo_transform(tr: &GeoTransform) -> Option<GeoTransform> {
+    let mut inverse = GeoTransform::default();
+    let rv = unsafe { gdal::GDALInvGeoTransform(tr.as_ptr(), inverse.as_mut_ptr()) };
+    return match rv {
+        0 => None,
+        _ => Some(inverse),
+    };
+}
+
+/// Read the geo-transform from the world file of the image at `path`,
+/// e.g. `image.pgw` for `image.png`. `extension` picks another world file
+/// extension, e.g. `"wld"`; without it GDAL tries the extension derived
+/// from the image, then `.wld`. Returns `None` if there is no readable
+/// world file.
+pub fn read_world_file(path: &Path, extension: Option<&str>) -> Option<GeoTransform> {
+    let c_filename = _cstring(path.to_str().unwrap()).ok()?;
+    let c_extension = match extension {
+        Some(extension) => Some(_cstring(extension).ok()?),
+        None => None,
+    };
+    let mut tr = GeoTransform::default();
+    let rv = unsafe { gdal::GDALReadWorldFile(
+        c_filename.as_ptr(),
+        c_extension.as_ref().map_or(null(), |e| e.as_ptr()),
+        tr.as_mut_ptr(),
+    ) };
+    return match rv {
+        0 => None,
+        _ => Some(tr),
+    };
+}
+
+/// Write `tr` to the world file of the image at `path`, replacing its
+/// extension by `extension`, e.g. `"pgw"` for a PNG.
+pub fn write_world_file(path: &Path, extension: &str, tr: &GeoTransform) -> Result<(), GdalError> {
+    let c_filename = _cstring(path.to_str().unwrap())?;
+    let c_extension = _cstring(extension)?;
+    let rv = unsafe { gdal::GDALWriteWorldFile(c_filename.as_ptr(), c_extension.as_ptr(), tr.as_ptr()) };
+    return match rv {
+        0 => Err(GdalError::CallFailed{desc: "GDALWriteWorldFile failed"}),
+        _ => Ok(()),
+    };
+}
+
+/// Where `Dataset::build_overviews` stores the pyramid.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum OverviewLocation {
+    /// Inside the dataset file itself, for formats that support it (e.g.
+    /// GeoTIFF). Requires a dataset opened with `Dataset::open_update`.
+    Internal,
+    /// In a separate `.ovr` file next to the dataset.
+    External,
+}
+
 pub struct Dataset {
     c_dataset: *const c_void,
+    open_flags: OpenFlags,
 }
 
 
@@ -25,16 +100,85 @@ impl Dataset {
     pub fn open(path: &Path) -> Option<Dataset> {
         _register_drivers();
         let filename = path.to_str().unwrap();
-        let c_filename = CString::new(filename.as_bytes()).unwrap();
+        let c_filename = _cstring(filename).ok()?;
         let c_dataset = unsafe { gdal::GDALOpen(c_filename.as_ptr(), GDALAccess::GA_ReadOnly) };
         return match c_dataset.is_null() {
             true  => None,
-            false => Some(Dataset{c_dataset: c_dataset}),
+            false => Some(Dataset{c_dataset: c_dataset, open_flags: OpenFlags::RASTER}),
+        };
+    }
+
+    /// Open the dataset at `path` for reading and writing.
+    pub fn open_update(path: &Path) -> Option<Dataset> {
+        _register_drivers();
+        let filename = path.to_str().unwrap();
+        let c_filename = _cstring(filename).ok()?;
+        let c_dataset = unsafe { gdal::GDALOpen(c_filename.as_ptr(), GDALAccess::GA_Update) };
+        return match c_dataset.is_null() {
+            true  => None,
+            false => Some(Dataset{c_dataset: c_dataset, open_flags: OpenFlags::RASTER | OpenFlags::UPDATE}),
         };
     }
 
+    /// Open the dataset at `path` through GDAL's shared dataset list.
+    /// Opening the same file again in the same thread hands back the
+    /// existing handle (reference counted), instead of parsing it again.
+    /// The handle is only closed once every `Dataset` using it is dropped.
+    pub fn open_shared(path: &Path) -> Option<Dataset> {
+        _register_drivers();
+        let filename = path.to_str().unwrap();
+        let c_filename = _cstring(filename).ok()?;
+        let c_dataset = unsafe { gdal::GDALOpenShared(c_filename.as_ptr(), GDALAccess::GA_ReadOnly) };
+        return match c_dataset.is_null() {
+            true  => None,
+            false => Some(Dataset{c_dataset: c_dataset, open_flags: OpenFlags::RASTER | OpenFlags::SHARED}),
+        };
+    }
+
+    /// Open the dataset at `path` with `flags`, e.g. `OpenFlags::UPDATE`
+    /// or `OpenFlags::SHARED`, passing driver specific open options as
+    /// `NAME=VALUE` strings. `OpenFlags::RASTER` is always added.
+    pub fn open_ex(path: &Path, flags: OpenFlags, options: &[String]) -> Option<Dataset> {
+        _register_drivers();
+        let filename = path.to_str().unwrap();
+        let c_filename = _cstring(filename).ok()?;
+        let c_options = CslStringList::new(options).ok()?;
+        let flags = flags | OpenFlags::RASTER;
+        let c_dataset = unsafe { gdal::GDALOpenEx(
+            c_filename.as_ptr(),
+            flags.bits(),
+            null(),
+            c_options.as_ptr(),
+            null(),
+        ) };
+        return match c_dataset.is_null() {
+            true  => None,
+            false => Some(Dataset{c_dataset: c_dataset, open_flags: flags}),
+        };
+    }
+
+    /// Open the dataset at `path`, overriding `GDAL_PAM_ENABLED` for this
+    /// dataset only. With `pam_enabled == false` GDAL neither reads nor
+    /// writes `.aux.xml` sidecar files, which is needed when the data lives
+    /// on a read-only mount. With `true` PAM is used even if it's disabled
+    /// globally.
+    pub fn open_with_pam(path: &Path, pam_enabled: bool) -> Option<Dataset> {
+        let value = match pam_enabled {
+            true  => "YES",
+            false => "NO",
+        };
+        return config::with_thread_local_config_option("GDAL_PAM_ENABLED", value, || Dataset::open(path)).ok()?;
+    }
+
+    /// Wrap a dataset that wasn't opened from a file, e.g. one created by
+    /// a driver or a VRT. Its open flags are `RASTER`, plus `UPDATE` if
+    /// GDAL allows writing to it.
     pub unsafe fn _with_c_ptr(c_dataset: *const c_void) -> Dataset {
-        return Dataset{c_dataset: c_dataset};
+        let open_flags = match gdal::GDALGetAccess(c_dataset) == GDALAccess::GA_Update as c_int {
+            true  => OpenFlags::RASTER | OpenFlags::UPDATE,
+            false => OpenFlags::RASTER,
+        };
+        return Dataset{c_dataset: c_dataset, open_flags: open_flags};
     }
 
     pub unsafe fn _c_ptr(&self) -> *const c_void {
@@ -54,18 +198,154 @@ impl Dataset {
         };
     }
 
+    /// Get band number `band_index`, starting from 1.
+    pub fn rasterband<'a>(&'a self, band_index: isize) -> Option<RasterBand<'a>> {
+        let c_band = unsafe { gdal::GDALGetRasterBand(self.c_dataset, band_index as c_int) };
+        return match c_band.is_null() {
+            true  => None,
+            false => Some(unsafe { RasterBand::_with_c_ptr(c_band, self) }),
+        };
+    }
+
+    /// An in-memory VRT exposing only the bands `band_indexes` of this
+    /// dataset, in that order, so APIs taking a `Dataset` can work on a
+    /// subset of bands without copying pixels. Band `i` of the view is band
+    /// `band_indexes[i - 1]` of this dataset; indexes may repeat.
+    ///
+    /// The view reads through the open bands of this dataset, so it works
+    /// for any dataset, MEM datasets included, and can't outlive it.
+    pub fn band_subset_view(&self, band_indexes: &[isize]) -> Result<BandSubsetView<'_>, GdalError> {
+        let mut bands = Vec::with_capacity(band_indexes.len());
+        for &band_index in band_indexes {
+            match self.rasterband(band_index) {
+                Some(band) => bands.push(band),
+                None => return Err(GdalError::CallFailed{desc: "band index out of range"}),
+            }
+        }
+        // the view borrows this dataset, so the bands outlive it
+        let vrt = unsafe { vrt::_stack_bands_in_memory(&bands.iter().collect::<Vec<_>>()) }?;
+        return Ok(unsafe { BandSubsetView::_with_dataset(vrt) });
+    }
+
+    /// Get the description of this dataset, which for datasets opened
+    /// from a file is usually the file name.
+    pub fn description(&self) -> String {
+        let rv = unsafe { gdal::GDALGetDescription(self.c_dataset) };
+        return _string(rv);
+    }
+
+    pub fn set_description(&self, description: &str) -> Result<(), GdalError> {
+        let c_description = _cstring(description)?;
+        unsafe { gdal::GDALSetDescription(self.c_dataset, c_description.as_ptr()) };
+        return Ok(());
+    }
+
+    /// Get the raw `KEY=VALUE` metadata entries of `domain`. Use `""` for
+    /// the default domain. XML domains (`xml:*`) hold a single document.
+    pub fn metadata(&self, domain: &str) -> Vec<String> {
+        let c_domain = match _cstring(domain) {
+            Ok(c_domain) => c_domain,
+            Err(_) => return vec!(),
+        };
+        let rv = unsafe { gdal::GDALGetMetadata(self.c_dataset, c_domain.as_ptr()) };
+        // the list is owned by the dataset, don't free it
+        return _string_array(rv);
+    }
+
+    pub fn metadata_item(&self, key: &str, domain: &str) -> Option<String> {
+        let c_key = _cstring(key).ok()?;
+        let c_domain = _cstring(domain).ok()?;
+        let rv = unsafe { gdal::GDALGetMetadataItem(self.c_dataset, c_key.as_ptr(), c_domain.as_ptr()) };
+        return match rv.is_null() {
+            true  => None,
+            false => Some(_string(rv)),
+        };
+    }
+
+    pub fn set_metadata_item(&self, key: &str, value: &str, domain: &str) -> Result<(), GdalError> {
+        let c_key = _cstring(key)?;
+        let c_value = _cstring(value)?;
+        let c_domain = _cstring(domain)?;
+        let rv = unsafe {
+            gdal::GDALSetMetadataItem(self.c_dataset, c_key.as_ptr(), c_value.as_ptr(), c_domain.as_ptr())
+        } as isize;
+        return match rv {
+            0 => Ok(()),
+            _ => Err(GdalError::CallFailed{desc: "GDALSetMetadataItem failed"}),
+        };
+    }
+
+    /// EXIF tags found in the default metadata domain.
+    pub fn exif(&self) -> Exif {
+        return Exif::from_metadata(&self.metadata(""));
+    }
+
+    /// The XMP packet, if any.
+    pub fn xmp(&self) -> Option<String> {
+        return self.metadata("xml:XMP").into_iter().next();
+    }
+
+    /// Rational polynomial coefficients, if the dataset has them.
+    pub fn rpc(&self) -> Option<Rpc> {
+        return Rpc::from_metadata(&self.metadata("RPC"));
+    }
+
+    /// DigitalGlobe `.IMD` metadata, if any.
+    pub fn imd(&self) -> Option<Imd> {
+        let entries = self.metadata("IMD");
+        return match entries.is_empty() {
+            true  => None,
+            false => Some(Imd::from_metadata(&entries)),
+        };
+    }
+
+    /// List the files making up this dataset: the main file plus any
+    /// sidecars such as world files, `.aux.xml` or overview files.
+    pub fn file_list(&self) -> Vec<String> {
+        let c_file_list = unsafe { gdal::GDALGetFileList(self.c_dataset) };
+        let file_list = _string_array(c_file_list);
+        unsafe { gdal::CSLDestroy(c_file_list) };
+        return file_list;
+    }
+
     pub fn count(&self) -> isize {
         return unsafe { gdal::GDALGetRasterCount(self.c_dataset) } as isize;
     }
 
+    /// The flags this dataset was opened with, as passed to GDAL. A raster
+    /// `Dataset` is always opened for its bands; datasets created by a
+    /// driver are writable.
+    pub fn open_flags(&self) -> OpenFlags {
+        return self.open_flags;
+    }
+
+    /// Whether this dataset can be written to.
+    pub fn is_update(&self) -> bool {
+        return unsafe { gdal::GDALGetAccess(self.c_dataset) } == GDALAccess::GA_Update as c_int;
+    }
+
+    /// Whether this dataset was opened for its raster bands.
+    pub fn is_raster(&self) -> bool {
+        return self.open_flags().contains(OpenFlags::RASTER);
+    }
+
+    /// Whether this dataset was opened for its vector layers.
+    pub fn is_vector(&self) -> bool {
+        return self.open_flags().contains(OpenFlags::VECTOR);
+    }
+
     pub fn projection(&self) -> String {
         let rv = unsafe { gdal::GDALGetProjectionRef(self.c_dataset) };
         return _string(rv);
     }
 
-    pub fn set_projection(&self, projection: &str) {
-        let c_projection = CString::new(projection.as_bytes()).unwrap();
-        unsafe { gdal::GDALSetProjection(self.c_dataset, c_projection.as_ptr()) };
+    pub fn set_projection(&self, projection: &str) -> Result<(), GdalError> {
+        let c_projection = _cstring(projection)?;
+        let rv = unsafe { gdal::GDALSetProjection(self.c_dataset, c_projection.as_ptr()) };
+        return match rv {
+            0 => Ok(()),
+            _ => Err(GdalError::CallFailed{desc: "GDALSetProjection failed"}),
+        };
     }
 
     pub fn set_geo_transform(&self, tr: &GeoTransform) {
@@ -76,6 +356,40 @@ impl Dataset {
         assert!(rv == 0);
     }
 
+    /// Same as `set_geo_transform`, but returns an error instead of
+    /// panicking when the driver refuses the geo-transform, e.g. for a
+    /// read-only dataset.
+    pub fn set_geo_transform_checked(&self, tr: &GeoTransform) -> Result<(), GdalError> {
+        let rv = unsafe { gdal::GDALSetGeoTransform(self.c_dataset, tr.as_ptr()) };
+        return match rv {
+            0 => Ok(()),
+            _ => Err(GdalError::CallFailed{desc: "GDALSetGeoTransform failed"}),
+        };
+    }
+
+    /// Georeference this dataset with the world file of the image at
+    /// `path`, see `read_world_file`, e.g. for a PNG opened from memory.
+    /// Returns the geo-transform that was set. Fails if there's no world
+    /// file or the dataset doesn't accept a geo-transform.
+    pub fn load_world_file(&self, path: &Path, extension: Option<&str>) -> Result<GeoTransform, GdalError> {
+        let tr = match read_world_file(path, extension) {
+            Some(tr) => tr,
+            None => return Err(GdalError::CallFailed{desc: "no readable world file"}),
+        };
+        self.set_geo_transform_checked(&tr)?;
+        return Ok(tr);
+    }
+
+    /// Write the geo-transform of this dataset to the world file of the
+    /// image at `path`, see `write_world_file`, e.g. next to a PNG that
+    /// can't store it.
+    pub fn save_world_file(&self, path: &Path, extension: &str) -> Result<(), GdalError> {
+        return match self.geo_transform() {
+            Some(tr) => write_world_file(path, extension, &tr),
+            None => Err(GdalError::CallFailed{desc: "dataset has no geo-transform"}),
+        };
+    }
+
     pub fn geo_transform(&self) -> Option<GeoTransform> {
         let mut tr = GeoTransform::default();
         let rv = unsafe {
@@ -92,148 +406,338 @@ impl Dataset {
         Some(tr)
     }
 
+    /// Whether this dataset has a geo-transform, other than the identity
+    /// GDAL reports for plain images.
+    pub fn is_georeferenced(&self) -> bool {
+        return match self.geo_transform() {
+            Some(tr) => tr != [0., 1., 0., 0., 0., 1.],
+            None => false,
+        };
+    }
+
+    /// Whether rows run north to south and columns west to east, without
+    /// rotation, so a pixel is `(tr[1], -tr[5])` wide and high. `false`
+    /// without a geo-transform.
+    pub fn is_north_up(&self) -> bool {
+        return match self.geo_transform() {
+            Some(tr) => tr[2] == 0. && tr[4] == 0. && tr[1] > 0. && tr[5] < 0.,
+            None => false,
+        };
+    }
+
+    /// Width and height of a pixel in georeferenced units, always
+    /// positive, also for rotated rasters.
+    pub fn pixel_size(&self) -> Option<(f64, f64)> {
+        let tr = self.geo_transform()?;
+        return Some((tr[1].hypot(tr[4]), tr[2].hypot(tr[5])));
+    }
+
+    /// The envelope of the four corners of this dataset in georeferenced
+    /// coordinates.
+    pub fn bounds(&self) -> Option<Envelope> {
+        let tr = self.geo_transform()?;
+        let (size_x, size_y) = self.size();
+        let corners = [(0., 0.), (size_x as f64, 0.), (0., size_y as f64), (size_x as f64, size_y as f64)];
+        let points: Vec<(f64, f64)> = corners.iter().map(|&(x, y)| apply_geo_transform(&tr, x, y)).collect();
+        let fold = |f: fn(f64, f64) -> f64, init: f64, coord: fn(&(f64, f64)) -> f64| {
+            points.iter().map(coord).fold(init, f)
+        };
+        return Some(Envelope::new(
+            fold(f64::min, f64::INFINITY, |p| p.0),
+            fold(f64::max, f64::NEG_INFINITY, |p| p.0),
+            fold(f64::min, f64::INFINITY, |p| p.1),
+            fold(f64::max, f64::NEG_INFINITY, |p| p.1),
+        ));
+    }
+
+    /// A virtual dataset showing this one reprojected to `dst_srs`. Pixels
+    /// are warped with `resampling` only when they're read, e.g. for a tile
+    /// server serving several projections from one source. The size and
+    /// geo-transform are chosen by GDAL to keep the resolution.
+    /// `max_error` is the error in pixels allowed when approximating the
+    /// transformation, 0 for exact computations; `gdalwarp` uses 0.125.
+    /// Fails if this dataset has no projection.
+    pub fn warped_view(
+        &self,
+        dst_srs: &SpatialRef,
+        resampling: GDALResampleAlg,
+        max_error: f64,
+    ) -> Result<WarpedView<'_>, GdalError> {
+        let c_dst_wkt = _cstring(&dst_srs.to_wkt()?)?;
+        let c_dataset = unsafe { gdal::GDALAutoCreateWarpedVRT(
+            self.c_dataset,
+            null(),
+            c_dst_wkt.as_ptr(),
+            resampling,
+            max_error as c_double,
+            null(),
+        ) };
+        return match c_dataset.is_null() {
+            true  => Err(GdalError::CallFailed{desc: "GDALAutoCreateWarpedVRT failed"}),
+            false => Ok(unsafe { WarpedView::_with_c_ptr(c_dataset) }),
+        };
+    }
+
+    /// Build overviews for all bands.
+    /// # Arguments
+    /// * resampling - the resampling method, e.g. `"NEAREST"` or `"AVERAGE"`
+    /// * levels - the decimation factors, e.g. `&[2, 4, 8]`
+    /// * location - whether to write the overviews into the dataset or to
+    ///   a separate `.ovr` file
+    pub fn build_overviews(
+        &self,
+        resampling: &str,
+        levels: &[i32],
+        location: OverviewLocation,
+    ) -> Result<(), GdalError> {
+        match location {
+            OverviewLocation::Internal if !self.is_update() => {
+                return Err(GdalError::CallFailed{desc: "internal overviews need a dataset opened for update"});
+            },
+            OverviewLocation::Internal => self._build_overviews(resampling, levels),
+            OverviewLocation::External => {
+                // GeoTIFF writes internal overviews by default when the
+                // file is writable, unless told otherwise
+                config::with_thread_local_config_option("TIFF_USE_OVR", "YES", || {
+                    self._build_overviews(resampling, levels)
+                })?
+            },
+        }
+    }
+
+    /// Remove all overviews of the dataset.
+    pub fn clean_overviews(&self) -> Result<(), GdalError> {
+        return self._build_overviews("NONE", &[]);
+    }
+
+    fn _build_overviews(&self, resampling: &str, levels: &[i32]) -> Result<(), GdalError> {
+        use std::ptr::null;
+        let c_resampling = _cstring(resampling)?;
+        let rv = unsafe {
+            gdal::GDALBuildOverviews(
+                self.c_dataset,
+                c_resampling.as_ptr(),
+                levels.len() as c_int,
+                levels.as_ptr(),
+                0,
+                null(),
+                null(),
+                null()
+            )
+        } as isize;
+        return match rv {
+            0 => Ok(()),
+            _ => Err(GdalError::CallFailed{desc: "GDALBuildOverviews failed"}),
+        };
+    }
+
     pub fn create_copy(
         &self,
         driver: Driver,
         filename: &str
+    ) -> Option<Dataset> {
+        self.create_copy_with_options(driver, filename, &[])
+    }
+
+    /// Copy the dataset, passing driver specific creation options. See
+    /// `GeoTiffOptions` for a typed builder.
+    pub fn create_copy_with_options(
+        &self,
+        driver: Driver,
+        filename: &str,
+        options: &[String],
     ) -> Option<Dataset> {
         use std::ptr::null;
-        let c_filename = CString::new(filename.as_bytes()).unwrap();
+        let c_filename = _cstring(filename).ok()?;
+        let c_options = CslStringList::new(options).ok()?;
         let c_dataset = unsafe { gdal::GDALCreateCopy(
                 driver._c_ptr(),
                 c_filename.as_ptr(),
                 self.c_dataset,
                 0,
-                null(),
+                c_options.as_ptr(),
                 null(),
                 null()
             ) };
         return match c_dataset.is_null() {
             true  => None,
-            false => Some(Dataset{c_dataset: c_dataset}),
+            false => Some(unsafe { Dataset::_with_c_ptr(c_dataset) }),
         };
     }
 
     /// Read a 'Buffer<u8>' from a 'Dataset'.
     /// # Arguments
     /// * band_index - the band_index
-    /// * window - the window position from top left
-    /// * window_size - the window size (GDAL will interpolate data if window_size != buffer_size)
-    /// * buffer_size - the desired size of the 'Buffer'
-    pub fn read_raster(&self,
-        band_index: isize,
-        window: (isize, isize),
-        window_size: (usize, usize),
-        size: (usize, usize)
-        ) -> ByteBuffer
-    {
-        self.read_raster_as::<u8>(
-            band_index,
-            window,
-            window_size,
-            size
-        )
+    /// * window - the window to read
+    /// * size - the desired size of the 'Buffer' (GDAL will interpolate data if it differs from the window size)
+    pub fn read_raster(&self, band_index: isize, window: Window, size: RasterSize) -> Result<ByteBuffer, GdalError> {
+        return self.read_raster_as::<u8>(band_index, window, size);
     }
 
     /// Read a full 'Dataset' as 'Buffer<T>'.
     /// # Arguments
     /// * band_index - the band_index
-    pub fn read_full_raster_as<T: Copy + GdalType>(
-        &self,
-        band_index: isize,
-    ) -> Buffer<T>
-    {
-        let size_x;
-        let size_y;
-
-        unsafe{
-            size_x = gdal::GDALGetRasterXSize(self.c_dataset) as usize;
-            size_y = gdal::GDALGetRasterYSize(self.c_dataset) as usize;
-        }
-
-        self.read_raster_as::<T>(
-            band_index,
-            (0, 0),
-            (size_x, size_y),
-            (size_y, size_y)
-        )
+    pub fn read_full_raster_as<T: Copy + GdalType>(&self, band_index: isize) -> Result<Buffer<T>, GdalError> {
+        let (size_x, size_y) = self.size();
+        let size = RasterSize::new(size_x as usize, size_y as usize);
+        return self.read_raster_as::<T>(band_index, Window::full(size), size);
     }
 
     /// Read a 'Buffer<T>' from a 'Dataset'. T implements 'GdalType'
     /// # Arguments
     /// * band_index - the band_index
-    /// * window - the window position from top left
-    /// * window_size - the window size (GDAL will interpolate data if window_size != buffer_size)
-    /// * buffer_size - the desired size of the 'Buffer'
+    /// * window - the window to read
+    /// * size - the desired size of the 'Buffer' (GDAL will interpolate data if it differs from the window size)
+    ///
+    /// Values are converted to `T` like `RasterBand::read_as` does,
+    /// saturating those that don't fit. Fails if there's no such band, or
+    /// for the same reasons as `RasterBand::read_as`.
     pub fn read_raster_as<T: Copy + GdalType>(
         &self,
         band_index: isize,
-        window: (isize, isize),
-        window_size: (usize, usize),
-        size: (usize, usize),
-    ) -> Buffer<T>
+        window: Window,
+        size: RasterSize,
+    ) -> Result<Buffer<T>, GdalError>
     {
-        let pixels = (size.0 * size.1) as usize;
-        let mut data: Vec<T> = Vec::with_capacity(pixels);
-        //let no_data:
-        unsafe {
-            let c_band = gdal::GDALGetRasterBand(self.c_dataset, band_index as c_int);
-            let rv = gdal::GDALRasterIO(
-                c_band,
-                GDALRWFlag::GF_Read,
-                window.0 as c_int,
-                window.1 as c_int,
-                window_size.0 as c_int,
-                window_size.1 as c_int,
-                data.as_mut_ptr() as *const c_void,
-                size.0 as c_int,
-                size.1 as c_int,
-                T::gdal_type(),
-                0,
-                0
-            ) as isize;
-            assert!(rv == 0);
-            data.set_len(pixels);
-        };
-        Buffer{
-            size: size,
-            data: data,
-        }
+        return self._band(band_index)?.read_as::<T>(window, size);
     }
 
     /// Write a 'Buffer<T>' into a 'Dataset'.
     /// # Arguments
     /// * band_index - the band_index
-    /// * window - the window position from top left
-    /// * window_size - the window size (GDAL will interpolate data if window_size != Buffer.size)
+    /// * window - the window to write (GDAL will interpolate data if its size != Buffer.size)
+    ///
+    /// Fails if there's no such band, or for the same reasons as
+    /// `RasterBand::write_window`.
     pub fn write_raster<T: GdalType+Copy>(
         &self,
         band_index: isize,
-        window: (isize, isize),
-        window_size: (usize, usize),
+        window: Window,
         buffer: Buffer<T>
-    ) {
-        assert_eq!(buffer.data.len(), buffer.size.0 * buffer.size.1);
-        unsafe {
-            let c_band = gdal::GDALGetRasterBand(self.c_dataset, band_index as c_int);
-            let rv = gdal::GDALRasterIO(
-                c_band,
-                GDALRWFlag::GF_Write,
-                window.0 as c_int,
-                window.1 as c_int,
-                window_size.0 as c_int,
-                window_size.1 as c_int,
-                buffer.data.as_ptr() as *const c_void,
-                buffer.size.0 as c_int,
-                buffer.size.1 as c_int,
-                T::gdal_type(),
-                0,
-                0
-            ) as isize;
-            assert!(rv == 0);
+    ) -> Result<(), GdalError>
+    {
+        return self._band(band_index)?.write_window(window, &buffer);
+    }
+
+    fn _band(&self, band_index: isize) -> Result<RasterBand<'_>, GdalError> {
+        return match self.rasterband(band_index) {
+            Some(band) => Ok(band),
+            None => Err(GdalError::CallFailed{desc: "band index out of range"}),
         };
     }
 
+    /// Read `window` of `bands` (1-based indices) in one call into a
+    /// caller-owned `buffer` of `size` pixels laid out as `layout`, e.g.
+    /// `BufferLayout::pixel_interleaved` for an RGBA texture. Elements the
+    /// layout skips are left alone.
+    pub fn read_into<T: Copy + GdalType>(
+        &self,
+        bands: &[isize],
+        window: Window,
+        size: RasterSize,
+        buffer: &mut [T],
+        layout: BufferLayout,
+    ) -> Result<(), GdalError> {
+        self._check_layout(bands, window, size, buffer.len(), layout)?;
+        return self._raster_io::<T>(GDALRWFlag::GF_Read, bands, window, size, buffer.as_mut_ptr() as *const c_void, layout);
+    }
+
+    /// Write `window` of `bands` from a caller-owned `buffer` of `size`
+    /// pixels laid out as `layout`, the counterpart of `read_into`.
+    pub fn write_from<T: Copy + GdalType>(
+        &self,
+        bands: &[isize],
+        window: Window,
+        size: RasterSize,
+        buffer: &[T],
+        layout: BufferLayout,
+    ) -> Result<(), GdalError> {
+        self._check_layout(bands, window, size, buffer.len(), layout)?;
+        return self._raster_io::<T>(GDALRWFlag::GF_Write, bands, window, size, buffer.as_ptr() as *const c_void, layout);
+    }
+
+    fn _check_layout(&self, bands: &[isize], window: Window, size: RasterSize, len: usize, layout: BufferLayout) -> Result<(), GdalError> {
+        let (size_x, size_y) = self.size();
+        window.validate(RasterSize::new(size_x as usize, size_y as usize))?;
+        if size.pixels()? == 0 || bands.is_empty() {
+            return Err(GdalError::CallFailed{desc: "empty buffer"});
+        }
+        if bands.iter().any(|&band| band < 1 || band > self.count()) {
+            return Err(GdalError::CallFailed{desc: "band index out of range"});
+        }
+        if len < layout.required_len(size, bands.len())? {
+            return Err(GdalError::CallFailed{desc: "buffer too small for its layout"});
+        }
+        return Ok(());
+    }
+
+    fn _raster_io<T: GdalType>(
+        &self,
+        rw_flag: GDALRWFlag,
+        bands: &[isize],
+        window: Window,
+        size: RasterSize,
+        data: *const c_void,
+        layout: BufferLayout,
+    ) -> Result<(), GdalError> {
+        let element_size = mem::size_of::<T>();
+        let band_map: Vec<c_int> = bands.iter().map(|&band| band as c_int).collect();
+        let rv = unsafe { gdal::GDALDatasetRasterIO(
+            self.c_dataset,
+            rw_flag,
+            window.x_off as c_int,
+            window.y_off as c_int,
+            window.x_size as c_int,
+            window.y_size as c_int,
+            data,
+            size.width as c_int,
+            size.height as c_int,
+            T::gdal_type(),
+            band_map.len() as c_int,
+            band_map.as_ptr(),
+            (layout.pixel_space * element_size) as c_int,
+            (layout.line_space * element_size) as c_int,
+            (layout.band_space * element_size) as c_int
+        ) };
+        return match rv {
+            0 => Ok(()),
+            _ => Err(GdalError::CallFailed{desc: "GDALDatasetRasterIO failed"}),
+        };
+    }
+
+
+    /// The storage layout of the raster, `None` if it has no bands.
+    pub fn layout(&self) -> Option<RasterLayout> {
+        let band = self.rasterband(1)?;
+        let size = band.size();
+        let block_size = band.block_size();
+        let interleave = match self.metadata_item("INTERLEAVE", "IMAGE_STRUCTURE") {
+            Some(ref interleave) if interleave == "PIXEL" => Interleave::Pixel,
+            Some(ref interleave) if interleave == "LINE" => Interleave::Line,
+            Some(ref interleave) if interleave == "BAND" => Interleave::Band,
+            _ => Interleave::Unknown,
+        };
+        return Some(RasterLayout{
+            size: size,
+            band_count: self.count() as usize,
+            band_type: band.band_type(),
+            block_size: block_size,
+            // strips span the raster width. Tiles may be as wide as the
+            // raster, but then they're more than a line high and don't
+            // cover it in one block
+            tiled: (block_size.1 > 1 && block_size.1 != size.1) || block_size.0 != size.0,
+            compression: self.metadata_item("COMPRESSION", "IMAGE_STRUCTURE"),
+            interleave: interleave,
+            overview_count: band.overview_count() as usize,
+        });
+    }
+
+    /// Recommend windows for reading the whole raster with at most about
+    /// `max_bytes` per window, based on its `layout`; see
+    /// `ReadStrategy::for_layout`. `None` if the raster has no bands.
+    pub fn read_strategy(&self, max_bytes: usize) -> Option<ReadStrategy> {
+        return self.layout().map(|layout| ReadStrategy::for_layout(&layout, max_bytes));
+    }
 
     pub fn get_band_type(&self, band_index: isize) -> Option<GDALDataType> {
 
@@ -1,9 +1,9 @@
 use libc::{c_int, c_void};
-use std::ffi::CString;
 use std::sync::{Once, ONCE_INIT};
-use utils::_string;
+use utils::{_cstring, _string, CslStringList};
 use raster::{gdal, Dataset};
 use raster::types::GdalType;
+use raster::gdal_enums::GDALDataType;
 
 
 static START: Once = ONCE_INIT;
@@ -28,7 +28,7 @@ pub struct Driver {
 impl Driver {
     pub fn get(name: &str) -> Option<Driver> {
         _register_drivers();
-        let c_name = CString::new(name.as_bytes()).unwrap();
+        let c_name = _cstring(name).ok()?;
         let c_driver = unsafe { gdal::GDALGetDriverByName(c_name.as_ptr()) };
         return match c_driver.is_null() {
             true  => None,
@@ -76,16 +76,56 @@ impl Driver {
         size_y: isize,
         bands: isize,
     ) -> Option<Dataset> {
-        use std::ptr::null;
-        let c_filename = CString::new(filename.as_bytes()).unwrap();
+        self.create_with_options::<T>(
+            filename,
+            size_x,
+            size_y,
+            bands,
+            &[],
+        )
+    }
+
+    /// Create a dataset, passing driver specific creation options such as
+    /// `COMPRESS=DEFLATE`. See `GeoTiffOptions` for a typed builder.
+    pub fn create_with_options<T: GdalType>(
+        &self,
+        filename: &str,
+        size_x: isize,
+        size_y: isize,
+        bands: isize,
+        options: &[String],
+    ) -> Option<Dataset> {
+        self.create_with_data_type(
+            filename,
+            size_x,
+            size_y,
+            bands,
+            T::gdal_type(),
+            options,
+        )
+    }
+
+    /// Like `create_with_options`, for when the band type is only known
+    /// at run time, e.g. when copying the layout of another dataset.
+    pub fn create_with_data_type(
+        &self,
+        filename: &str,
+        size_x: isize,
+        size_y: isize,
+        bands: isize,
+        band_type: GDALDataType,
+        options: &[String],
+    ) -> Option<Dataset> {
+        let c_filename = _cstring(filename).ok()?;
+        let c_options = CslStringList::new(options).ok()?;
         let c_dataset = unsafe { gdal::GDALCreate(
                 self.c_driver,
                 c_filename.as_ptr(),
                 size_x as c_int,
                 size_y as c_int,
                 bands as c_int,
-                T::gdal_type(),
-                null()
+                band_type,
+                c_options.as_ptr()
             ) };
         return match c_dataset.is_null() {
             true  => None,
@@ -0,0 +1,131 @@
+//! Focal (neighborhood) statistics
+//!
+//! ```no_run
+//! use std::path::Path;
+//! use gdal::raster::{Dataset, Driver};
+//! use gdal::raster::focal::{focal, Kernel};
+//!
+//! let dataset = Dataset::open(Path::new("dem.tif")).unwrap();
+//! let band = dataset.rasterband(1).unwrap();
+//! let driver = Driver::get("GTiff").unwrap();
+//! // 5x5 moving average
+//! let smoothed = focal(&band, &Kernel::Mean, 2, &driver, "dem_smoothed.tif").unwrap();
+//! ```
+
+use raster::{Buffer, Dataset, Driver, RasterBand, Window, WindowOrder};
+use GdalError;
+
+/// How `focal` combines the valid pixels of a neighborhood.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Kernel {
+    Mean,
+    Sum,
+    Min,
+    Max,
+    /// Weighted sum, with one weight per pixel of the `2 * radius + 1`
+    /// square neighborhood, row by row.
+    Weights(Vec<f64>),
+}
+
+/// Size of the windows `focal` processes at once, before the halo.
+const WINDOW_SIZE: (usize, usize) = (256, 256);
+
+/// Apply `kernel` to the `radius` neighborhood of every pixel of `band`,
+/// writing the result to a new single band Float64 dataset `filename`
+/// created by `driver`, with the geo-transform and projection of `band`.
+///
+/// Pixels masked out by the band (nodata, alpha) and neighbors beyond the
+/// raster edges are left out. Pixels without any valid neighbor are set to
+/// the nodata value of the output, which is the one of `band` or NaN.
+///
+/// The band is processed window by window, each read with a halo of
+/// `radius` pixels so windows don't see each other's edges.
+pub fn focal(
+    band: &RasterBand,
+    kernel: &Kernel,
+    radius: usize,
+    driver: &Driver,
+    filename: &str,
+) -> Result<Dataset, GdalError> {
+    let side = 2 * radius + 1;
+    if let Kernel::Weights(ref weights) = *kernel {
+        if weights.len() != side * side {
+            return Err(GdalError::CallFailed{desc: "kernel doesn't match the neighborhood size"});
+        }
+    }
+    let (size_x, size_y) = band.size();
+    let output = match driver.create_with_band_type::<f64>(filename, size_x as isize, size_y as isize, 1) {
+        Some(output) => output,
+        None => return Err(GdalError::CallFailed{desc: "failed to create output dataset"}),
+    };
+    let dataset = band.owning_dataset();
+    if let Some(transform) = dataset.geo_transform() {
+        output.set_geo_transform_checked(&transform)?;
+        output.set_projection(&dataset.projection())?;
+    }
+    let no_data = band.no_data_value().unwrap_or(f64::NAN);
+    output.rasterband(1).unwrap().set_no_data_value(no_data)?;
+
+    for (window, window_size) in band.windows(WINDOW_SIZE, WindowOrder::BlockMajor, true) {
+        let (x, y) = (window.0 as usize, window.1 as usize);
+        // window grown by the halo, clipped to the raster
+        let x0 = x.saturating_sub(radius);
+        let y0 = y.saturating_sub(radius);
+        let x1 = (x + window_size.0 + radius).min(size_x);
+        let y1 = (y + window_size.1 + radius).min(size_y);
+        let halo_size = (x1 - x0, y1 - y0);
+        let halo = Window::new(x0, y0, halo_size.0, halo_size.1);
+        let values = band.read_as::<f64>(halo, halo.size())?.data;
+        let valid = band.read_mask(halo, halo.size())?.data;
+
+        let mut data = Vec::with_capacity(window_size.0 * window_size.1);
+        for row in y..y + window_size.1 {
+            for col in x..x + window_size.0 {
+                let mut result = None;
+                for ky in 0..side {
+                    for kx in 0..side {
+                        // neighbor (col + kx - radius, row + ky - radius)
+                        if col + kx < radius || row + ky < radius {
+                            continue;
+                        }
+                        let (nx, ny) = (col + kx - radius, row + ky - radius);
+                        if nx >= x1 || ny >= y1 {
+                            continue;
+                        }
+                        let i = (ny - y0) * halo_size.0 + (nx - x0);
+                        if valid[i] == 0 {
+                            continue;
+                        }
+                        result = Some(combine(kernel, result, values[i], ky * side + kx));
+                    }
+                }
+                data.push(match result {
+                    Some((value, count)) => match *kernel {
+                        Kernel::Mean => value / count as f64,
+                        _ => value,
+                    },
+                    None => no_data,
+                });
+            }
+        }
+        output.write_raster(1, Window::from_tuples(window, window_size), Buffer::new(window_size, data))?;
+    }
+    return Ok(output);
+}
+
+/// Add the neighbor `value` at kernel position `k` to the running
+/// `(value, count)` of a pixel.
+fn combine(kernel: &Kernel, acc: Option<(f64, usize)>, value: f64, k: usize) -> (f64, usize) {
+    let value = match *kernel {
+        Kernel::Weights(ref weights) => value * weights[k],
+        _ => value,
+    };
+    return match acc {
+        None => (value, 1),
+        Some((acc, count)) => match *kernel {
+            Kernel::Min => (acc.min(value), count + 1),
+            Kernel::Max => (acc.max(value), count + 1),
+            _ => (acc + value, count + 1),
+        },
+    };
+}
@@ -1,6 +1,19 @@
-use libc::{c_int, c_char, c_double, c_void};
+use libc::{c_int, c_char, c_double, c_uint, c_void};
 use super::gdal_enums::*;
 
+/// A color table entry, `c1` to `c4` being red, green, blue and alpha for
+/// RGB color tables.
+#[repr(C)]
+#[derive(Clone, Copy, Default)]
+pub struct GDALColorEntry {
+    pub c1: i16,
+    pub c2: i16,
+    pub c3: i16,
+    pub c4: i16,
+}
+
+pub const GPI_RGB: c_int = 1;
+
 #[link(name="gdal")]
 extern {
     pub fn GDALAllRegister();
@@ -26,7 +39,32 @@ extern {
             pProgressData: *const c_void
         ) -> *const c_void;
     pub fn GDALOpen(pszFilename: *const c_char, eAccess: GDALAccess) -> *const c_void;
+    pub fn GDALGetAccess(hDS: *const c_void) -> c_int;
+    pub fn GDALBuildOverviews(
+            hDS: *const c_void,
+            pszResampling: *const c_char,
+            nOverviews: c_int,
+            panOverviewList: *const c_int,
+            nListBands: c_int,
+            panBandList: *const c_int,
+            pfnProgress: *const c_void,
+            pProgressData: *const c_void
+        ) -> c_int;
+    pub fn GDALOpenShared(pszFilename: *const c_char, eAccess: GDALAccess) -> *const c_void;
+    pub fn GDALOpenEx(
+        pszFilename: *const c_char,
+        nOpenFlags: c_uint,
+        papszAllowedDrivers: *const *const c_char,
+        papszOpenOptions: *const *const c_char,
+        papszSiblingFiles: *const *const c_char
+    ) -> *const c_void;
     pub fn GDALClose(hDS: *const c_void);
+    pub fn GDALGetDescription(hObject: *const c_void) -> *const c_char;
+    pub fn GDALSetDescription(hObject: *const c_void, pszNewDesc: *const c_char);
+    pub fn GDALGetMetadata(hObject: *const c_void, pszDomain: *const c_char) -> *const *const c_char;
+    pub fn GDALGetMetadataItem(hObject: *const c_void, pszName: *const c_char, pszDomain: *const c_char) -> *const c_char;
+    pub fn GDALSetMetadataItem(hObject: *const c_void, pszName: *const c_char, pszValue: *const c_char, pszDomain: *const c_char) -> c_int;
+    pub fn GDALGetFileList(hDS: *const c_void) -> *const *const c_char;
     pub fn GDALGetDatasetDriver(hDataset: *const c_void) -> *const c_void;
     pub fn GDALGetRasterXSize(hDataset: *const c_void) -> c_int;
     pub fn GDALGetRasterYSize(hDataset: *const c_void) -> c_int;
@@ -36,7 +74,66 @@ extern {
     pub fn GDALSetProjection(hDS: *const c_void, pszProjection: *const c_char) -> c_int;
     pub fn GDALSetGeoTransform(hDS: *const c_void, padfTransform: *const c_double) -> c_int;
     pub fn GDALGetGeoTransform(hDS: *const c_void, padfTransform: *mut c_double) -> c_int;
+    pub fn GDALInvGeoTransform(padfGeoTransformIn: *const c_double, padfInvGeoTransformOut: *mut c_double) -> c_int;
+    pub fn GDALReadWorldFile(pszBaseFilename: *const c_char, pszExtension: *const c_char, padfGeoTransform: *mut c_double) -> c_int;
+    pub fn GDALWriteWorldFile(pszBaseFilename: *const c_char, pszExtension: *const c_char, padfGeoTransform: *const c_double) -> c_int;
     pub fn GDALGetRasterBand(hDS: *const c_void, nBandId: c_int) -> *const c_void;
+    pub fn GDALGetBandNumber(hBand: *const c_void) -> c_int;
+    pub fn GDALGetDataTypeName(eDataType: c_int) -> *const c_char;
+    pub fn GDALGetDataTypeSize(eDataType: c_int) -> c_int;
+    pub fn GDALGetRasterBandXSize(hBand: *const c_void) -> c_int;
+    pub fn GDALGetRasterBandYSize(hBand: *const c_void) -> c_int;
+    pub fn GDALGetBlockSize(hBand: *const c_void, pnXSize: *mut c_int, pnYSize: *mut c_int);
+    pub fn GDALGetRasterNoDataValue(hBand: *const c_void, pbSuccess: *mut c_int) -> c_double;
+    pub fn GDALSetRasterNoDataValue(hBand: *const c_void, dfNoData: c_double) -> c_int;
+    pub fn GDALGetRasterCategoryNames(hBand: *const c_void) -> *const *const c_char;
+    pub fn GDALSetRasterCategoryNames(hBand: *const c_void, papszNames: *const *const c_char) -> c_int;
+    pub fn GDALGetRasterScale(hBand: *const c_void, pbSuccess: *mut c_int) -> c_double;
+    pub fn GDALSetRasterScale(hBand: *const c_void, dfNewScale: c_double) -> c_int;
+    pub fn GDALGetRasterOffset(hBand: *const c_void, pbSuccess: *mut c_int) -> c_double;
+    pub fn GDALSetRasterOffset(hBand: *const c_void, dfNewOffset: c_double) -> c_int;
+    pub fn GDALGetMaskBand(hBand: *const c_void) -> *const c_void;
+    pub fn GDALGetMaskFlags(hBand: *const c_void) -> c_int;
+    pub fn GDALGetOverviewCount(hBand: *const c_void) -> c_int;
+    pub fn GDALGetOverview(hBand: *const c_void, i: c_int) -> *const c_void;
+    pub fn GDALHasArbitraryOverviews(hBand: *const c_void) -> c_int;
+    pub fn GDALGetRasterColorTable(hBand: *const c_void) -> *const c_void;
+    pub fn GDALSetRasterColorTable(hBand: *const c_void, hCT: *const c_void) -> c_int;
+    pub fn GDALCreateColorTable(eInterp: c_int) -> *const c_void;
+    pub fn GDALDestroyColorTable(hTable: *const c_void);
+    pub fn GDALCloneColorTable(hTable: *const c_void) -> *const c_void;
+    pub fn GDALGetColorEntryCount(hTable: *const c_void) -> c_int;
+    pub fn GDALGetColorEntry(hTable: *const c_void, i: c_int) -> *const GDALColorEntry;
+    pub fn GDALSetColorEntry(hTable: *const c_void, i: c_int, poEntry: *const GDALColorEntry);
+    pub fn GDALComputeMedianCutPCT(
+            hRed: *const c_void,
+            hGreen: *const c_void,
+            hBlue: *const c_void,
+            pfnIncludePixel: *const c_void,
+            nColors: c_int,
+            hColorTable: *const c_void,
+            pfnProgress: *const c_void,
+            pProgressArg: *const c_void
+        ) -> c_int;
+    pub fn GDALDitherRGB2PCT(
+            hRed: *const c_void,
+            hGreen: *const c_void,
+            hBlue: *const c_void,
+            hTarget: *const c_void,
+            hColorTable: *const c_void,
+            pfnProgress: *const c_void,
+            pProgressArg: *const c_void
+        ) -> c_int;
+    pub fn GDALGetVirtualMemAuto(
+            hBand: *const c_void,
+            eRWFlag: GDALRWFlag,
+            pnPixelSpace: *mut c_int,
+            pnLineSpace: *mut i64,
+            papszOptions: *const *const c_char
+        ) -> *const c_void;
+    pub fn CPLVirtualMemGetAddr(ctxt: *const c_void) -> *const c_void;
+    pub fn CPLVirtualMemGetSize(ctxt: *const c_void) -> usize;
+    pub fn CPLVirtualMemFree(ctxt: *const c_void);
     pub fn GDALRasterIO(
             hBand: *const c_void,
             eRWFlag: GDALRWFlag,
@@ -51,6 +148,87 @@ extern {
             nPixelSpace: c_int,
             nLineSpace: c_int
         ) -> c_int;
+    pub fn GDALDatasetRasterIO(
+            hDS: *const c_void,
+            eRWFlag: GDALRWFlag,
+            nXOff: c_int,
+            nYOff: c_int,
+            nXSize: c_int,
+            nYSize: c_int,
+            pData: *const c_void,
+            nBufXSize: c_int,
+            nBufYSize: c_int,
+            eBufType: GDALDataType,
+            nBandCount: c_int,
+            panBandMap: *const c_int,
+            nPixelSpace: c_int,
+            nLineSpace: c_int,
+            nBandSpace: c_int
+        ) -> c_int;
+    pub fn GDALRasterizeGeometries(
+        hDS: *const c_void,
+        nBandCount: c_int,
+        panBandList: *const c_int,
+        nGeomCount: c_int,
+        pahGeometries: *const *const c_void,
+        pfnTransformer: *const c_void,
+        pTransformArg: *const c_void,
+        padfGeomBurnValue: *const c_double,
+        papszOptions: *const *const c_char,
+        pfnProgress: *const c_void,
+        pProgressArg: *const c_void
+    ) -> c_int;
+    pub fn GDALGetRasterStatistics(
+        hBand: *const c_void,
+        bApproxOK: c_int,
+        bForce: c_int,
+        pdfMin: *mut c_double,
+        pdfMax: *mut c_double,
+        pdfMean: *mut c_double,
+        pdfStdDev: *mut c_double
+    ) -> c_int;
+    pub fn GDALComputeRasterStatistics(
+        hBand: *const c_void,
+        bApproxOK: c_int,
+        pdfMin: *mut c_double,
+        pdfMax: *mut c_double,
+        pdfMean: *mut c_double,
+        pdfStdDev: *mut c_double,
+        pfnProgress: *const c_void,
+        pProgressData: *const c_void
+    ) -> c_int;
+    pub fn GDALSetRasterStatistics(
+        hBand: *const c_void,
+        dfMin: c_double,
+        dfMax: c_double,
+        dfMean: c_double,
+        dfStdDev: c_double
+    ) -> c_int;
+    pub fn GDALGridCreate(
+        eAlgorithm: c_int,
+        poOptions: *const c_void,
+        nPoints: c_uint,
+        padfX: *const c_double,
+        padfY: *const c_double,
+        padfZ: *const c_double,
+        dfXMin: c_double,
+        dfXMax: c_double,
+        dfYMin: c_double,
+        dfYMax: c_double,
+        nXSize: c_uint,
+        nYSize: c_uint,
+        eType: GDALDataType,
+        pData: *mut c_void,
+        pfnProgress: *const c_void,
+        pProgressArg: *const c_void
+    ) -> c_int;
+    pub fn ParseAlgorithmAndOptions(
+        pszAlgorithm: *const c_char,
+        peAlgorithm: *mut c_int,
+        ppOptions: *mut *mut c_void
+    ) -> c_int;
+    pub fn VSIFree(ptr: *mut c_void);
+    pub fn CSLDestroy(papszStrList: *const *const c_char);
     pub fn GDALReprojectImage(
         hSrcDS: *const c_void,
         pszSrcWKT: *const c_char,
@@ -63,6 +241,54 @@ extern {
         pProgressArg: *const c_void,
         psOptions: *const c_void
     ) -> c_int;
+    pub fn GDALPolygonize(
+        hSrcBand: *const c_void,
+        hMaskBand: *const c_void,
+        hOutLayer: *const c_void,
+        iPixValField: c_int,
+        papszOptions: *const *const c_char,
+        pfnProgress: *const c_void,
+        pProgressArg: *const c_void
+    ) -> c_int;
+    pub fn GDALFPolygonize(
+        hSrcBand: *const c_void,
+        hMaskBand: *const c_void,
+        hOutLayer: *const c_void,
+        iPixValField: c_int,
+        papszOptions: *const *const c_char,
+        pfnProgress: *const c_void,
+        pProgressArg: *const c_void
+    ) -> c_int;
+    pub fn GDALAutoCreateWarpedVRT(
+        hSrcDS: *const c_void,
+        pszSrcWKT: *const c_char,
+        pszDstWKT: *const c_char,
+        eResampleAlg: GDALResampleAlg,
+        dfMaxError: c_double,
+        psOptions: *const c_void
+    ) -> *const c_void;
+    pub fn GDALCreateWarpOptions() -> *mut c_void;
+    pub fn GDALDestroyWarpOptions(psOptions: *mut c_void);
+    pub fn CSLSetNameValue(papszStrList: *mut *mut c_char, pszName: *const c_char, pszValue: *const c_char) -> *mut *mut c_char;
+    pub fn GDALAddBand(hDS: *const c_void, eType: GDALDataType, papszOptions: *const *const c_char) -> c_int;
+    pub fn VRTCreate(nXSize: c_int, nYSize: c_int) -> *const c_void;
+    pub fn VRTAddSimpleSource(
+        hVRTBand: *const c_void,
+        hSrcBand: *const c_void,
+        nSrcXOff: c_int,
+        nSrcYOff: c_int,
+        nSrcXSize: c_int,
+        nSrcYSize: c_int,
+        nDstXOff: c_int,
+        nDstYOff: c_int,
+        nDstXSize: c_int,
+        nDstYSize: c_int,
+        pszResampling: *const c_char,
+        dfNoDataValue: c_double
+    ) -> c_int;
 }
 
+/// `dfNoDataValue` of `VRTAddSimpleSource` for sources without nodata.
+pub static VRT_NODATA_UNSET: c_double = -1234.56;
+
 pub static REPROJECT_MEMORY_LIMIT: c_double = 0.0;
@@ -23,6 +23,7 @@ impl GDALDataType {
         match gdal_type {
             gdal_type if gdal_type == GDALDataType::GDT_Byte     as c_int => GDALDataType::GDT_Byte,
             gdal_type if gdal_type == GDALDataType::GDT_UInt16   as c_int => GDALDataType::GDT_UInt16,
+            gdal_type if gdal_type == GDALDataType::GDT_Int16    as c_int => GDALDataType::GDT_Int16,
             gdal_type if gdal_type == GDALDataType::GDT_UInt32   as c_int => GDALDataType::GDT_UInt32,
             gdal_type if gdal_type == GDALDataType::GDT_Int32    as c_int => GDALDataType::GDT_Int32,
             gdal_type if gdal_type == GDALDataType::GDT_Float32  as c_int => GDALDataType::GDT_Float32,
@@ -0,0 +1,247 @@
+//! GeoTIFF creation options
+//!
+//! ```no_run
+//! use std::path::Path;
+//! use gdal::raster::{Dataset, Driver};
+//! use gdal::raster::geotiff::{GeoTiffOptions, Compression, Predictor};
+//!
+//! let options = GeoTiffOptions::new()
+//!     .compression(Compression::Deflate)
+//!     .predictor(Predictor::Horizontal)
+//!     .tiled(256, 256);
+//! let dataset = Dataset::open(Path::new("fixtures/tinymarble.png")).unwrap();
+//! let driver = Driver::get("GTiff").unwrap();
+//! dataset.create_copy_with_options(driver, "/tmp/tinymarble.tif", &options.options().unwrap());
+//! ```
+
+use config::NumThreads;
+use GdalError;
+
+/// Value of the `COMPRESS` creation option.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Compression {
+    None,
+    Lzw,
+    Deflate,
+    PackBits,
+    Jpeg,
+    Lzma,
+    Zstd,
+    Webp,
+}
+
+impl Compression {
+    pub fn name(&self) -> &'static str {
+        match *self {
+            Compression::None => "NONE",
+            Compression::Lzw => "LZW",
+            Compression::Deflate => "DEFLATE",
+            Compression::PackBits => "PACKBITS",
+            Compression::Jpeg => "JPEG",
+            Compression::Lzma => "LZMA",
+            Compression::Zstd => "ZSTD",
+            Compression::Webp => "WEBP",
+        }
+    }
+}
+
+/// Value of the `PREDICTOR` creation option, used by LZW, DEFLATE and
+/// ZSTD compression.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Predictor {
+    None,
+    /// Horizontal differencing, good for integer data.
+    Horizontal,
+    /// Floating point prediction, for `f32`/`f64` data.
+    FloatingPoint,
+}
+
+/// Value of the `BIGTIFF` creation option.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BigTiff {
+    Yes,
+    No,
+    IfNeeded,
+    IfSafer,
+}
+
+/// Builder for GeoTIFF creation options, rendered with `options()` to the
+/// string list expected by `Driver::create_with_options` and
+/// `Dataset::create_copy_with_options`. Values out of range are reported
+/// by `options()`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GeoTiffOptions {
+    compression: Option<Compression>,
+    predictor: Option<Predictor>,
+    jpeg_quality: Option<u8>,
+    zlevel: Option<u8>,
+    block_size: Option<(usize, usize)>,
+    bigtiff: Option<BigTiff>,
+    copy_src_overviews: bool,
+    num_threads: Option<NumThreads>,
+}
+
+impl GeoTiffOptions {
+    pub fn new() -> GeoTiffOptions {
+        return GeoTiffOptions::default();
+    }
+
+    pub fn compression(mut self, compression: Compression) -> GeoTiffOptions {
+        self.compression = Some(compression);
+        return self;
+    }
+
+    pub fn predictor(mut self, predictor: Predictor) -> GeoTiffOptions {
+        self.predictor = Some(predictor);
+        return self;
+    }
+
+    /// JPEG quality, from 1 to 100. Only used with `Compression::Jpeg`.
+    pub fn jpeg_quality(mut self, quality: u8) -> GeoTiffOptions {
+        self.jpeg_quality = Some(quality);
+        return self;
+    }
+
+    /// DEFLATE compression level, from 1 to 9.
+    pub fn zlevel(mut self, level: u8) -> GeoTiffOptions {
+        self.zlevel = Some(level);
+        return self;
+    }
+
+    /// Write a tiled TIFF, with tiles of `block_x` by `block_y` pixels.
+    /// Both must be multiples of 16.
+    pub fn tiled(mut self, block_x: usize, block_y: usize) -> GeoTiffOptions {
+        self.block_size = Some((block_x, block_y));
+        return self;
+    }
+
+    pub fn bigtiff(mut self, bigtiff: BigTiff) -> GeoTiffOptions {
+        self.bigtiff = Some(bigtiff);
+        return self;
+    }
+
+    /// Copy the overviews of the source dataset into the output, when used
+    /// with `create_copy_with_options`.
+    pub fn copy_src_overviews(mut self, copy: bool) -> GeoTiffOptions {
+        self.copy_src_overviews = copy;
+        return self;
+    }
+
+    /// Compress blocks with several threads (GDAL 2.1). Defaults to
+    /// `GDAL_NUM_THREADS`, see `config::set_num_threads`.
+    pub fn num_threads(mut self, threads: NumThreads) -> GeoTiffOptions {
+        self.num_threads = Some(threads);
+        return self;
+    }
+
+    /// The creation options, or an error if the JPEG quality, DEFLATE
+    /// level or tile size is out of range.
+    pub fn options(&self) -> Result<Vec<String>, GdalError> {
+        let mut options = Vec::new();
+        if let Some(compression) = self.compression {
+            options.push(format!("COMPRESS={}", compression.name()));
+        }
+        if let Some(predictor) = self.predictor {
+            let value = match predictor {
+                Predictor::None => 1,
+                Predictor::Horizontal => 2,
+                Predictor::FloatingPoint => 3,
+            };
+            options.push(format!("PREDICTOR={}", value));
+        }
+        if let Some(quality) = self.jpeg_quality {
+            if !(1..=100).contains(&quality) {
+                return Err(GdalError::CallFailed{desc: "the JPEG quality must be from 1 to 100"});
+            }
+            options.push(format!("JPEG_QUALITY={}", quality));
+        }
+        if let Some(level) = self.zlevel {
+            if !(1..=9).contains(&level) {
+                return Err(GdalError::CallFailed{desc: "the DEFLATE level must be from 1 to 9"});
+            }
+            options.push(format!("ZLEVEL={}", level));
+        }
+        if let Some((block_x, block_y)) = self.block_size {
+            if !is_tile_size(block_x) || !is_tile_size(block_y) {
+                return Err(GdalError::CallFailed{desc: "tile sizes must be positive multiples of 16"});
+            }
+            options.push("TILED=YES".to_string());
+            options.push(format!("BLOCKXSIZE={}", block_x));
+            options.push(format!("BLOCKYSIZE={}", block_y));
+        }
+        if let Some(bigtiff) = self.bigtiff {
+            let value = match bigtiff {
+                BigTiff::Yes => "YES",
+                BigTiff::No => "NO",
+                BigTiff::IfNeeded => "IF_NEEDED",
+                BigTiff::IfSafer => "IF_SAFER",
+            };
+            options.push(format!("BIGTIFF={}", value));
+        }
+        if self.copy_src_overviews {
+            options.push("COPY_SRC_OVERVIEWS=YES".to_string());
+        }
+        if let Some(threads) = self.num_threads {
+            options.push(format!("NUM_THREADS={}", threads.value()));
+        }
+        return Ok(options);
+    }
+}
+
+/// Builder for the creation options of the COG driver (GDAL 3.1), which
+/// writes cloud optimized GeoTIFFs with `Dataset::create_copy_with_options`.
+/// The COG driver always tiles and builds overviews, so only the block
+/// size is configurable.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CogOptions {
+    compression: Option<Compression>,
+    block_size: Option<usize>,
+    num_threads: Option<NumThreads>,
+}
+
+impl CogOptions {
+    pub fn new() -> CogOptions {
+        return CogOptions::default();
+    }
+
+    pub fn compression(mut self, compression: Compression) -> CogOptions {
+        self.compression = Some(compression);
+        return self;
+    }
+
+    /// Square tiles of `block_size` pixels, a multiple of 16.
+    pub fn block_size(mut self, block_size: usize) -> CogOptions {
+        self.block_size = Some(block_size);
+        return self;
+    }
+
+    /// Compress tiles and compute overviews with several threads. Defaults
+    /// to `GDAL_NUM_THREADS`, see `config::set_num_threads`.
+    pub fn num_threads(mut self, threads: NumThreads) -> CogOptions {
+        self.num_threads = Some(threads);
+        return self;
+    }
+
+    /// The creation options, or an error if the block size isn't a
+    /// multiple of 16.
+    pub fn options(&self) -> Result<Vec<String>, GdalError> {
+        let mut options = Vec::new();
+        if let Some(compression) = self.compression {
+            options.push(format!("COMPRESS={}", compression.name()));
+        }
+        if let Some(block_size) = self.block_size {
+            if !is_tile_size(block_size) {
+                return Err(GdalError::CallFailed{desc: "the block size must be a positive multiple of 16"});
+            }
+            options.push(format!("BLOCKSIZE={}", block_size));
+        }
+        if let Some(threads) = self.num_threads {
+            options.push(format!("NUM_THREADS={}", threads.value()));
+        }
+        return Ok(options);
+    }
+}
+
+fn is_tile_size(size: usize) -> bool {
+    return size > 0 && size % 16 == 0;
+}
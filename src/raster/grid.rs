@@ -0,0 +1,196 @@
+//! Interpolate scattered points onto a regular grid with `GDALGridCreate`,
+//! like the `gdal_grid` utility but straight from Rust slices.
+//!
+//! ```no_run
+//! use gdal::raster::grid::{grid_create, GridAlgorithm, InverseDistanceOptions};
+//!
+//! let (x, y, z) = (vec![0., 10., 10.], vec![0., 0., 10.], vec![1., 2., 3.]);
+//! let algorithm = GridAlgorithm::InverseDistance(InverseDistanceOptions{power: 3., ..Default::default()});
+//! let elevation = grid_create::<f32>(&algorithm, &x, &y, &z, (0., 0., 10., 10.), (100, 100)).unwrap();
+//! ```
+
+use libc::{c_int, c_uint, c_void};
+use std::ptr::{null, null_mut};
+use raster::{gdal, Buffer};
+use raster::types::GdalType;
+use utils::_cstring;
+use GdalError;
+
+/// Options of inverse distance to a power. A radius of zero means
+/// unlimited; `max_points` and `min_points` only apply within a search
+/// ellipse.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct InverseDistanceOptions {
+    pub power: f64,
+    pub smoothing: f64,
+    pub radius1: f64,
+    pub radius2: f64,
+    /// Rotation of the search ellipse, in degrees counter clockwise.
+    pub angle: f64,
+    pub max_points: u32,
+    pub min_points: u32,
+    pub no_data: f64,
+}
+
+impl Default for InverseDistanceOptions {
+    fn default() -> InverseDistanceOptions {
+        return InverseDistanceOptions{
+            power: 2., smoothing: 0., radius1: 0., radius2: 0., angle: 0.,
+            max_points: 0, min_points: 0, no_data: 0.,
+        };
+    }
+}
+
+/// Options of the moving average and data metrics algorithms: the search
+/// ellipse, and the minimum number of points in it for a cell to get a
+/// value other than `no_data`.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct SearchOptions {
+    pub radius1: f64,
+    pub radius2: f64,
+    pub angle: f64,
+    pub min_points: u32,
+    pub no_data: f64,
+}
+
+/// Options of nearest neighbor: cells without a point in the search
+/// ellipse get `no_data`.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct NearestNeighborOptions {
+    pub radius1: f64,
+    pub radius2: f64,
+    pub angle: f64,
+    pub no_data: f64,
+}
+
+/// Options of linear interpolation over a Delaunay triangulation. Cells
+/// outside the triangulation take the nearest point within `radius`, or
+/// `no_data`. A negative radius means unlimited.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct LinearOptions {
+    pub radius: f64,
+    pub no_data: f64,
+}
+
+/// The statistic computed by `GridAlgorithm::Metric` over the points in
+/// the search ellipse of each cell.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GridMetric {
+    Minimum,
+    Maximum,
+    Range,
+    Count,
+    /// Average distance between the cell center and the points.
+    AverageDistance,
+    /// Average distance between the points.
+    AverageDistancePoints,
+}
+
+/// A gridding algorithm with its parameters.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GridAlgorithm {
+    InverseDistance(InverseDistanceOptions),
+    MovingAverage(SearchOptions),
+    NearestNeighbor(NearestNeighborOptions),
+    Metric(GridMetric, SearchOptions),
+    /// Needs GDAL 2.1.
+    Linear(LinearOptions),
+}
+
+impl GridAlgorithm {
+    /// The algorithm and its parameters in the syntax of `gdal_grid -a`,
+    /// e.g. `nearest:radius1=0:radius2=0:angle=0:nodata=0`.
+    pub fn options(&self) -> String {
+        return match *self {
+            GridAlgorithm::InverseDistance(o) => format!(
+                "invdist:power={}:smoothing={}:radius1={}:radius2={}:angle={}:max_points={}:min_points={}:nodata={}",
+                o.power, o.smoothing, o.radius1, o.radius2, o.angle, o.max_points, o.min_points, o.no_data,
+            ),
+            GridAlgorithm::MovingAverage(o) => format!("average:{}", search_options(&o)),
+            GridAlgorithm::NearestNeighbor(o) => format!(
+                "nearest:radius1={}:radius2={}:angle={}:nodata={}",
+                o.radius1, o.radius2, o.angle, o.no_data,
+            ),
+            GridAlgorithm::Metric(metric, o) => {
+                let name = match metric {
+                    GridMetric::Minimum => "minimum",
+                    GridMetric::Maximum => "maximum",
+                    GridMetric::Range => "range",
+                    GridMetric::Count => "count",
+                    GridMetric::AverageDistance => "average_distance",
+                    GridMetric::AverageDistancePoints => "average_distance_pts",
+                };
+                format!("{}:{}", name, search_options(&o))
+            },
+            GridAlgorithm::Linear(o) => format!("linear:radius={}:nodata={}", o.radius, o.no_data),
+        };
+    }
+}
+
+fn search_options(o: &SearchOptions) -> String {
+    return format!(
+        "radius1={}:radius2={}:angle={}:min_points={}:nodata={}",
+        o.radius1, o.radius2, o.angle, o.min_points, o.no_data,
+    );
+}
+
+/// Interpolate the points (`x[i]`, `y[i]`) with values `z[i]` onto a grid
+/// of `size` cells covering `extent` (`(min_x, min_y, max_x, max_y)`).
+/// The buffer is north-up: its first row is the northern edge, as with
+/// `Dataset::read_raster_as`.
+pub fn grid_create<T: Copy + GdalType>(
+    algorithm: &GridAlgorithm,
+    x: &[f64],
+    y: &[f64],
+    z: &[f64],
+    extent: (f64, f64, f64, f64),
+    size: (usize, usize),
+) -> Result<Buffer<T>, GdalError> {
+    if x.len() != y.len() || x.len() != z.len() {
+        return Err(GdalError::CallFailed{desc: "x, y and z must have the same length"});
+    }
+    if x.is_empty() {
+        return Err(GdalError::CallFailed{desc: "no points to grid"});
+    }
+    let (min_x, min_y, max_x, max_y) = extent;
+
+    // GDAL allocates the options struct of the algorithm, whose layout
+    // changes between versions
+    let c_string = _cstring(&algorithm.options())?;
+    let mut c_algorithm: c_int = 0;
+    let mut c_options: *mut c_void = null_mut();
+    let rv = unsafe { gdal::ParseAlgorithmAndOptions(c_string.as_ptr(), &mut c_algorithm, &mut c_options) };
+    if rv != 0 {
+        return Err(GdalError::CallFailed{desc: "ParseAlgorithmAndOptions failed"});
+    }
+
+    let pixels = size.0 * size.1;
+    let mut data: Vec<T> = Vec::with_capacity(pixels);
+    let rv = unsafe {
+        gdal::GDALGridCreate(
+            c_algorithm,
+            c_options,
+            x.len() as c_uint,
+            x.as_ptr(),
+            y.as_ptr(),
+            z.as_ptr(),
+            min_x,
+            max_x,
+            // GDAL fills rows from the first y bound to the second
+            max_y,
+            min_y,
+            size.0 as c_uint,
+            size.1 as c_uint,
+            T::gdal_type(),
+            data.as_mut_ptr() as *mut c_void,
+            null(),
+            null(),
+        )
+    };
+    unsafe { gdal::VSIFree(c_options) };
+    if rv != 0 {
+        return Err(GdalError::CallFailed{desc: "GDALGridCreate failed"});
+    }
+    unsafe { data.set_len(pixels) };
+    return Ok(Buffer::new(size, data));
+}
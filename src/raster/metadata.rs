@@ -0,0 +1,190 @@
+//! Typed readers for common metadata domains
+//!
+//! GDAL exposes metadata as lists of `KEY=VALUE` strings, grouped in
+//! domains. The types in this module parse the domains most often found
+//! on drone and satellite imagery.
+
+use std::collections::HashMap;
+
+/// Split `KEY=VALUE` metadata entries into pairs. Entries without a `=`
+/// are skipped.
+pub fn parse_name_values(entries: &[String]) -> Vec<(String, String)> {
+    return entries.iter()
+        .filter_map(|entry| {
+            let mut parts = entry.splitn(2, '=');
+            match (parts.next(), parts.next()) {
+                (Some(key), Some(value)) => Some((key.to_string(), value.to_string())),
+                _ => None,
+            }
+        })
+        .collect();
+}
+
+/// EXIF tags, as reported by the JPEG and TIFF drivers in the default
+/// metadata domain (keys starting with `EXIF_`).
+#[derive(Clone, Debug, PartialEq)]
+pub struct Exif {
+    pub tags: HashMap<String, String>,
+}
+
+impl Exif {
+    pub fn from_metadata(entries: &[String]) -> Exif {
+        let tags = parse_name_values(entries).into_iter()
+            .filter(|(key, _)| key.starts_with("EXIF_"))
+            .collect();
+        return Exif{tags: tags};
+    }
+
+    /// Get a tag by name, with or without the `EXIF_` prefix.
+    pub fn tag(&self, name: &str) -> Option<&str> {
+        let key = match name.starts_with("EXIF_") {
+            true  => name.to_string(),
+            false => format!("EXIF_{}", name),
+        };
+        return self.tags.get(&key).map(|value| value.as_str());
+    }
+
+    pub fn make(&self) -> Option<&str> {
+        return self.tag("Make");
+    }
+
+    pub fn model(&self) -> Option<&str> {
+        return self.tag("Model");
+    }
+
+    pub fn date_time_original(&self) -> Option<&str> {
+        return self.tag("DateTimeOriginal");
+    }
+
+    /// GPS position as `(longitude, latitude)` in decimal degrees.
+    pub fn gps_position(&self) -> Option<(f64, f64)> {
+        let lat = parse_dms(self.tag("GPSLatitude")?)?;
+        let lon = parse_dms(self.tag("GPSLongitude")?)?;
+        let lat = match self.tag("GPSLatitudeRef") {
+            Some("S") => -lat,
+            _ => lat,
+        };
+        let lon = match self.tag("GPSLongitudeRef") {
+            Some("W") => -lon,
+            _ => lon,
+        };
+        return Some((lon, lat));
+    }
+
+    /// GPS altitude in meters, negative below sea level.
+    pub fn gps_altitude(&self) -> Option<f64> {
+        let altitude = parse_rationals(self.tag("GPSAltitude")?)?;
+        let altitude = *altitude.first()?;
+        return match self.tag("GPSAltitudeRef").map(|r| r.trim()) {
+            Some("1") | Some("(1)") => Some(-altitude),
+            _ => Some(altitude),
+        };
+    }
+}
+
+/// GDAL reports rational EXIF values as `(v1) (v2) (v3)`.
+fn parse_rationals(value: &str) -> Option<Vec<f64>> {
+    return value
+        .split(['(', ')', ' '])
+        .filter(|part| !part.is_empty())
+        .map(|part| part.parse::<f64>().ok())
+        .collect();
+}
+
+fn parse_dms(value: &str) -> Option<f64> {
+    let parts = parse_rationals(value)?;
+    let degrees = *parts.first()?;
+    let minutes = parts.get(1).cloned().unwrap_or(0.);
+    let seconds = parts.get(2).cloned().unwrap_or(0.);
+    return Some(degrees + minutes / 60. + seconds / 3600.);
+}
+
+/// Rational polynomial camera model, from the `RPC` metadata domain.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Rpc {
+    pub line_off: f64,
+    pub samp_off: f64,
+    pub lat_off: f64,
+    pub long_off: f64,
+    pub height_off: f64,
+    pub line_scale: f64,
+    pub samp_scale: f64,
+    pub lat_scale: f64,
+    pub long_scale: f64,
+    pub height_scale: f64,
+    pub line_num_coeff: Vec<f64>,
+    pub line_den_coeff: Vec<f64>,
+    pub samp_num_coeff: Vec<f64>,
+    pub samp_den_coeff: Vec<f64>,
+}
+
+impl Rpc {
+    /// Parse the `RPC` domain. Returns `None` if any of the required
+    /// entries is missing or malformed.
+    pub fn from_metadata(entries: &[String]) -> Option<Rpc> {
+        let items: HashMap<String, String> = parse_name_values(entries).into_iter().collect();
+        let number = |key: &str| -> Option<f64> {
+            items.get(key)?.trim().parse::<f64>().ok()
+        };
+        let coefficients = |key: &str| -> Option<Vec<f64>> {
+            let coeff: Option<Vec<f64>> = items.get(key)?
+                .split_whitespace()
+                .map(|c| c.parse::<f64>().ok())
+                .collect();
+            match coeff {
+                Some(ref c) if c.len() == 20 => coeff,
+                _ => None,
+            }
+        };
+        return Some(Rpc{
+            line_off: number("LINE_OFF")?,
+            samp_off: number("SAMP_OFF")?,
+            lat_off: number("LAT_OFF")?,
+            long_off: number("LONG_OFF")?,
+            height_off: number("HEIGHT_OFF")?,
+            line_scale: number("LINE_SCALE")?,
+            samp_scale: number("SAMP_SCALE")?,
+            lat_scale: number("LAT_SCALE")?,
+            long_scale: number("LONG_SCALE")?,
+            height_scale: number("HEIGHT_SCALE")?,
+            line_num_coeff: coefficients("LINE_NUM_COEFF")?,
+            line_den_coeff: coefficients("LINE_DEN_COEFF")?,
+            samp_num_coeff: coefficients("SAMP_NUM_COEFF")?,
+            samp_den_coeff: coefficients("SAMP_DEN_COEFF")?,
+        });
+    }
+}
+
+/// DigitalGlobe style image metadata, from the `IMD` domain. Nested groups
+/// are flattened into dotted keys, e.g. `IMAGE_1.satId`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Imd {
+    pub items: HashMap<String, String>,
+}
+
+impl Imd {
+    pub fn from_metadata(entries: &[String]) -> Imd {
+        let items = parse_name_values(entries).into_iter()
+            .map(|(key, value)| (key, value.trim_matches('"').to_string()))
+            .collect();
+        return Imd{items: items};
+    }
+
+    pub fn get(&self, key: &str) -> Option<&str> {
+        return self.items.get(key).map(|value| value.as_str());
+    }
+
+    /// Satellite identifier, e.g. `WV02`.
+    pub fn satellite_id(&self) -> Option<&str> {
+        return self.get("IMAGE_1.satId");
+    }
+
+    /// Acquisition time, as found in `IMAGE_1.firstLineTime`.
+    pub fn acquisition_time(&self) -> Option<&str> {
+        return self.get("IMAGE_1.firstLineTime");
+    }
+
+    pub fn cloud_cover(&self) -> Option<f64> {
+        return self.get("IMAGE_1.cloudCover")?.parse::<f64>().ok();
+    }
+}
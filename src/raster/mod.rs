@@ -1,15 +1,48 @@
 //! GDAL Raster Data
 
-pub use raster::dataset::{Dataset, Buffer, ByteBuffer};
+pub use raster::dataset::{Dataset, Buffer, ByteBuffer, OverviewLocation};
 pub use raster::driver::Driver;
-pub use raster::warp::reproject;
+pub use raster::types::Complex;
+pub use raster::gdal_enums::{GDALDataType, GDALResampleAlg};
+pub use raster::cache::DatasetCache;
+pub use raster::rasterband::{RasterBand, BandStatistics, Interpolation};
+pub use raster::window::{Windows, PartialWindows, WindowOrder, Window, RasterSize, BufferLayout};
+pub use raster::warp::{reproject, reproject_to_match, reproject_to_match_with_options, align, align_with_options, GridSpec, WarpOptions, WarpedView};
+pub use raster::retile::{retile, RetileOptions};
+pub use raster::chips::{extract_chips, Chip};
+pub use raster::cog::{validate_cog, CogReport};
+pub use raster::stack::{RasterStack, CubeBuffer};
+pub use raster::vrt::{stack_bands, stack_bands_vrt, StackOptions, BandSubsetView};
+pub use raster::zonal::{zonal_stats, Statistic, ZonalStats};
+pub use raster::polygonize::{polygonize, polygonize_float, Connectedness};
+pub use raster::virtual_mem::VirtualMem;
+pub use raster::strategy::{Interleave, RasterLayout, ReadStrategy};
+pub use raster::color::{ColorEntry, ColorTable, expand_palette, rgb_to_paletted};
 
 mod gdal;
 mod types;
 mod gdal_enums;
+mod polygonize;
+pub mod cache;
+pub mod chips;
+pub mod color;
+pub mod cog;
 pub mod dataset;
 pub mod driver;
+pub mod focal;
+pub mod geotiff;
+pub mod grid;
+pub mod metadata;
+pub mod rasterband;
+pub mod retile;
+pub mod stack;
+pub mod strategy;
+pub mod terrain;
+pub mod virtual_mem;
+pub mod vrt;
 pub mod warp;
+pub mod window;
+pub mod zonal;
 
 #[cfg(test)]
 mod tests;
@@ -0,0 +1,88 @@
+use std::ptr::null;
+use libc::{c_char, c_int, c_void};
+use raster::{gdal, RasterBand};
+use vector::Layer;
+use utils::CslStringList;
+use GdalError;
+
+/// Which neighbors of a pixel `polygonize` merges it with.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Connectedness {
+    /// Pixels sharing an edge, the default of `gdal_polygonize.py`.
+    Four,
+    /// Pixels sharing an edge or a corner, so diagonal features stay in one
+    /// polygon.
+    Eight,
+}
+
+/// Write a polygon for every area of connected pixels of `band` with the
+/// same value to `layer`, like `gdal_polygonize.py`. The pixel value is
+/// written to field number `value_field` of the layer, if given, and
+/// pixel values are truncated to integers; see `polygonize_float` for real
+/// valued bands.
+///
+/// Pixels that are 0 in `mask` are skipped. Without a mask, pixels masked
+/// out by `band` itself (nodata, alpha) are skipped. Polygons are in the
+/// georeferenced coordinates of the raster, so the layer should have its
+/// spatial reference.
+pub fn polygonize(
+    band: &RasterBand,
+    mask: Option<&RasterBand>,
+    layer: &Layer,
+    value_field: Option<usize>,
+    connectedness: Connectedness,
+) -> Result<(), GdalError> {
+    return _polygonize(gdal::GDALPolygonize, band, mask, layer, value_field, connectedness);
+}
+
+/// Same as `polygonize`, but compares and writes pixel values as reals,
+/// for bands of continuous data that were e.g. classified into a few
+/// float values.
+pub fn polygonize_float(
+    band: &RasterBand,
+    mask: Option<&RasterBand>,
+    layer: &Layer,
+    value_field: Option<usize>,
+    connectedness: Connectedness,
+) -> Result<(), GdalError> {
+    return _polygonize(gdal::GDALFPolygonize, band, mask, layer, value_field, connectedness);
+}
+
+type PolygonizeFn = unsafe extern "C" fn(
+    *const c_void, *const c_void, *const c_void, c_int, *const *const c_char, *const c_void, *const c_void,
+) -> c_int;
+
+fn _polygonize(
+    function: PolygonizeFn,
+    band: &RasterBand,
+    mask: Option<&RasterBand>,
+    layer: &Layer,
+    value_field: Option<usize>,
+    connectedness: Connectedness,
+) -> Result<(), GdalError> {
+    let mut options = vec!();
+    if connectedness == Connectedness::Eight {
+        options.push("8CONNECTED=8".to_string());
+    }
+    let c_options = CslStringList::new(&options)?;
+    let c_mask = match mask {
+        Some(mask) => unsafe { mask._c_ptr() },
+        None => unsafe { band.mask_band()._c_ptr() },
+    };
+    let rv = unsafe { function(
+        band._c_ptr(),
+        c_mask,
+        layer._c_layer(),
+        match value_field {
+            Some(field) => field as c_int,
+            None => -1,
+        },
+        c_options.as_ptr(),
+        null(),
+        null(),
+    ) };
+    return match rv {
+        0 => Ok(()),
+        _ => Err(GdalError::CallFailed{desc: "GDALPolygonize failed"}),
+    };
+}
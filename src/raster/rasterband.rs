@@ -0,0 +1,708 @@
+use libc::{c_int, c_double, c_void};
+use std::collections::HashMap;
+use std::ptr::null;
+use raster::{gdal, Dataset, Buffer};
+use raster::gdal_enums::{GDALRWFlag, GDALDataType};
+use raster::types::{Complex, GdalType};
+use raster::window::{Windows, WindowOrder, Window, RasterSize, BufferLayout};
+use raster::virtual_mem::VirtualMem;
+use raster::color::ColorTable;
+use std::mem;
+use raster::dataset::{apply_geo_transform, invert_geo_transform};
+use vector::{Geometry, Layer};
+use utils::{_cstring, _string, _string_array, CslStringList};
+use GdalError;
+
+/// Mask flag: all pixels are valid.
+pub const GMF_ALL_VALID: i32 = 0x01;
+/// Mask flag: the mask is shared between all bands of the dataset.
+pub const GMF_PER_DATASET: i32 = 0x02;
+/// Mask flag: the mask is derived from an alpha band.
+pub const GMF_ALPHA: i32 = 0x04;
+/// Mask flag: the mask is derived from the nodata value.
+pub const GMF_NODATA: i32 = 0x08;
+
+/// How `RasterBand::sample` computes a value between pixel centers.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Interpolation {
+    /// Value of the pixel containing the point.
+    Nearest,
+    /// Weighted average of the four pixels whose centers surround the point.
+    Bilinear,
+}
+
+/// Summary statistics of a band, ignoring nodata pixels.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BandStatistics {
+    pub min: f64,
+    pub max: f64,
+    pub mean: f64,
+    pub std_dev: f64,
+}
+
+/// A single band of a raster `Dataset`.
+///
+/// Bands are owned by their dataset, so a `RasterBand` can't outlive the
+/// `Dataset` it was obtained from. Mask bands and overviews are bands too,
+/// which means the accessors below compose: `band.mask_band().overview(0)`
+/// is the mask of the first overview level.
+pub struct RasterBand<'a> {
+    c_rasterband: *const c_void,
+    owning_dataset: &'a Dataset,
+}
+
+impl<'a> RasterBand<'a> {
+    pub unsafe fn _with_c_ptr(c_rasterband: *const c_void, owning_dataset: &'a Dataset) -> RasterBand<'a> {
+        return RasterBand{c_rasterband: c_rasterband, owning_dataset: owning_dataset};
+    }
+
+    pub unsafe fn _c_ptr(&self) -> *const c_void {
+        return self.c_rasterband;
+    }
+
+    /// The `Dataset` this band belongs to.
+    pub fn owning_dataset(&self) -> &'a Dataset {
+        return self.owning_dataset;
+    }
+
+    /// Index of this band in its dataset, starting from 1. Mask bands and
+    /// overviews report 0.
+    pub fn index(&self) -> isize {
+        return unsafe { gdal::GDALGetBandNumber(self.c_rasterband) } as isize;
+    }
+
+    /// Size of the band in pixels. Overviews are smaller than the dataset.
+    pub fn size(&self) -> (usize, usize) {
+        let size_x = unsafe { gdal::GDALGetRasterBandXSize(self.c_rasterband) } as usize;
+        let size_y = unsafe { gdal::GDALGetRasterBandYSize(self.c_rasterband) } as usize;
+        return (size_x, size_y);
+    }
+
+    /// The natural block size of this band, i.e. the most efficient unit
+    /// for reading and writing. Tiled formats report the tile size,
+    /// stripped formats a single scanline (or strip).
+    pub fn block_size(&self) -> (usize, usize) {
+        let mut size_x: c_int = 0;
+        let mut size_y: c_int = 0;
+        unsafe { gdal::GDALGetBlockSize(self.c_rasterband, &mut size_x, &mut size_y) };
+        return (size_x as usize, size_y as usize);
+    }
+
+    /// Iterate over windows of `window_size` covering this band, in the
+    /// given `order`. See `Windows::new` for `align_to_blocks`.
+    pub fn windows(&self, window_size: (usize, usize), order: WindowOrder, align_to_blocks: bool) -> Windows {
+        return Windows::new(self.size(), self.block_size(), window_size, order, align_to_blocks);
+    }
+
+    pub fn band_type(&self) -> GDALDataType {
+        let gdal_type = unsafe { gdal::GDALGetRasterDataType(self.c_rasterband) };
+        return GDALDataType::from_c_int(gdal_type);
+    }
+
+    /// Read `window` of this band into a 'Buffer<T>' of `size` pixels. T
+    /// implements 'GdalType'. GDAL resamples if the window and buffer sizes
+    /// differ. Fails if `window` doesn't lie inside the band or `size` is
+    /// empty.
+    ///
+    /// `T` doesn't have to match `band_type`: GDAL converts every value
+    /// while reading, e.g. an Int16 band can be read as `f32`. Values that
+    /// don't fit into `T` saturate at its minimum or maximum, floating
+    /// point values read as integers are rounded to the nearest integer,
+    /// and NaN becomes 0. The same conversion applies when writing.
+    pub fn read_as<T: Copy + GdalType>(&self, window: Window, size: RasterSize) -> Result<Buffer<T>, GdalError> {
+        let (size_x, size_y) = self.size();
+        window.validate(RasterSize::new(size_x, size_y))?;
+        let pixels = size.pixels()?;
+        if pixels == 0 {
+            return Err(GdalError::CallFailed{desc: "empty buffer"});
+        }
+        let mut data: Vec<T> = Vec::with_capacity(pixels);
+        let rv = unsafe { gdal::GDALRasterIO(
+            self.c_rasterband,
+            GDALRWFlag::GF_Read,
+            window.x_off as c_int,
+            window.y_off as c_int,
+            window.x_size as c_int,
+            window.y_size as c_int,
+            data.as_mut_ptr() as *const c_void,
+            size.width as c_int,
+            size.height as c_int,
+            T::gdal_type(),
+            0,
+            0
+        ) };
+        if rv != 0 {
+            return Err(GdalError::CallFailed{desc: "GDALRasterIO failed"});
+        }
+        unsafe { data.set_len(pixels) };
+        return Ok(Buffer::new((size.width, size.height), data));
+    }
+
+    /// Write `buffer` into `window` of this band, resampling it if the
+    /// sizes differ. Fails if the window doesn't lie inside the band or the
+    /// buffer data doesn't match its size.
+    pub fn write_window<T: Copy + GdalType>(&self, window: Window, buffer: &Buffer<T>) -> Result<(), GdalError> {
+        let (size_x, size_y) = self.size();
+        window.validate(RasterSize::new(size_x, size_y))?;
+        let pixels = RasterSize::new(buffer.size.0, buffer.size.1).pixels()?;
+        if pixels == 0 || buffer.data.len() != pixels {
+            return Err(GdalError::CallFailed{desc: "buffer data doesn't match its size"});
+        }
+        let rv = unsafe { gdal::GDALRasterIO(
+            self.c_rasterband,
+            GDALRWFlag::GF_Write,
+            window.x_off as c_int,
+            window.y_off as c_int,
+            window.x_size as c_int,
+            window.y_size as c_int,
+            buffer.data.as_ptr() as *const c_void,
+            buffer.size.0 as c_int,
+            buffer.size.1 as c_int,
+            T::gdal_type(),
+            0,
+            0
+        ) };
+        return match rv {
+            0 => Ok(()),
+            _ => Err(GdalError::CallFailed{desc: "GDALRasterIO failed"}),
+        };
+    }
+
+    /// Read `window` into a caller-owned `buffer` of `size` pixels laid out
+    /// as `layout`, e.g. one channel of an interleaved RGBA texture, without
+    /// an intermediate `Buffer`. Elements the layout skips are left alone.
+    pub fn read_into<T: Copy + GdalType>(
+        &self,
+        window: Window,
+        size: RasterSize,
+        buffer: &mut [T],
+        layout: BufferLayout,
+    ) -> Result<(), GdalError> {
+        self._check_layout(window, size, buffer.len(), layout)?;
+        return self._raster_io::<T>(GDALRWFlag::GF_Read, window, size, buffer.as_mut_ptr() as *const c_void, layout);
+    }
+
+    /// Write `window` from a caller-owned `buffer` of `size` pixels laid
+    /// out as `layout`, the counterpart of `read_into`.
+    pub fn write_from<T: Copy + GdalType>(
+        &self,
+        window: Window,
+        size: RasterSize,
+        buffer: &[T],
+        layout: BufferLayout,
+    ) -> Result<(), GdalError> {
+        self._check_layout(window, size, buffer.len(), layout)?;
+        return self._raster_io::<T>(GDALRWFlag::GF_Write, window, size, buffer.as_ptr() as *const c_void, layout);
+    }
+
+    fn _check_layout(&self, window: Window, size: RasterSize, len: usize, layout: BufferLayout) -> Result<(), GdalError> {
+        let (size_x, size_y) = self.size();
+        window.validate(RasterSize::new(size_x, size_y))?;
+        if size.pixels()? == 0 {
+            return Err(GdalError::CallFailed{desc: "empty buffer"});
+        }
+        if len < layout.required_len(size, 1)? {
+            return Err(GdalError::CallFailed{desc: "buffer too small for its layout"});
+        }
+        return Ok(());
+    }
+
+    fn _raster_io<T: GdalType>(
+        &self,
+        rw_flag: GDALRWFlag,
+        window: Window,
+        size: RasterSize,
+        data: *const c_void,
+        layout: BufferLayout,
+    ) -> Result<(), GdalError> {
+        let element_size = mem::size_of::<T>();
+        let rv = unsafe { gdal::GDALRasterIO(
+            self.c_rasterband,
+            rw_flag,
+            window.x_off as c_int,
+            window.y_off as c_int,
+            window.x_size as c_int,
+            window.y_size as c_int,
+            data,
+            size.width as c_int,
+            size.height as c_int,
+            T::gdal_type(),
+            (layout.pixel_space * element_size) as c_int,
+            (layout.line_space * element_size) as c_int
+        ) };
+        return match rv {
+            0 => Ok(()),
+            _ => Err(GdalError::CallFailed{desc: "GDALRasterIO failed"}),
+        };
+    }
+
+    /// Read a window of a complex band, e.g. a SAR image, as two planes of
+    /// magnitude and phase (in radians). Arguments and errors are the same
+    /// as for `read_as`. Real bands have a phase of 0 or π.
+    pub fn read_magnitude_phase(&self, window: Window, size: RasterSize) -> Result<(Buffer<f64>, Buffer<f64>), GdalError> {
+        let values = self.read_as::<Complex<f64>>(window, size)?;
+        let magnitude = values.data.iter().map(|value| value.magnitude()).collect();
+        let phase = values.data.iter().map(|value| value.phase()).collect();
+        return Ok((Buffer::new(values.size, magnitude), Buffer::new(values.size, phase)));
+    }
+
+    /// Read a window of a complex band as two planes of real and imaginary
+    /// parts. Arguments and errors are the same as for `read_as`.
+    pub fn read_re_im(&self, window: Window, size: RasterSize) -> Result<(Buffer<f64>, Buffer<f64>), GdalError> {
+        let values = self.read_as::<Complex<f64>>(window, size)?;
+        let re = values.data.iter().map(|value| value.re).collect();
+        let im = values.data.iter().map(|value| value.im).collect();
+        return Ok((Buffer::new(values.size, re), Buffer::new(values.size, im)));
+    }
+
+    /// The nodata value of this band, if it has one.
+    pub fn no_data_value(&self) -> Option<f64> {
+        let mut success: c_int = 0;
+        let rv = unsafe { gdal::GDALGetRasterNoDataValue(self.c_rasterband, &mut success) };
+        return match success {
+            0 => None,
+            _ => Some(rv as f64),
+        };
+    }
+
+    pub fn set_no_data_value(&self, no_data: f64) -> Result<(), GdalError> {
+        let rv = unsafe { gdal::GDALSetRasterNoDataValue(self.c_rasterband, no_data as c_double) };
+        return match rv {
+            0 => Ok(()),
+            _ => Err(GdalError::CallFailed{desc: "GDALSetRasterNoDataValue failed"}),
+        };
+    }
+
+    /// The factor raw pixel values are multiplied with to get physical
+    /// values, e.g. reflectance or degrees Celsius. 1 if not set.
+    pub fn scale(&self) -> f64 {
+        let mut success: c_int = 0;
+        let rv = unsafe { gdal::GDALGetRasterScale(self.c_rasterband, &mut success) };
+        return match success {
+            0 => 1.,
+            _ => rv as f64,
+        };
+    }
+
+    pub fn set_scale(&self, scale: f64) -> Result<(), GdalError> {
+        let rv = unsafe { gdal::GDALSetRasterScale(self.c_rasterband, scale as c_double) };
+        return match rv {
+            0 => Ok(()),
+            _ => Err(GdalError::CallFailed{desc: "GDALSetRasterScale failed"}),
+        };
+    }
+
+    /// The value added to scaled pixel values to get physical values. 0 if
+    /// not set.
+    pub fn offset(&self) -> f64 {
+        let mut success: c_int = 0;
+        let rv = unsafe { gdal::GDALGetRasterOffset(self.c_rasterband, &mut success) };
+        return match success {
+            0 => 0.,
+            _ => rv as f64,
+        };
+    }
+
+    pub fn set_offset(&self, offset: f64) -> Result<(), GdalError> {
+        let rv = unsafe { gdal::GDALSetRasterOffset(self.c_rasterband, offset as c_double) };
+        return match rv {
+            0 => Ok(()),
+            _ => Err(GdalError::CallFailed{desc: "GDALSetRasterOffset failed"}),
+        };
+    }
+
+    /// Convert raw values read from this band to physical values,
+    /// `raw * scale + offset`. Nodata pixels become NaN.
+    pub fn to_physical<T: Copy + GdalType + Into<f64>>(&self, buffer: &Buffer<T>) -> Buffer<f64> {
+        let (scale, offset) = (self.scale(), self.offset());
+        let no_data = self.no_data_value();
+        let data = buffer.data.iter()
+            .map(|&value| {
+                let value: f64 = value.into();
+                match no_data {
+                    Some(no_data) if value == no_data => f64::NAN,
+                    _ => value * scale + offset,
+                }
+            })
+            .collect();
+        return Buffer::new(buffer.size, data);
+    }
+
+    /// Convert physical values back to raw values of this band, the inverse
+    /// of `to_physical`. NaN becomes the nodata value, or stays NaN without
+    /// one. The result isn't rounded; `write_raster` rounds and clamps it
+    /// to the band type.
+    pub fn from_physical(&self, buffer: &Buffer<f64>) -> Buffer<f64> {
+        let (scale, offset) = (self.scale(), self.offset());
+        let no_data = self.no_data_value();
+        let data = buffer.data.iter()
+            .map(|&value| match (value.is_nan(), no_data) {
+                (true, Some(no_data)) => no_data,
+                _ => (value - offset) / scale,
+            })
+            .collect();
+        return Buffer::new(buffer.size, data);
+    }
+
+    /// Count the pixels of every distinct value of this integer band, e.g.
+    /// the classes of a land cover raster, skipping pixels masked out by the
+    /// band (nodata, alpha). The band is scanned block by block; the scan
+    /// stops with an error as soon as more than `limit` distinct values were
+    /// found, to guard against continuous data.
+    pub fn unique_values(&self, limit: usize) -> Result<HashMap<i64, usize>, GdalError> {
+        match self.band_type() {
+            GDALDataType::GDT_Byte | GDALDataType::GDT_UInt16 | GDALDataType::GDT_Int16 |
+            GDALDataType::GDT_UInt32 | GDALDataType::GDT_Int32 => (),
+            _ => return Err(GdalError::CallFailed{desc: "band is not an integer band"}),
+        }
+        let mut counts = HashMap::new();
+        for (window, window_size) in self.windows((256, 256), WindowOrder::BlockMajor, true) {
+            let window = Window::from_tuples(window, window_size);
+            let values = self.read_as::<f64>(window, window.size())?.data;
+            let valid = self.read_mask(window, window.size())?.data;
+            for (&value, &valid) in values.iter().zip(valid.iter()) {
+                if valid == 0 {
+                    continue;
+                }
+                *counts.entry(value as i64).or_insert(0) += 1;
+                if counts.len() > limit {
+                    return Err(GdalError::CallFailed{desc: "too many distinct values"});
+                }
+            }
+        }
+        return Ok(counts);
+    }
+
+    /// Map the pixels of this band read-only into memory, see the
+    /// `virtual_mem` module. `T` must be the type of the band, there's no
+    /// conversion. Fails for formats that can't be mapped on this
+    /// platform.
+    ///
+    /// # Safety
+    ///
+    /// Some formats, MEM datasets among them, are mapped in place, so the
+    /// slices of the mapping alias the pixels of the band. Nothing may
+    /// write to the band or its dataset, e.g. with `write_raster` or
+    /// `write_window`, while the mapping is alive.
+    pub unsafe fn virtual_mem<T: Copy + GdalType>(&self) -> Result<VirtualMem<'a, T>, GdalError> {
+        if T::gdal_type() != self.band_type() {
+            return Err(GdalError::CallFailed{desc: "the pixel type doesn't match the band type"});
+        }
+        let mut pixel_space: c_int = 0;
+        let mut line_space: i64 = 0;
+        let c_mem = gdal::GDALGetVirtualMemAuto(
+            self.c_rasterband,
+            GDALRWFlag::GF_Read,
+            &mut pixel_space,
+            &mut line_space,
+            null(),
+        );
+        if c_mem.is_null() {
+            return Err(GdalError::CallFailed{desc: "GDALGetVirtualMemAuto failed"});
+        }
+        return Ok(VirtualMem::_with_c_mem(c_mem, self.size(), pixel_space as usize, line_space as usize));
+    }
+
+    /// Get metadata item `key` of `domain`, e.g. `"IFD_OFFSET"` of the
+    /// `"TIFF"` domain of a GeoTIFF band. Use `""` for the default domain.
+    pub fn metadata_item(&self, key: &str, domain: &str) -> Option<String> {
+        let c_key = _cstring(key).ok()?;
+        let c_domain = _cstring(domain).ok()?;
+        let rv = unsafe { gdal::GDALGetMetadataItem(self.c_rasterband, c_key.as_ptr(), c_domain.as_ptr()) };
+        return match rv.is_null() {
+            true  => None,
+            false => Some(_string(rv)),
+        };
+    }
+
+    /// The palette of a paletted band, `None` for other bands.
+    pub fn color_table(&self) -> Option<ColorTable> {
+        let c_color_table = unsafe { gdal::GDALGetRasterColorTable(self.c_rasterband) };
+        return match c_color_table.is_null() {
+            true  => None,
+            // the band keeps ownership of its color table
+            false => Some(unsafe { ColorTable::_clone_from_c_table(c_color_table) }),
+        };
+    }
+
+    /// Make this a paletted band with the colors of `table`. Only some
+    /// formats, like PNG, GIF and GeoTIFF, support color tables, mostly
+    /// on byte bands.
+    pub fn set_color_table(&self, table: &ColorTable) -> Result<(), GdalError> {
+        let rv = unsafe { gdal::GDALSetRasterColorTable(self.c_rasterband, table._c_ptr()) };
+        return match rv {
+            0 => Ok(()),
+            _ => Err(GdalError::CallFailed{desc: "GDALSetRasterColorTable failed"}),
+        };
+    }
+
+    /// The names of the categories of a classified band, indexed by pixel
+    /// value. Empty if the band has none.
+    pub fn category_names(&self) -> Vec<String> {
+        return _string_array(unsafe { gdal::GDALGetRasterCategoryNames(self.c_rasterband) });
+    }
+
+    /// Set the category names, `names[i]` being the name of pixel value `i`.
+    /// Not every format can store them; GeoTIFF keeps them in the
+    /// `.aux.xml` file.
+    pub fn set_category_names(&self, names: &[String]) -> Result<(), GdalError> {
+        let c_names = CslStringList::new(names)?;
+        let rv = unsafe { gdal::GDALSetRasterCategoryNames(self.c_rasterband, c_names.as_ptr()) };
+        return match rv {
+            0 => Ok(()),
+            _ => Err(GdalError::CallFailed{desc: "GDALSetRasterCategoryNames failed"}),
+        };
+    }
+
+    /// The statistics stored with the band (`STATISTICS_*` metadata, e.g.
+    /// from the `.aux.xml` file), without scanning the pixels. `None` if
+    /// there are none; with `approx_ok`, statistics computed from overviews
+    /// or a subset of the pixels are accepted too.
+    pub fn statistics(&self, approx_ok: bool) -> Option<BandStatistics> {
+        let mut statistics = BandStatistics{min: 0., max: 0., mean: 0., std_dev: 0.};
+        let rv = unsafe { gdal::GDALGetRasterStatistics(
+            self.c_rasterband,
+            approx_ok as c_int,
+            0,
+            &mut statistics.min,
+            &mut statistics.max,
+            &mut statistics.mean,
+            &mut statistics.std_dev,
+        ) };
+        return match rv {
+            0 => Some(statistics),
+            _ => None,
+        };
+    }
+
+    /// Scan the band to compute its statistics. With `approx_ok` GDAL may
+    /// use overviews or a subset of the blocks, which is much faster on
+    /// big rasters.
+    ///
+    /// The result is stored with the band as if set by `set_statistics`,
+    /// so for datasets with PAM enabled it's written to the `.aux.xml`
+    /// file when the dataset is closed, and later `statistics` calls pick
+    /// it up. Open the dataset with `Dataset::open_with_pam(path, false)`
+    /// to avoid writing anything.
+    pub fn compute_statistics(&self, approx_ok: bool) -> Result<BandStatistics, GdalError> {
+        let mut statistics = BandStatistics{min: 0., max: 0., mean: 0., std_dev: 0.};
+        let rv = unsafe { gdal::GDALComputeRasterStatistics(
+            self.c_rasterband,
+            approx_ok as c_int,
+            &mut statistics.min,
+            &mut statistics.max,
+            &mut statistics.mean,
+            &mut statistics.std_dev,
+            null(),
+            null(),
+        ) };
+        return match rv {
+            0 => Ok(statistics),
+            _ => Err(GdalError::CallFailed{desc: "GDALComputeRasterStatistics failed"}),
+        };
+    }
+
+    /// Store `statistics` with the band, e.g. ones computed elsewhere, so
+    /// readers don't have to scan the pixels. They are persisted like
+    /// those of `compute_statistics`.
+    pub fn set_statistics(&self, statistics: &BandStatistics) -> Result<(), GdalError> {
+        let rv = unsafe { gdal::GDALSetRasterStatistics(
+            self.c_rasterband,
+            statistics.min,
+            statistics.max,
+            statistics.mean,
+            statistics.std_dev,
+        ) };
+        return match rv {
+            0 => Ok(()),
+            _ => Err(GdalError::CallFailed{desc: "GDALSetRasterStatistics failed"}),
+        };
+    }
+
+    /// Read the validity mask of this band in one call. Valid pixels are
+    /// 255, invalid (nodata, transparent) pixels are 0, so the result can be
+    /// paired with a data buffer read with the same arguments. Errors are
+    /// the same as for `read_as`.
+    pub fn read_mask(&self, window: Window, size: RasterSize) -> Result<Buffer<u8>, GdalError> {
+        return self.mask_band().read_as::<u8>(window, size);
+    }
+
+    /// Get the mask band of this band. GDAL always returns a mask, even
+    /// when every pixel is valid; check `mask_flags` to find out which kind.
+    pub fn mask_band(&self) -> RasterBand<'a> {
+        let c_mask = unsafe { gdal::GDALGetMaskBand(self.c_rasterband) };
+        return unsafe { RasterBand::_with_c_ptr(c_mask, self.owning_dataset) };
+    }
+
+    /// The `GMF_*` flags describing the mask band.
+    pub fn mask_flags(&self) -> i32 {
+        return unsafe { gdal::GDALGetMaskFlags(self.c_rasterband) } as i32;
+    }
+
+    /// Number of overview levels available for this band.
+    pub fn overview_count(&self) -> isize {
+        return unsafe { gdal::GDALGetOverviewCount(self.c_rasterband) } as isize;
+    }
+
+    /// Get overview level `overview_index`, starting from 0.
+    pub fn overview(&self, overview_index: isize) -> Option<RasterBand<'a>> {
+        let c_band = unsafe { gdal::GDALGetOverview(self.c_rasterband, overview_index as c_int) };
+        return match c_band.is_null() {
+            true  => None,
+            false => Some(unsafe { RasterBand::_with_c_ptr(c_band, self.owning_dataset) }),
+        };
+    }
+
+    /// Whether the driver can produce overviews at any resolution on the
+    /// fly (e.g. wavelet based formats), rather than a fixed pyramid.
+    pub fn has_arbitrary_overviews(&self) -> bool {
+        return unsafe { gdal::GDALHasArbitraryOverviews(self.c_rasterband) } != 0;
+    }
+
+    /// Pick the pyramid level best suited for rendering this band at
+    /// `size_x` by `size_y` pixels: the most reduced overview that is still
+    /// at least as large as the requested size. Falls back to the band
+    /// itself if no overview qualifies.
+    pub fn get_overview_for_size(&self, size_x: usize, size_y: usize) -> RasterBand<'a> {
+        let mut best = unsafe { RasterBand::_with_c_ptr(self.c_rasterband, self.owning_dataset) };
+        let mut best_size = self.size();
+        for i in 0..self.overview_count() {
+            let overview = match self.overview(i) {
+                Some(overview) => overview,
+                None => continue,
+            };
+            let (ov_x, ov_y) = overview.size();
+            if ov_x >= size_x && ov_y >= size_y && ov_x * ov_y < best_size.0 * best_size.1 {
+                best_size = (ov_x, ov_y);
+                best = overview;
+            }
+        }
+        return best;
+    }
+
+    /// Sample this band at georeferenced `points`, e.g. to look up the
+    /// elevation at GPS positions. Points are converted to pixels with the
+    /// geo-transform of the owning dataset, which must be in the same
+    /// coordinate system. Returns `None` for points outside of the raster
+    /// or on masked out (nodata) pixels; with bilinear interpolation, a
+    /// single masked neighbour is enough. Fails if the dataset has no
+    /// invertible geo-transform or reading a pixel fails.
+    pub fn sample(&self, points: &[(f64, f64)], interpolation: Interpolation) -> Result<Vec<Option<f64>>, GdalError> {
+        let transform = match self.owning_dataset.geo_transform() {
+            Some(transform) => transform,
+            None => return Err(GdalError::CallFailed{desc: "dataset has no geo-transform"}),
+        };
+        let inverse = match invert_geo_transform(&transform) {
+            Some(inverse) => inverse,
+            None => return Err(GdalError::CallFailed{desc: "geo-transform is not invertible"}),
+        };
+        return points.iter()
+            .map(|&(x, y)| {
+                let (pixel_x, pixel_y) = apply_geo_transform(&inverse, x, y);
+                self.sample_pixel(pixel_x, pixel_y, interpolation)
+            })
+            .collect();
+    }
+
+    /// Sample this band at the centroid of every feature of `layer`,
+    /// returning `(fid, value)` pairs in layer order. Features without a
+    /// geometry or feature id are skipped. See `sample`.
+    pub fn sample_layer(&self, layer: &Layer, interpolation: Interpolation) -> Result<Vec<(u64, Option<f64>)>, GdalError> {
+        let mut rv = vec!();
+        for feature in layer.features() {
+            let fid = match feature.fid() {
+                Some(fid) => fid,
+                None => continue,
+            };
+            let geometry = match feature.geometry() {
+                Some(geometry) => geometry,
+                None => continue,
+            };
+            let value = match geometry._centroid_xy() {
+                Some(point) => self.sample(&[point], interpolation)?[0],
+                None => None,
+            };
+            rv.push((fid, value));
+        }
+        return Ok(rv);
+    }
+
+    /// Sample this band along a LineString every `sample_distance` units,
+    /// e.g. for an elevation profile. Returns `(distance, value)` pairs
+    /// from the start of the line; the last vertex is always included.
+    /// Values are interpolated bilinearly, see `sample`.
+    pub fn profile(&self, line: &Geometry, sample_distance: f64) -> Result<Vec<(f64, Option<f64>)>, GdalError> {
+        if sample_distance.is_nan() || sample_distance <= 0. {
+            return Err(GdalError::CallFailed{desc: "the sample distance must be positive"});
+        }
+        let vertices = line.get_point_vec();
+        let mut distances = vec!();
+        let mut points = vec!();
+        // samples closer than this to the end of a segment are left to the
+        // next one, so rounding doesn't produce near duplicates at vertices
+        let epsilon = sample_distance * 1e-9;
+        let mut start = 0.;
+        let mut step = 0;
+        for segment in vertices.windows(2) {
+            let (x0, y0, _) = segment[0];
+            let (x1, y1, _) = segment[1];
+            let length = ((x1 - x0) * (x1 - x0) + (y1 - y0) * (y1 - y0)).sqrt();
+            let mut next = step as f64 * sample_distance;
+            while next < start + length - epsilon {
+                let t = ((next - start) / length).max(0.);
+                distances.push(next);
+                points.push((x0 + t * (x1 - x0), y0 + t * (y1 - y0)));
+                step += 1;
+                next = step as f64 * sample_distance;
+            }
+            start += length;
+        }
+        if let Some(&(x, y, _)) = vertices.last() {
+            distances.push(start);
+            points.push((x, y));
+        }
+        let values = self.sample(&points, Interpolation::Bilinear)?;
+        return Ok(distances.into_iter().zip(values).collect());
+    }
+
+    fn sample_pixel(&self, pixel_x: f64, pixel_y: f64, interpolation: Interpolation) -> Result<Option<f64>, GdalError> {
+        let (size_x, size_y) = self.size();
+        if pixel_x < 0. || pixel_y < 0. || pixel_x >= size_x as f64 || pixel_y >= size_y as f64 {
+            return Ok(None);
+        }
+        match interpolation {
+            Interpolation::Nearest => {
+                let window = Window::new(pixel_x as usize, pixel_y as usize, 1, 1);
+                if self.read_mask(window, window.size())?.data[0] == 0 {
+                    return Ok(None);
+                }
+                return Ok(Some(self.read_as::<f64>(window, window.size())?.data[0]));
+            },
+            Interpolation::Bilinear => {
+                // pixel centers are at half-pixel offsets; neighbours past the
+                // edges are clamped to the last row or column
+                let center_x = (pixel_x - 0.5).max(0.).min((size_x - 1) as f64);
+                let center_y = (pixel_y - 0.5).max(0.).min((size_y - 1) as f64);
+                let x0 = (center_x.floor() as usize).min(size_x.saturating_sub(2));
+                let y0 = (center_y.floor() as usize).min(size_y.saturating_sub(2));
+                let window_size = ((size_x - x0).min(2), (size_y - y0).min(2));
+                let window = Window::new(x0, y0, window_size.0, window_size.1);
+                let mask = self.read_mask(window, window.size())?;
+                if mask.data.contains(&0) {
+                    return Ok(None);
+                }
+                let values = self.read_as::<f64>(window, window.size())?;
+                let value = |dx: usize, dy: usize| {
+                    let dx = dx.min(window_size.0 - 1);
+                    let dy = dy.min(window_size.1 - 1);
+                    values.data[dy * window_size.0 + dx]
+                };
+                let fx = center_x - x0 as f64;
+                let fy = center_y - y0 as f64;
+                let top = value(0, 0) * (1. - fx) + value(1, 0) * fx;
+                let bottom = value(0, 1) * (1. - fx) + value(1, 1) * fx;
+                return Ok(Some(top * (1. - fy) + bottom * fy));
+            },
+        }
+    }
+}
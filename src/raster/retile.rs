@@ -0,0 +1,135 @@
+use std::path::{Path, PathBuf};
+use raster::{Dataset, Driver, Windows, Window, WindowOrder};
+use vector;
+use vector::{FieldValue, Geometry, OFT_STRING};
+use GdalError;
+
+/// Options for `retile`.
+pub struct RetileOptions {
+    /// Short name of the raster driver used for the tiles.
+    pub driver: String,
+    /// File extension of the tiles.
+    pub extension: String,
+    /// Creation options passed to the driver for every tile.
+    pub creation_options: Vec<String>,
+    /// If set, write a GeoJSON tile index with the footprint of every tile
+    /// and its path in a `location` field.
+    pub index: Option<PathBuf>,
+}
+
+impl Default for RetileOptions {
+    fn default() -> RetileOptions {
+        RetileOptions{
+            driver: "GTiff".to_string(),
+            extension: "tif".to_string(),
+            creation_options: vec!(),
+            index: None,
+        }
+    }
+}
+
+/// Cut `dataset` into tiles of `tile_size` pixels written to `out_dir`,
+/// like `gdal_retile.py`. Tiles on the right and bottom edges are smaller.
+/// Tiles are named `<name>_<row>_<col>.<extension>`, where `<name>` is the
+/// file name of the dataset. Returns the paths of the tiles, row by row.
+///
+/// Every band is copied, along with the nodata values, projection and a
+/// geo-transform adjusted to the tile origin.
+pub fn retile(
+    dataset: &Dataset,
+    out_dir: &Path,
+    tile_size: (usize, usize),
+    options: &RetileOptions,
+) -> Result<Vec<PathBuf>, GdalError> {
+    let driver = match Driver::get(&options.driver) {
+        Some(driver) => driver,
+        None => return Err(GdalError::CallFailed{desc: "unknown raster driver"}),
+    };
+    let band_count = dataset.count();
+    let band_type = match dataset.rasterband(1) {
+        Some(band) => band.band_type(),
+        None => return Err(GdalError::CallFailed{desc: "dataset has no bands"}),
+    };
+    let (size_x, size_y) = dataset.size();
+    let transform = dataset.geo_transform();
+    let projection = dataset.projection();
+    let description = dataset.description();
+    let name = Path::new(&description)
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or("tile");
+
+    let mut tiles = vec!();
+    let mut footprints = vec!();
+    let mut locations = vec!();
+    let windows = Windows::new((size_x as usize, size_y as usize), (1, 1), tile_size, WindowOrder::RowMajor, false);
+    for (window, window_size) in windows {
+        let row = window.1 as usize / tile_size.1;
+        let col = window.0 as usize / tile_size.0;
+        let path = out_dir.join(format!("{}_{}_{}.{}", name, row, col, options.extension));
+        let location = match path.to_str() {
+            Some(location) => location.to_string(),
+            None => return Err(GdalError::CallFailed{desc: "tile path is not valid UTF-8"}),
+        };
+        let tile = match driver.create_with_data_type(
+            &location,
+            window_size.0 as isize,
+            window_size.1 as isize,
+            band_count,
+            band_type,
+            &options.creation_options,
+        ) {
+            Some(tile) => tile,
+            None => return Err(GdalError::CallFailed{desc: "failed to create tile"}),
+        };
+
+        let tr = transform.unwrap_or([0., 1., 0., 0., 0., 1.]);
+        let origin = |x: isize, y: isize| (
+            tr[0] + x as f64 * tr[1] + y as f64 * tr[2],
+            tr[3] + x as f64 * tr[4] + y as f64 * tr[5],
+        );
+        if transform.is_some() {
+            let (origin_x, origin_y) = origin(window.0, window.1);
+            tile.set_geo_transform_checked(&[origin_x, tr[1], tr[2], origin_y, tr[4], tr[5]])?;
+            tile.set_projection(&projection)?;
+        }
+
+        for band_index in 1..band_count + 1 {
+            let band = dataset.rasterband(band_index).unwrap();
+            let source = Window::from_tuples(window, window_size);
+            let buffer = band.read_as::<f64>(source, source.size())?;
+            tile.write_raster(band_index, Window::full(source.size()), buffer)?;
+            if let Some(no_data) = band.no_data_value() {
+                tile.rasterband(band_index).unwrap().set_no_data_value(no_data)?;
+            }
+        }
+
+        let (x1, y1) = (window.0 + window_size.0 as isize, window.1 + window_size.1 as isize);
+        let corners = [origin(window.0, window.1), origin(x1, window.1), origin(x1, y1), origin(window.0, y1)];
+        footprints.push(Geometry::from_wkt(&format!(
+            "POLYGON (({} {}, {} {}, {} {}, {} {}, {} {}))",
+            corners[0].0, corners[0].1,
+            corners[1].0, corners[1].1,
+            corners[2].0, corners[2].1,
+            corners[3].0, corners[3].1,
+            corners[0].0, corners[0].1,
+        ))?);
+        locations.push(location);
+        tiles.push(path);
+    }
+
+    if let Some(ref index) = options.index {
+        let index_driver = vector::Driver::get("GeoJSON").unwrap();
+        let mut index_dataset = match index_driver.create(index) {
+            Some(index_dataset) => index_dataset,
+            None => return Err(GdalError::CallFailed{desc: "failed to create tile index"}),
+        };
+        let layer = index_dataset.create_layer()?;
+        layer.create_defn_fields(&[("location", OFT_STRING)])?;
+        for (footprint, location) in footprints.into_iter().zip(locations) {
+            let location = FieldValue::StringValue(location);
+            layer.create_feature_fields(footprint, &["location"], &[location])?;
+        }
+    }
+    return Ok(tiles);
+}
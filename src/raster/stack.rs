@@ -0,0 +1,127 @@
+use std::path::Path;
+use raster::{Dataset, Window, RasterSize};
+use raster::types::GdalType;
+use spatial_ref::SpatialRef;
+use GdalError;
+
+/// A 3D block of pixels read from a `RasterStack`: `size.2` layers of
+/// `size.0` by `size.1` pixels, stored layer by layer, then row by row.
+pub struct CubeBuffer<T: GdalType> {
+    /// (columns, rows, layers)
+    pub size: (usize, usize, usize),
+    pub data: Vec<T>,
+}
+
+impl<T: GdalType + Copy> CubeBuffer<T> {
+    /// The value at column `x`, row `y` of layer `t`.
+    pub fn get(&self, x: usize, y: usize, t: usize) -> T {
+        assert!(x < self.size.0 && y < self.size.1 && t < self.size.2);
+        return self.data[(t * self.size.1 + y) * self.size.0 + x];
+    }
+
+    /// The values of all layers at column `x`, row `y`.
+    pub fn series(&self, x: usize, y: usize) -> Vec<T> {
+        return (0..self.size.2).map(|t| self.get(x, y, t)).collect();
+    }
+}
+
+/// A list of co-registered datasets, e.g. the scenes of a time series,
+/// read as one cube. Every dataset must have the same size, band count,
+/// geo-transform and coordinate system; use `align` to bring them onto a
+/// common grid first.
+pub struct RasterStack {
+    datasets: Vec<Dataset>,
+}
+
+impl RasterStack {
+    /// Stack `datasets` in the given order, checking that they're aligned.
+    pub fn new(datasets: Vec<Dataset>) -> Result<RasterStack, GdalError> {
+        if let Some(first) = datasets.first() {
+            for dataset in &datasets {
+                if dataset.size() != first.size() || dataset.count() != first.count() {
+                    return Err(GdalError::CallFailed{desc: "datasets don't have the same size"});
+                }
+                if dataset.geo_transform() != first.geo_transform() {
+                    return Err(GdalError::CallFailed{desc: "datasets are not aligned"});
+                }
+                if !same_projection(&dataset.projection(), &first.projection()) {
+                    return Err(GdalError::CallFailed{desc: "datasets have different coordinate systems"});
+                }
+            }
+        }
+        return Ok(RasterStack{datasets: datasets});
+    }
+
+    /// Open every file of `paths` and stack them.
+    pub fn open(paths: &[&Path]) -> Result<RasterStack, GdalError> {
+        let mut datasets = vec!();
+        for path in paths {
+            match Dataset::open(path) {
+                Some(dataset) => datasets.push(dataset),
+                None => return Err(GdalError::CallFailed{desc: "failed to open dataset"}),
+            }
+        }
+        return RasterStack::new(datasets);
+    }
+
+    pub fn datasets(&self) -> &[Dataset] {
+        return &self.datasets;
+    }
+
+    /// Number of datasets in the stack.
+    pub fn len(&self) -> usize {
+        return self.datasets.len();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return self.datasets.is_empty();
+    }
+
+    /// Size of every dataset in pixels.
+    pub fn size(&self) -> Option<(isize, isize)> {
+        return self.datasets.first().map(|dataset| dataset.size());
+    }
+
+    /// Read band `band_index` at pixel (`x`, `y`) of every dataset, in
+    /// stack order. Fails if the pixel lies outside of a dataset.
+    pub fn read_pixel_series<T: Copy + GdalType>(&self, band_index: isize, x: usize, y: usize) -> Result<Vec<T>, GdalError> {
+        let window = Window::new(x, y, 1, 1);
+        return self.datasets.iter()
+            .map(|dataset| dataset.read_raster_as::<T>(band_index, window, window.size()).map(|buffer| buffer.data[0]))
+            .collect();
+    }
+
+    /// Read the same window of band `band_index` from every dataset into
+    /// a cube with one layer per dataset.
+    /// # Arguments
+    /// * band_index - the band_index
+    /// * window - the window to read
+    /// * size - the desired size of each layer of the cube (GDAL will interpolate data if it differs from the window size)
+    ///
+    /// Fails for the same reasons as `Dataset::read_raster_as`.
+    pub fn read_cube<T: Copy + GdalType>(
+        &self,
+        band_index: isize,
+        window: Window,
+        size: RasterSize,
+    ) -> Result<CubeBuffer<T>, GdalError>
+    {
+        let mut data = Vec::new();
+        for dataset in &self.datasets {
+            data.extend(dataset.read_raster_as::<T>(band_index, window, size)?.data);
+        }
+        return Ok(CubeBuffer{size: (size.width, size.height, self.datasets.len()), data: data});
+    }
+}
+
+/// Whether the WKT projections `a` and `b` describe the same coordinate
+/// system, even if written differently.
+fn same_projection(a: &str, b: &str) -> bool {
+    if a == b {
+        return true;
+    }
+    return match (SpatialRef::from_wkt(a), SpatialRef::from_wkt(b)) {
+        (Ok(a), Ok(b)) => a.is_same(&b),
+        _ => false,
+    };
+}
@@ -0,0 +1,130 @@
+//! Choosing windows for reading whole rasters
+//!
+//! How fast a raster can be read depends on how it's stored: tiled or in
+//! strips, compressed or not, with the bands of a pixel together or one
+//! band after the other. `Dataset::layout` reports these facts and
+//! `Dataset::read_strategy` turns them into windows that read every block
+//! once, in the order it's stored.
+//!
+//! ```no_run
+//! use std::path::Path;
+//! use gdal::raster::{Dataset, Window};
+//!
+//! let dataset = Dataset::open(Path::new("ortho.tif")).unwrap();
+//! let strategy = dataset.read_strategy(16 << 20).unwrap();
+//! for (window, window_size) in strategy.windows() {
+//!     let window = Window::from_tuples(window, window_size);
+//!     let buffer = dataset.read_raster(1, window, window.size()).unwrap();
+//!     // process the buffer
+//! }
+//! ```
+
+use libc::c_int;
+use raster::gdal;
+use raster::gdal_enums::GDALDataType;
+use raster::window::{Windows, WindowOrder};
+
+/// How the bands of a multi band raster are stored, the `INTERLEAVE`
+/// structure metadata of GDAL.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Interleave {
+    /// The values of all bands of a pixel are stored together, so a block
+    /// holds every band.
+    Pixel,
+    /// Each row holds all bands one after the other.
+    Line,
+    /// Each band is stored separately.
+    Band,
+    /// The driver doesn't say.
+    Unknown,
+}
+
+/// Structural facts about the storage of a raster, see `Dataset::layout`.
+/// The block size, type and overviews are those of the first band.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RasterLayout {
+    pub size: (usize, usize),
+    pub band_count: usize,
+    pub band_type: GDALDataType,
+    pub block_size: (usize, usize),
+    /// Whether blocks are tiles rather than strips spanning the raster
+    /// width.
+    pub tiled: bool,
+    /// The compression method, e.g. `"DEFLATE"`, `None` for uncompressed
+    /// rasters or if the driver doesn't say.
+    pub compression: Option<String>,
+    pub interleave: Interleave,
+    pub overview_count: usize,
+}
+
+impl RasterLayout {
+    /// Size of a value of `band_type` in bytes.
+    pub fn value_bytes(&self) -> usize {
+        let bits = unsafe { gdal::GDALGetDataTypeSize(self.band_type as c_int) } as usize;
+        return (bits / 8).max(1);
+    }
+}
+
+/// Windows recommended by `Dataset::read_strategy`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ReadStrategy {
+    pub window_size: (usize, usize),
+    pub order: WindowOrder,
+    /// Whether the windows are whole blocks, see `Windows::new`.
+    pub align_to_blocks: bool,
+    /// Whether to read all bands of a window in one call with
+    /// `Dataset::read_into`, as their values are stored together.
+    pub all_bands: bool,
+    raster_size: (usize, usize),
+    block_size: (usize, usize),
+}
+
+impl ReadStrategy {
+    /// Recommend windows for reading a whole raster of `layout` with at
+    /// most about `max_bytes` per window, see `Dataset::read_strategy`.
+    ///
+    /// Windows are made of whole blocks, several blocks of a row of tiles
+    /// or several strips at a time, read in storage order. Blocks larger
+    /// than `max_bytes` are split into windows of whole rows, visited one
+    /// block after the other for compressed rasters so each block is only
+    /// decoded once (with GDAL's block cache large enough for one block).
+    /// Pixel interleaved bands are read together.
+    pub fn for_layout(layout: &RasterLayout, max_bytes: usize) -> ReadStrategy {
+        let all_bands = layout.band_count > 1 && layout.interleave == Interleave::Pixel;
+        let pixel_bytes = layout.value_bytes() * match all_bands {
+            true  => layout.band_count,
+            false => 1,
+        };
+        let (size_x, size_y) = (layout.size.0.max(1), layout.size.1.max(1));
+        let (block_x, block_y) = (layout.block_size.0.max(1).min(size_x), layout.block_size.1.max(1).min(size_y));
+        let block_bytes = block_x * block_y * pixel_bytes;
+
+        let mut strategy = ReadStrategy{
+            window_size: (block_x, block_y),
+            order: WindowOrder::RowMajor,
+            align_to_blocks: true,
+            all_bands: all_bands,
+            raster_size: layout.size,
+            block_size: (block_x, block_y),
+        };
+        if block_bytes >= max_bytes {
+            let rows = (max_bytes / (block_x * pixel_bytes)).max(1).min(block_y);
+            strategy.window_size = (block_x, rows);
+            strategy.align_to_blocks = false;
+            if layout.compression.is_some() {
+                strategy.order = WindowOrder::BlockMajor;
+            }
+        } else {
+            let blocks = max_bytes / block_bytes;
+            let blocks_x = blocks.min((size_x + block_x - 1) / block_x);
+            let blocks_y = (blocks / blocks_x).min((size_y + block_y - 1) / block_y);
+            strategy.window_size = ((blocks_x * block_x).min(size_x), (blocks_y * block_y).min(size_y));
+        }
+        return strategy;
+    }
+
+    /// The windows covering the raster.
+    pub fn windows(&self) -> Windows {
+        return Windows::new(self.raster_size, self.block_size, self.window_size, self.order, self.align_to_blocks);
+    }
+}
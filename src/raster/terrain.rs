@@ -0,0 +1,164 @@
+//! Terrain derivatives of in-memory elevation buffers
+//!
+//! These compute the same slope, aspect and hillshade as `gdaldem`, with
+//! Horn's method, but on a `Buffer` and its geo-transform instead of files,
+//! so interactive applications don't need temporary datasets.
+//!
+//! ```no_run
+//! use std::path::Path;
+//! use gdal::raster::{Dataset, Window, RasterSize};
+//! use gdal::raster::terrain::{hillshade, TerrainOptions};
+//!
+//! let dataset = Dataset::open(Path::new("dem.tif")).unwrap();
+//! let band = dataset.rasterband(1).unwrap();
+//! let (size_x, size_y) = band.size();
+//! let size = RasterSize::new(size_x, size_y);
+//! let dem = band.to_physical(&band.read_as::<f32>(Window::full(size), size).unwrap());
+//! let options = TerrainOptions::new().z_factor(2.).azimuth(270.);
+//! let shaded = hillshade(&dem, &dataset.geo_transform().unwrap(), &options);
+//! ```
+
+use raster::Buffer;
+use raster::dataset::GeoTransform;
+use raster::types::GdalType;
+
+/// Options for `slope`, `aspect` and `hillshade`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TerrainOptions {
+    z_factor: f64,
+    scale: f64,
+    azimuth: f64,
+    altitude: f64,
+}
+
+impl Default for TerrainOptions {
+    fn default() -> TerrainOptions {
+        TerrainOptions{z_factor: 1., scale: 1., azimuth: 315., altitude: 45.}
+    }
+}
+
+impl TerrainOptions {
+    pub fn new() -> TerrainOptions {
+        return TerrainOptions::default();
+    }
+
+    /// Vertical exaggeration, elevations are multiplied with this factor.
+    pub fn z_factor(mut self, z_factor: f64) -> TerrainOptions {
+        self.z_factor = z_factor;
+        return self;
+    }
+
+    /// Ratio of horizontal to vertical units, e.g. 111120 for a DEM in
+    /// degrees with elevations in meters.
+    pub fn scale(mut self, scale: f64) -> TerrainOptions {
+        self.scale = scale;
+        return self;
+    }
+
+    /// Direction the light comes from for `hillshade`, in degrees clockwise
+    /// from north. 315 (north-west) by default.
+    pub fn azimuth(mut self, azimuth: f64) -> TerrainOptions {
+        self.azimuth = azimuth;
+        return self;
+    }
+
+    /// Angle of the light above the horizon for `hillshade`, in degrees.
+    /// 45 by default.
+    pub fn altitude(mut self, altitude: f64) -> TerrainOptions {
+        self.altitude = altitude;
+        return self;
+    }
+}
+
+/// Slope of every pixel of `dem` in degrees, 0 for flat terrain.
+///
+/// Pixels on the raster edges use neighbors extrapolated from the inner
+/// ones. NaN elevations, e.g. nodata converted by `RasterBand::to_physical`,
+/// make the derivatives of their neighbors NaN too.
+pub fn slope<T: Copy + GdalType + Into<f64>>(
+    dem: &Buffer<T>,
+    transform: &GeoTransform,
+    options: &TerrainOptions,
+) -> Buffer<f32> {
+    return derive(dem, transform, options, |dz_dx, dz_dy| {
+        dz_dx.hypot(dz_dy).atan().to_degrees()
+    });
+}
+
+/// Direction every pixel of `dem` faces, i.e. the downhill direction, in
+/// degrees clockwise from north. NaN for flat terrain. See `slope` for
+/// edges and nodata.
+pub fn aspect<T: Copy + GdalType + Into<f64>>(
+    dem: &Buffer<T>,
+    transform: &GeoTransform,
+    options: &TerrainOptions,
+) -> Buffer<f32> {
+    return derive(dem, transform, options, |dz_dx, dz_dy| {
+        if dz_dx == 0. && dz_dy == 0. {
+            return f64::NAN;
+        }
+        let aspect = (-dz_dx).atan2(-dz_dy).to_degrees();
+        match aspect < 0. {
+            true => aspect + 360.,
+            false => aspect,
+        }
+    });
+}
+
+/// Shaded relief of `dem` lit from the azimuth and altitude of `options`,
+/// from 0 (in shadow) to 255 (facing the light). See `slope` for edges and
+/// nodata.
+pub fn hillshade<T: Copy + GdalType + Into<f64>>(
+    dem: &Buffer<T>,
+    transform: &GeoTransform,
+    options: &TerrainOptions,
+) -> Buffer<f32> {
+    let azimuth = options.azimuth.to_radians();
+    let altitude = options.altitude.to_radians();
+    // direction towards the light as (east, north, up)
+    let light = (azimuth.sin() * altitude.cos(), azimuth.cos() * altitude.cos(), altitude.sin());
+    return derive(dem, transform, options, |dz_dx, dz_dy| {
+        let norm = (dz_dx * dz_dx + dz_dy * dz_dy + 1.).sqrt();
+        let shade = (-dz_dx * light.0 - dz_dy * light.1 + light.2) / norm;
+        shade.max(0.) * 255.
+    });
+}
+
+/// Apply `f` to the east and north gradients of every pixel of `dem`,
+/// computed with Horn's method.
+fn derive<T, F>(dem: &Buffer<T>, transform: &GeoTransform, options: &TerrainOptions, f: F) -> Buffer<f32>
+    where T: Copy + GdalType + Into<f64>, F: Fn(f64, f64) -> f64
+{
+    let (size_x, size_y) = dem.size;
+    let value = |x: usize, y: usize| -> f64 { dem.data[y * size_x + x].into() * options.z_factor };
+    // neighbor (x + dx, y + dy), extrapolated from the opposite neighbor
+    // outside the raster
+    let neighbor = |x: usize, y: usize, dx: isize, dy: isize| -> f64 {
+        let inside = |dx: isize, dy: isize| {
+            let (nx, ny) = (x as isize + dx, y as isize + dy);
+            match nx >= 0 && ny >= 0 && (nx as usize) < size_x && (ny as usize) < size_y {
+                true => Some(value(nx as usize, ny as usize)),
+                false => None,
+            }
+        };
+        return match (inside(dx, dy), inside(-dx, -dy)) {
+            (Some(z), _) => z,
+            (None, Some(opposite)) => 2. * value(x, y) - opposite,
+            (None, None) => value(x, y),
+        };
+    };
+    let ew_res = transform[1] * options.scale;
+    let ns_res = transform[5] * options.scale;
+
+    let mut data = Vec::with_capacity(size_x * size_y);
+    for y in 0..size_y {
+        for x in 0..size_x {
+            let z = |dx, dy| neighbor(x, y, dx, dy);
+            let dz_dx = ((z(1, -1) + 2. * z(1, 0) + z(1, 1)) - (z(-1, -1) + 2. * z(-1, 0) + z(-1, 1))) / (8. * ew_res);
+            // rows go south for north-up rasters, where ns_res is negative
+            let dz_dy = ((z(-1, 1) + 2. * z(0, 1) + z(1, 1)) - (z(-1, -1) + 2. * z(0, -1) + z(1, -1))) / (8. * ns_res);
+            data.push(f(dz_dx, dz_dy) as f32);
+        }
+    }
+    return Buffer::new(dem.size, data);
+}
@@ -1,6 +1,10 @@
 use std::path::Path;
-use super::{ByteBuffer, Driver, Dataset};
+use super::{ByteBuffer, Driver, Dataset, DatasetCache, OverviewLocation, RetileOptions, Windows, Window, RasterSize, WindowOrder, extract_chips, retile};
 use super::gdal_enums::{GDALDataType};
+use super::rasterband::{GMF_ALL_VALID, GMF_NODATA};
+use super::metadata::{Exif, Rpc, Imd};
+use super::geotiff::{GeoTiffOptions, CogOptions, Compression, Predictor, BigTiff};
+use config::NumThreads;
 
 
 macro_rules! fixture {
@@ -56,10 +60,9 @@ fn test_read_raster() {
     let dataset = Dataset::open(fixture!("tinymarble.png")).unwrap();
     let rv = dataset.read_raster(
         1,
-        (20, 30),
-        (2, 3),
-        (2, 3)
-    );
+        Window::new(20, 30, 2, 3),
+        RasterSize::new(2, 3)
+    ).unwrap();
     assert_eq!(rv.size.0, 2);
     assert_eq!(rv.size.1, 3);
     assert_eq!(rv.data, vec!(7, 7, 7, 10, 8, 12));
@@ -80,27 +83,24 @@ fn test_write_raster() {
     // epand it to fill the image (20x10)
     dataset.write_raster(
         1,
-        (0, 0),
-        (20, 10),
+        Window::new(0, 0, 20, 10),
         raster
-    );
+    ).unwrap();
 
     // read a pixel from the left side
     let left = dataset.read_raster(
         1,
-        (5, 5),
-        (1, 1),
-        (1, 1)
-    );
+        Window::new(5, 5, 1, 1),
+        RasterSize::new(1, 1)
+    ).unwrap();
     assert_eq!(left.data[0], 50u8);
 
     // read a pixel from the right side
     let right = dataset.read_raster(
         1,
-        (15, 5),
-        (1, 1),
-        (1, 1)
-    );
+        Window::new(15, 5, 1, 1),
+        RasterSize::new(1, 1)
+    ).unwrap();
     assert_eq!(right.data[0], 20u8);
 }
 
@@ -170,10 +170,9 @@ fn test_read_raster_as() {
     let dataset = Dataset::open(fixture!("tinymarble.png")).unwrap();
     let rv = dataset.read_raster_as::<u8>(
         1,
-        (20, 30),
-        (2, 3),
-        (2, 3)
-    );
+        Window::new(20, 30, 2, 3),
+        RasterSize::new(2, 3)
+    ).unwrap();
     assert_eq!(rv.data, vec!(7, 7, 7, 10, 8, 12));
     assert_eq!(rv.size.0, 2);
     assert_eq!(rv.size.1, 3);
@@ -183,9 +182,10 @@ fn test_read_raster_as() {
 #[test]
 fn test_read_full_raster_as() {
     let dataset = Dataset::open(fixture!("tinymarble.png")).unwrap();
-    let rv = dataset.read_full_raster_as::<u8>(1);
-    assert_eq!(rv.size.0, 50);
+    let rv = dataset.read_full_raster_as::<u8>(1).unwrap();
+    assert_eq!(rv.size.0, 100);
     assert_eq!(rv.size.1, 50);
+    assert_eq!(rv.data.len(), 100 * 50);
     assert_eq!(dataset.get_band_type(1), Some(GDALDataType::GDT_Byte));
     //TODO: find a value to assert?
 }
@@ -197,3 +197,1256 @@ fn test_get_band_type() {
     assert_eq!(dataset.get_band_type(1), Some(GDALDataType::GDT_Byte));
     assert_eq!(dataset.get_band_type(2), None);
 }
+
+#[test]
+fn test_rasterband_overviews_and_mask() {
+    let dataset = Dataset::open(fixture!("tinymarble.png")).unwrap();
+    assert!(dataset.rasterband(4).is_none());
+    let band = dataset.rasterband(1).unwrap();
+    assert_eq!(band.size(), (100, 50));
+    assert_eq!(band.overview_count(), 0);
+    assert!(band.overview(0).is_none());
+    assert!(!band.has_arbitrary_overviews());
+    assert_eq!(band.get_overview_for_size(10, 5).size(), (100, 50));
+
+    let mask = band.mask_band();
+    assert_eq!(mask.size(), (100, 50));
+    assert_eq!(band.mask_flags() & GMF_ALL_VALID, GMF_ALL_VALID);
+    assert_eq!(mask.overview_count(), 0);
+}
+
+#[test]
+fn test_windows_order() {
+    let row_major: Vec<_> = Windows::new((4, 4), (4, 1), (2, 2), WindowOrder::RowMajor, false).collect();
+    assert_eq!(row_major, vec!(
+        ((0, 0), (2, 2)), ((2, 0), (2, 2)),
+        ((0, 2), (2, 2)), ((2, 2), (2, 2)),
+    ));
+
+    // 2x2 blocks, 1x1 windows: each block is visited completely first
+    let block_major: Vec<_> = Windows::new((4, 2), (2, 2), (1, 1), WindowOrder::BlockMajor, false)
+        .map(|(offset, _)| offset)
+        .collect();
+    assert_eq!(block_major, vec!(
+        (0, 0), (1, 0), (0, 1), (1, 1),
+        (2, 0), (3, 0), (2, 1), (3, 1),
+    ));
+
+    let hilbert: Vec<_> = Windows::new((2, 2), (2, 2), (1, 1), WindowOrder::Hilbert, false)
+        .map(|(offset, _)| offset)
+        .collect();
+    assert_eq!(hilbert, vec!((0, 0), (0, 1), (1, 1), (1, 0)));
+}
+
+#[test]
+fn test_windows_align_to_blocks() {
+    let windows: Vec<_> = Windows::new((100, 50), (64, 16), (50, 20), WindowOrder::RowMajor, true).collect();
+    assert_eq!(windows, vec!(
+        ((0, 0), (64, 32)), ((64, 0), (36, 32)),
+        ((0, 32), (64, 18)), ((64, 32), (36, 18)),
+    ));
+
+    let dataset = Dataset::open(fixture!("tinymarble.png")).unwrap();
+    let band = dataset.rasterband(1).unwrap();
+    assert_eq!(band.block_size(), (100, 1));
+    let total: usize = band.windows((30, 30), WindowOrder::Hilbert, false)
+        .map(|(_, size)| size.0 * size.1)
+        .sum();
+    assert_eq!(total, 100 * 50);
+}
+
+#[test]
+fn test_windows_zero_size() {
+    let windows: Vec<_> = Windows::new((2, 2), (0, 0), (0, 1), WindowOrder::RowMajor, true).collect();
+    assert_eq!(windows, vec!(
+        ((0, 0), (1, 1)), ((1, 0), (1, 1)),
+        ((0, 1), (1, 1)), ((1, 1), (1, 1)),
+    ));
+    assert_eq!(Windows::new((0, 0), (1, 1), (0, 0), WindowOrder::Hilbert, false).count(), 0);
+}
+
+#[test]
+fn test_windows_with_partial() {
+    let windows = Windows::new((100, 50), (64, 16), (50, 20), WindowOrder::RowMajor, true);
+    assert_eq!(windows.window_size(), (64, 32));
+    let partial: Vec<_> = windows.with_partial().map(|(_, _, partial)| partial).collect();
+    assert_eq!(partial, vec!(false, true, true, true));
+    let exact = Windows::new((4, 4), (4, 1), (2, 2), WindowOrder::RowMajor, false);
+    assert!(exact.with_partial().all(|(_, _, partial)| !partial));
+}
+
+#[test]
+fn test_dataset_cache() {
+    use OpenFlags;
+
+    let mut cache = DatasetCache::new();
+    assert!(cache.is_empty());
+    let first = cache.open(fixture!("tinymarble.png")).unwrap();
+    let second = cache.open(fixture!("tinymarble.png")).unwrap();
+    assert_eq!(cache.len(), 1);
+    assert_eq!(unsafe { first._c_ptr() }, unsafe { second._c_ptr() });
+    assert!(cache.open(fixture!("no_such_file.png")).is_none());
+    assert_eq!(cache.len(), 1);
+
+    // other open options give another handle
+    let options = ["GEOREF_SOURCES=INTERNAL".to_string()];
+    let with_options = cache.open_ex(fixture!("tinymarble.png"), OpenFlags::READ_ONLY, &options).unwrap();
+    assert_eq!(cache.len(), 2);
+    assert!(unsafe { with_options._c_ptr() } != unsafe { first._c_ptr() });
+    let again = cache.open_ex(fixture!("tinymarble.png"), OpenFlags::READ_ONLY, &options).unwrap();
+    assert_eq!(unsafe { again._c_ptr() }, unsafe { with_options._c_ptr() });
+    assert!(cache.remove(fixture!("tinymarble.png")));
+    assert!(cache.is_empty());
+
+    drop(first);
+    drop(second);
+    let first = cache.open(fixture!("tinymarble.png")).unwrap();
+    drop(first);
+    cache.release_unused();
+    assert!(cache.is_empty());
+}
+
+#[test]
+fn test_file_list() {
+    let dataset = Dataset::open(fixture!("tinymarble.png")).unwrap();
+    let file_list = dataset.file_list();
+    assert_eq!(file_list.len(), 2);
+    assert!(file_list[0].ends_with("tinymarble.png"));
+    assert!(file_list[1].ends_with("tinymarble.png.aux.xml"));
+
+    let driver = Driver::get("MEM").unwrap();
+    let dataset = driver.create("", 20, 10, 1).unwrap();
+    assert!(dataset.file_list().is_empty());
+}
+
+#[test]
+fn test_description() {
+    let dataset = Dataset::open(fixture!("tinymarble.png")).unwrap();
+    assert!(dataset.description().ends_with("tinymarble.png"));
+
+    let driver = Driver::get("MEM").unwrap();
+    let dataset = driver.create("", 20, 10, 1).unwrap();
+    dataset.set_description("in memory").unwrap();
+    assert_eq!(dataset.description(), "in memory");
+}
+
+#[test]
+fn test_open_without_pam() {
+    // the projection of tinymarble.png comes from its .aux.xml file
+    let dataset = Dataset::open_with_pam(fixture!("tinymarble.png"), false).unwrap();
+    assert_eq!(dataset.projection(), "");
+    assert_eq!(dataset.file_list().len(), 1);
+
+    let dataset = Dataset::open_with_pam(fixture!("tinymarble.png"), true).unwrap();
+    assert!(dataset.projection().starts_with("GEOGCS"));
+}
+
+#[test]
+fn test_metadata() {
+    let dataset = Dataset::open(fixture!("tinymarble.png")).unwrap();
+    assert_eq!(dataset.metadata_item("INTERLEAVE", "IMAGE_STRUCTURE"), Some("PIXEL".to_string()));
+    assert_eq!(dataset.metadata_item("NO_SUCH_ITEM", ""), None);
+    let xmp = dataset.xmp().unwrap();
+    assert!(xmp.contains("<exif:PixelXDimension>100</exif:PixelXDimension>"));
+    assert!(dataset.rpc().is_none());
+    assert!(dataset.imd().is_none());
+
+    let driver = Driver::get("MEM").unwrap();
+    let dataset = driver.create("", 20, 10, 1).unwrap();
+    dataset.set_metadata_item("EXIF_Make", "Rusty", "").unwrap();
+    assert_eq!(dataset.metadata(""), vec!("EXIF_Make=Rusty".to_string()));
+    assert_eq!(dataset.exif().make(), Some("Rusty"));
+}
+
+#[test]
+fn test_parse_exif() {
+    let entries: Vec<String> = [
+        "EXIF_Model=Phantom",
+        "EXIF_GPSLatitude=(44) (25) (48)",
+        "EXIF_GPSLatitudeRef=S",
+        "EXIF_GPSLongitude=(26) (6) (0)",
+        "EXIF_GPSLongitudeRef=E",
+        "EXIF_GPSAltitude=(120.5)",
+        "NOT_EXIF=1",
+    ].iter().map(|s| s.to_string()).collect();
+    let exif = Exif::from_metadata(&entries);
+    assert_eq!(exif.tags.len(), 6);
+    assert_eq!(exif.model(), Some("Phantom"));
+    assert_eq!(exif.tag("EXIF_Model"), Some("Phantom"));
+    assert_eq!(exif.gps_position(), Some((26.1, -44.43)));
+    assert_eq!(exif.gps_altitude(), Some(120.5));
+}
+
+#[test]
+fn test_parse_rpc_and_imd() {
+    let coeff = (0..20).map(|i| format!("{}", i)).collect::<Vec<String>>().join(" ");
+    let mut entries: Vec<String> = [
+        "LINE_OFF=1", "SAMP_OFF=2", "LAT_OFF=3", "LONG_OFF=4", "HEIGHT_OFF=5",
+        "LINE_SCALE=6", "SAMP_SCALE=7", "LAT_SCALE=8", "LONG_SCALE=9", "HEIGHT_SCALE=10",
+    ].iter().map(|s| s.to_string()).collect();
+    for key in ["LINE_NUM_COEFF", "LINE_DEN_COEFF", "SAMP_NUM_COEFF"].iter() {
+        entries.push(format!("{}={}", key, coeff));
+    }
+    assert!(Rpc::from_metadata(&entries).is_none());
+    entries.push(format!("SAMP_DEN_COEFF={}", coeff));
+    let rpc = Rpc::from_metadata(&entries).unwrap();
+    assert_eq!(rpc.long_off, 4.);
+    assert_eq!(rpc.height_scale, 10.);
+    assert_eq!(rpc.samp_den_coeff[19], 19.);
+
+    let entries: Vec<String> = [
+        "IMAGE_1.satId=\"WV02\"",
+        "IMAGE_1.cloudCover=0.012",
+    ].iter().map(|s| s.to_string()).collect();
+    let imd = Imd::from_metadata(&entries);
+    assert_eq!(imd.satellite_id(), Some("WV02"));
+    assert_eq!(imd.cloud_cover(), Some(0.012));
+}
+
+#[test]
+fn test_geotiff_options() {
+    let options = GeoTiffOptions::new()
+        .compression(Compression::Deflate)
+        .predictor(Predictor::Horizontal)
+        .zlevel(6)
+        .tiled(32, 16)
+        .bigtiff(BigTiff::IfSafer)
+        .num_threads(NumThreads::AllCpus);
+    assert_eq!(options.options().unwrap(), vec!(
+        "COMPRESS=DEFLATE", "PREDICTOR=2", "ZLEVEL=6",
+        "TILED=YES", "BLOCKXSIZE=32", "BLOCKYSIZE=16", "BIGTIFF=IF_SAFER",
+        "NUM_THREADS=ALL_CPUS",
+    ));
+    assert!(GeoTiffOptions::new().options().unwrap().is_empty());
+    assert!(GeoTiffOptions::new().jpeg_quality(0).options().is_err());
+    assert!(GeoTiffOptions::new().jpeg_quality(101).options().is_err());
+    assert!(GeoTiffOptions::new().zlevel(10).options().is_err());
+    assert!(GeoTiffOptions::new().tiled(32, 20).options().is_err());
+    assert!(GeoTiffOptions::new().tiled(0, 16).options().is_err());
+
+    let options = CogOptions::new()
+        .compression(Compression::Zstd)
+        .block_size(256)
+        .num_threads(NumThreads::Count(4));
+    assert_eq!(options.options().unwrap(), vec!("COMPRESS=ZSTD", "BLOCKSIZE=256", "NUM_THREADS=4"));
+    assert!(CogOptions::new().options().unwrap().is_empty());
+    assert!(CogOptions::new().block_size(100).options().is_err());
+}
+
+#[test]
+fn test_create_with_geotiff_options() {
+    use std::fs;
+
+    let options = GeoTiffOptions::new()
+        .compression(Compression::Lzw)
+        .tiled(32, 32)
+        .options()
+        .unwrap();
+    {
+        let driver = Driver::get("GTiff").unwrap();
+        let dataset = driver.create_with_options::<u16>(fixture!("geotiff_options.tif").to_str().unwrap(), 64, 64, 1, &options).unwrap();
+        assert_eq!(dataset.rasterband(1).unwrap().block_size(), (32, 32));
+        assert_eq!(dataset.metadata_item("COMPRESSION", "IMAGE_STRUCTURE"), Some("LZW".to_string()));
+    }
+    fs::remove_file(fixture!("geotiff_options.tif")).unwrap();
+
+    let dataset = Dataset::open(fixture!("tinymarble.png")).unwrap();
+    let driver = Driver::get("GTiff").unwrap();
+    {
+        let copy = dataset.create_copy_with_options(driver, fixture!("geotiff_options.tif").to_str().unwrap(), &options).unwrap();
+        assert_eq!(copy.rasterband(1).unwrap().block_size(), (32, 32));
+    }
+    fs::remove_file(fixture!("geotiff_options.tif")).unwrap();
+}
+
+#[test]
+fn test_read_mask() {
+    let dataset = Dataset::open(fixture!("tinymarble.png")).unwrap();
+    let band = dataset.rasterband(1).unwrap();
+    assert_eq!(band.no_data_value(), None);
+    let mask = band.read_mask(Window::new(20, 30, 2, 3), RasterSize::new(2, 3)).unwrap();
+    assert_eq!(mask.size, (2, 3));
+    assert_eq!(mask.data, vec!(255u8; 6));
+
+    let driver = Driver::get("MEM").unwrap();
+    let dataset = driver.create("", 3, 1, 1).unwrap();
+    dataset.write_raster(1, Window::new(0, 0, 3, 1), ByteBuffer::new((3, 1), vec!(1u8, 0u8, 2u8))).unwrap();
+    let band = dataset.rasterband(1).unwrap();
+    band.set_no_data_value(0.).unwrap();
+    assert_eq!(band.no_data_value(), Some(0.));
+    assert_eq!(band.mask_flags(), GMF_NODATA);
+    assert_eq!(band.read_mask(Window::new(0, 0, 3, 1), RasterSize::new(3, 1)).unwrap().data, vec!(255u8, 0u8, 255u8));
+}
+
+#[test]
+fn test_build_overviews() {
+    use std::fs;
+
+    let tif = fixture!("overviews.tif").to_str().unwrap().to_string();
+    let driver = Driver::get("GTiff").unwrap();
+    Dataset::open(fixture!("tinymarble.png")).unwrap().create_copy(driver, &tif).unwrap();
+
+    {
+        let dataset = Dataset::open(fixture!("overviews.tif")).unwrap();
+        assert!(dataset.build_overviews("NEAREST", &[2], OverviewLocation::Internal).is_err());
+        dataset.build_overviews("NEAREST", &[2, 4], OverviewLocation::External).unwrap();
+        assert_eq!(dataset.rasterband(1).unwrap().overview_count(), 2);
+        assert_eq!(dataset.rasterband(1).unwrap().overview(0).unwrap().size(), (50, 25));
+        assert!(dataset.file_list().iter().any(|f| f.ends_with("overviews.tif.ovr")));
+        dataset.clean_overviews().unwrap();
+        assert_eq!(dataset.rasterband(1).unwrap().overview_count(), 0);
+    }
+    let _ = fs::remove_file(fixture!("overviews.tif.ovr"));
+
+    {
+        let dataset = Dataset::open_update(fixture!("overviews.tif")).unwrap();
+        dataset.build_overviews("AVERAGE", &[2], OverviewLocation::Internal).unwrap();
+        assert_eq!(dataset.rasterband(1).unwrap().overview_count(), 1);
+        assert_eq!(dataset.file_list().len(), 1);
+    }
+    fs::remove_file(fixture!("overviews.tif")).unwrap();
+}
+
+#[test]
+fn test_retile() {
+    use std::fs;
+    use vector;
+
+    let out_dir = fixture!("retile").to_path_buf();
+    fs::create_dir_all(&out_dir).unwrap();
+    let dataset = Dataset::open(fixture!("tinymarble.png")).unwrap();
+    let options = RetileOptions{
+        index: Some(out_dir.join("index.geojson")),
+        ..RetileOptions::default()
+    };
+    let tiles = retile(&dataset, &out_dir, (40, 40), &options).unwrap();
+    assert_eq!(tiles.len(), 6);
+    assert!(tiles[5].ends_with("tinymarble_1_2.tif"));
+
+    let last = Dataset::open(&tiles[5]).unwrap();
+    assert_eq!(last.size(), (20, 10));
+    assert_eq!(last.count(), 3);
+    let transform = dataset.geo_transform().unwrap();
+    let tile_transform = last.geo_transform().unwrap();
+    assert_eq!(tile_transform[0], transform[0] + 80. * transform[1]);
+    assert_eq!(tile_transform[3], transform[3] + 40. * transform[5]);
+    assert_eq!(
+        last.read_raster(2, Window::new(0, 0, 20, 10), RasterSize::new(20, 10)).unwrap().data,
+        dataset.read_raster(2, Window::new(80, 40, 20, 10), RasterSize::new(20, 10)).unwrap().data
+    );
+
+    let mut index = vector::Dataset::open(&out_dir.join("index.geojson")).unwrap();
+    let locations: Vec<String> = index.layer(0).unwrap().features()
+        .map(|f| f.field("location").unwrap().unwrap().as_string())
+        .collect();
+    assert_eq!(locations.len(), 6);
+    assert!(locations[0].ends_with("tinymarble_0_0.tif"));
+    fs::remove_dir_all(&out_dir).unwrap();
+}
+
+#[test]
+fn test_extract_chips() {
+    use vector;
+    use vector::{Geometry, FieldValue, OFT_STRING};
+
+    let dataset = Dataset::open(fixture!("tinymarble.png")).unwrap();
+    let transform = dataset.geo_transform().unwrap();
+    let world = |x: f64, y: f64| (transform[0] + x * transform[1], transform[3] + y * transform[5]);
+
+    let driver = vector::Driver::get("Memory").unwrap();
+    let mut samples = driver.create(Path::new("")).unwrap();
+    let layer = samples.create_layer().unwrap();
+    layer.create_defn_fields(&[("class", OFT_STRING)]).unwrap();
+    let (x, y) = world(20.5, 30.5);
+    layer.create_feature_fields(
+        Geometry::from_wkt(&format!("POINT ({} {})", x, y)).unwrap(),
+        &["class"],
+        &[FieldValue::StringValue("water".to_string())],
+    ).unwrap();
+    // too close to the edge for a full chip
+    let (x, y) = world(0.5, 0.5);
+    layer.create_feature_fields(
+        Geometry::from_wkt(&format!("POINT ({} {})", x, y)).unwrap(),
+        &["class"],
+        &[FieldValue::StringValue("edge".to_string())],
+    ).unwrap();
+
+    let chips = extract_chips::<u8>(&dataset, layer, (2, 3), Some("class")).unwrap();
+    assert_eq!(chips.len(), 1);
+    let chip = chips.into_iter().next().unwrap();
+    assert_eq!(chip.window, (19, 29));
+    assert_eq!(chip.label.unwrap().as_string(), "water");
+    assert_eq!(chip.bands.len(), 3);
+    assert_eq!(chip.bands[0].data, dataset.read_raster(1, Window::new(19, 29, 2, 3), RasterSize::new(2, 3)).unwrap().data);
+
+    let plain = Driver::get("MEM").unwrap().create("", 10, 10, 1).unwrap();
+    assert!(extract_chips::<u8>(&plain, layer, (2, 3), None).is_err());
+}
+
+#[test]
+fn test_zonal_stats() {
+    use vector;
+    use vector::Geometry;
+    use super::{Statistic, zonal_stats};
+
+    let dataset = Dataset::open(fixture!("tinymarble.png")).unwrap();
+    let transform = dataset.geo_transform().unwrap();
+    let world = |x: f64, y: f64| (transform[0] + x * transform[1], transform[3] + y * transform[5]);
+
+    let driver = vector::Driver::get("Memory").unwrap();
+    let mut zones = driver.create(Path::new("")).unwrap();
+    let layer = zones.create_layer().unwrap();
+    // a 4x2 pixel rectangle starting at pixel (10, 20)
+    let (x0, y0) = world(10., 20.);
+    let (x1, y1) = world(14., 22.);
+    layer.create_feature(Geometry::bbox(x0, y1, x1, y0)).unwrap();
+    // entirely outside of the raster
+    layer.create_feature(Geometry::bbox(x1 + 1000., y1, x1 + 1001., y0)).unwrap();
+
+    let band = dataset.rasterband(1).unwrap();
+    let all = [Statistic::Count, Statistic::Sum, Statistic::Mean, Statistic::Min, Statistic::Max, Statistic::Std];
+    let results = zonal_stats(&band, layer, &all).unwrap();
+    assert_eq!(results.len(), 2);
+
+    let values: Vec<f64> = band.read_as::<u8>(Window::new(10, 20, 4, 2), RasterSize::new(4, 2)).unwrap().data
+        .iter().map(|&v| v as f64).collect();
+    let sum: f64 = values.iter().sum();
+    let mean = sum / 8.;
+    let std = (values.iter().map(|v| (v - mean) * (v - mean)).sum::<f64>() / 8.).sqrt();
+    let inside = &results[&0];
+    assert_eq!(inside.count, Some(8));
+    assert_eq!(inside.sum, Some(sum));
+    assert_eq!(inside.mean, Some(mean));
+    assert_eq!(inside.min, Some(values.iter().cloned().fold(f64::INFINITY, f64::min)));
+    assert_eq!(inside.max, Some(values.iter().cloned().fold(f64::NEG_INFINITY, f64::max)));
+    assert!((inside.std.unwrap() - std).abs() < 1e-9);
+
+    let outside = &results[&1];
+    assert_eq!(outside.count, Some(0));
+    assert_eq!(outside.mean, None);
+
+    let only_count = zonal_stats(&band, layer, &[Statistic::Count]).unwrap();
+    assert_eq!(only_count[&0].count, Some(8));
+    assert_eq!(only_count[&0].sum, None);
+}
+
+#[test]
+fn test_sample() {
+    use vector;
+    use vector::Geometry;
+    use super::Interpolation;
+
+    let dataset = Dataset::open(fixture!("tinymarble.png")).unwrap();
+    let transform = dataset.geo_transform().unwrap();
+    let world = |x: f64, y: f64| (transform[0] + x * transform[1], transform[3] + y * transform[5]);
+    let band = dataset.rasterband(1).unwrap();
+    let pixels = band.read_as::<u8>(Window::new(10, 20, 2, 2), RasterSize::new(2, 2)).unwrap().data;
+
+    let points = [world(10.5, 20.5), world(11.25, 20.75), world(-1., 20.)];
+    let nearest = band.sample(&points, Interpolation::Nearest).unwrap();
+    assert_eq!(nearest, vec!(Some(pixels[0] as f64), Some(pixels[1] as f64), None));
+
+    let bilinear = band.sample(&points, Interpolation::Bilinear).unwrap();
+    assert_eq!(bilinear[0], Some(pixels[0] as f64));
+    let expected =
+        (pixels[0] as f64 * 0.25 + pixels[1] as f64 * 0.75) * 0.75 +
+        (pixels[2] as f64 * 0.25 + pixels[3] as f64 * 0.75) * 0.25;
+    assert!((bilinear[1].unwrap() - expected).abs() < 1e-9);
+    assert_eq!(bilinear[2], None);
+
+    let driver = vector::Driver::get("Memory").unwrap();
+    let mut samples = driver.create(Path::new("")).unwrap();
+    let layer = samples.create_layer().unwrap();
+    let (x, y) = world(10.5, 20.5);
+    layer.create_feature(Geometry::from_wkt(&format!("POINT ({} {})", x, y)).unwrap()).unwrap();
+    let sampled = band.sample_layer(layer, Interpolation::Nearest).unwrap();
+    assert_eq!(sampled, vec!((0, Some(pixels[0] as f64))));
+
+    let plain = Driver::get("MEM").unwrap().create("", 10, 10, 1).unwrap();
+    let plain_band = plain.rasterband(1).unwrap();
+    assert!(plain_band.sample(&points, Interpolation::Nearest).is_err());
+    assert!(plain_band.sample_layer(layer, Interpolation::Nearest).is_err());
+}
+
+#[test]
+fn test_profile() {
+    use vector::Geometry;
+    use super::Interpolation;
+
+    let dataset = Dataset::open(fixture!("tinymarble.png")).unwrap();
+    let transform = dataset.geo_transform().unwrap();
+    let world = |x: f64, y: f64| (transform[0] + x * transform[1], transform[3] + y * transform[5]);
+    let band = dataset.rasterband(1).unwrap();
+
+    // three pixels to the right, then two pixels down
+    let (x0, y0) = world(10.5, 20.5);
+    let (x1, y1) = world(13.5, 20.5);
+    let (x2, y2) = world(13.5, 22.5);
+    let line = Geometry::from_wkt(&format!("LINESTRING ({} {}, {} {}, {} {})", x0, y0, x1, y1, x2, y2)).unwrap();
+    let step = transform[1];
+    let profile = band.profile(&line, step).unwrap();
+
+    let expected_points = [(10.5, 20.5), (11.5, 20.5), (12.5, 20.5), (13.5, 20.5), (13.5, 21.5), (13.5, 22.5)];
+    assert_eq!(profile.len(), expected_points.len());
+    for (i, &(distance, value)) in profile.iter().enumerate() {
+        assert!((distance - i as f64 * step).abs() < 1e-9);
+        let (px, py) = expected_points[i];
+        let point = world(px, py);
+        assert_eq!(value, band.sample(&[point], Interpolation::Bilinear).unwrap()[0]);
+        assert_eq!(value, band.sample(&[point], Interpolation::Nearest).unwrap()[0]);
+    }
+    assert!(band.profile(&line, 0.).is_err());
+}
+
+#[test]
+fn test_align() {
+    use super::{align, GridSpec};
+
+    let dataset = Dataset::open(fixture!("tinymarble.png")).unwrap();
+    let own_grid = GridSpec::from_dataset(&dataset).unwrap();
+    assert_eq!(own_grid.size, (100, 50));
+    assert_eq!(own_grid.geo_transform(), dataset.geo_transform().unwrap());
+
+    let same = align(&dataset, &own_grid).unwrap();
+    assert_eq!(same.size(), (100, 50));
+    assert_eq!(same.count(), 3);
+    assert_eq!(
+        same.read_raster(2, Window::new(0, 0, 100, 50), RasterSize::new(100, 50)).unwrap().data,
+        dataset.read_raster(2, Window::new(0, 0, 100, 50), RasterSize::new(100, 50)).unwrap().data
+    );
+
+    let coarse_grid = GridSpec::from_extent((-90., -45., 90., 45.), 7.2, &dataset.projection());
+    assert_eq!(coarse_grid.size, (25, 13));
+    let coarse = align(&dataset, &coarse_grid).unwrap();
+    assert_eq!(coarse.size(), (25, 13));
+    assert_eq!(coarse.geo_transform().unwrap(), [-90., 7.2, 0., 45., 0., -7.2]);
+    assert_eq!(coarse.projection(), dataset.projection());
+}
+
+#[test]
+fn test_stack_bands() {
+    use std::fs;
+    use super::{stack_bands, StackOptions};
+
+    let dataset = Dataset::open(fixture!("tinymarble.png")).unwrap();
+    let red = dataset.rasterband(1).unwrap();
+    let blue = dataset.rasterband(3).unwrap();
+
+    let vrt = stack_bands(&[&blue, &red], fixture!("stack.vrt"), &StackOptions::default()).unwrap();
+    assert_eq!(vrt.count(), 2);
+    assert_eq!(vrt.size(), (100, 50));
+    assert_eq!(vrt.geo_transform(), dataset.geo_transform());
+    assert_eq!(
+        vrt.read_raster(1, Window::new(0, 0, 100, 50), RasterSize::new(100, 50)).unwrap().data,
+        dataset.read_raster(3, Window::new(0, 0, 100, 50), RasterSize::new(100, 50)).unwrap().data
+    );
+    assert_eq!(
+        vrt.read_raster(2, Window::new(0, 0, 100, 50), RasterSize::new(100, 50)).unwrap().data,
+        dataset.read_raster(1, Window::new(0, 0, 100, 50), RasterSize::new(100, 50)).unwrap().data
+    );
+
+    let options = StackOptions{driver: "GTiff".to_string(), ..StackOptions::default()};
+    let tiff = stack_bands(&[&blue, &red], fixture!("stack.tif"), &options).unwrap();
+    assert_eq!(tiff.count(), 2);
+    assert_eq!(
+        tiff.read_raster(2, Window::new(0, 0, 100, 50), RasterSize::new(100, 50)).unwrap().data,
+        dataset.read_raster(1, Window::new(0, 0, 100, 50), RasterSize::new(100, 50)).unwrap().data
+    );
+
+    let driver = Driver::get("MEM").unwrap();
+    let smaller = driver.create("", 10, 10, 1).unwrap();
+    let smaller_band = smaller.rasterband(1).unwrap();
+    assert!(stack_bands(&[&red, &smaller_band], fixture!("misaligned.vrt"), &StackOptions::default()).is_err());
+
+    // sources are referenced by absolute path, whatever they were opened with
+    let canonical = fs::canonicalize(fixture!("tinymarble.png")).unwrap();
+    assert!(super::stack_bands_vrt(&[&red]).unwrap().contains(canonical.to_str().unwrap()));
+
+    // MEM datasets can only be copied from
+    let memory = driver.create("", 10, 10, 2).unwrap();
+    memory.write_raster(2, Window::new(0, 0, 10, 10), ByteBuffer{size: (1, 1), data: vec!(7u8)}).unwrap();
+    let first = memory.rasterband(1).unwrap();
+    let second = memory.rasterband(2).unwrap();
+    assert!(stack_bands(&[&second, &first], fixture!("memory.vrt"), &StackOptions::default()).is_err());
+    let options = StackOptions{driver: "MEM".to_string(), ..StackOptions::default()};
+    let copy = stack_bands(&[&second, &first], Path::new(""), &options).unwrap();
+    assert_eq!(copy.count(), 2);
+    assert_eq!(copy.read_raster(1, Window::new(3, 3, 1, 1), RasterSize::new(1, 1)).unwrap().data, vec!(7));
+    assert_eq!(copy.read_raster(2, Window::new(3, 3, 1, 1), RasterSize::new(1, 1)).unwrap().data, vec!(0));
+
+    fs::remove_file(fixture!("stack.vrt")).unwrap();
+    fs::remove_file(fixture!("stack.tif")).unwrap();
+}
+
+#[test]
+fn test_raster_stack() {
+    use super::RasterStack;
+    use spatial_ref::SpatialRef;
+
+    let stack = RasterStack::open(&[fixture!("tinymarble.png"), fixture!("tinymarble.png")]).unwrap();
+    assert_eq!(stack.len(), 2);
+    assert_eq!(stack.size(), Some((100, 50)));
+    assert_eq!(stack.read_pixel_series::<u8>(1, 20, 30).unwrap(), vec!(7, 7));
+    assert!(stack.read_pixel_series::<u8>(1, 100, 30).is_err());
+
+    let cube = stack.read_cube::<u8>(1, Window::new(20, 30, 2, 3), RasterSize::new(2, 3)).unwrap();
+    assert_eq!(cube.size, (2, 3, 2));
+    assert_eq!(cube.data, vec!(7, 7, 7, 10, 8, 12, 7, 7, 7, 10, 8, 12));
+    assert_eq!(cube.get(0, 1, 1), 7);
+    assert_eq!(cube.series(1, 2), vec!(12, 12));
+
+    let driver = Driver::get("MEM").unwrap();
+    let other = driver.create("", 10, 10, 3).unwrap();
+    let tinymarble = Dataset::open(fixture!("tinymarble.png")).unwrap();
+    assert!(RasterStack::new(vec!(tinymarble, other)).is_err());
+
+    // same grid, different coordinate systems
+    let wgs84 = SpatialRef::from_epsg(4326).unwrap();
+    let mercator = SpatialRef::from_epsg(3857).unwrap();
+    let first = driver.create("", 10, 10, 1).unwrap();
+    first.set_projection(&wgs84.to_wkt().unwrap()).unwrap();
+    let second = driver.create("", 10, 10, 1).unwrap();
+    second.set_projection(&mercator.to_wkt().unwrap()).unwrap();
+    assert!(RasterStack::new(vec!(first, second)).is_err());
+    let first = driver.create("", 10, 10, 1).unwrap();
+    first.set_projection(&wgs84.to_wkt().unwrap()).unwrap();
+    let second = driver.create("", 10, 10, 1).unwrap();
+    second.set_projection(&wgs84.to_wkt().unwrap()).unwrap();
+    assert!(RasterStack::new(vec!(first, second)).is_ok());
+}
+
+#[test]
+fn test_grid_create() {
+    use super::grid::{grid_create, GridAlgorithm, GridMetric, InverseDistanceOptions, LinearOptions, NearestNeighborOptions, SearchOptions};
+
+    // one point in the north, one in the south
+    let (x, y, z) = ([5., 5.], [9., 1.], [1., 2.]);
+    let nearest = GridAlgorithm::NearestNeighbor(NearestNeighborOptions::default());
+    let buffer = grid_create::<f64>(&nearest, &x, &y, &z, (0., 0., 10., 10.), (2, 2)).unwrap();
+    assert_eq!(buffer.size, (2, 2));
+    assert_eq!(buffer.data, vec!(1., 1., 2., 2.));
+
+    let idw = GridAlgorithm::InverseDistance(InverseDistanceOptions::default());
+    let buffer = grid_create::<f32>(&idw, &x, &y, &z, (0., 0., 10., 10.), (1, 1)).unwrap();
+    assert!((buffer.data[0] - 1.5).abs() < 1e-6);
+
+    let count = GridAlgorithm::Metric(GridMetric::Count, SearchOptions{radius1: 20., radius2: 20., ..Default::default()});
+    let buffer = grid_create::<u8>(&count, &x, &y, &z, (0., 0., 10., 10.), (1, 1)).unwrap();
+    assert_eq!(buffer.data, vec!(2));
+
+    assert!(grid_create::<f64>(&nearest, &x, &y, &[1.], (0., 0., 10., 10.), (2, 2)).is_err());
+
+    assert_eq!(nearest.options(), "nearest:radius1=0:radius2=0:angle=0:nodata=0");
+    assert_eq!(count.options(), "count:radius1=20:radius2=20:angle=0:min_points=0:nodata=0");
+    let linear = GridAlgorithm::Linear(LinearOptions{radius: -1., no_data: 0.5});
+    assert_eq!(linear.options(), "linear:radius=-1:nodata=0.5");
+}
+
+#[test]
+fn test_reproject_to_match() {
+    use super::{align, reproject_to_match, reproject_to_match_with_options, GDALResampleAlg, GridSpec, WarpOptions};
+
+    let dataset = Dataset::open(fixture!("tinymarble.png")).unwrap();
+    let coarse_grid = GridSpec::from_extent((-90., -45., 90., 45.), 7.2, &dataset.projection());
+    let reference = align(&dataset, &coarse_grid).unwrap();
+
+    let matched = reproject_to_match(&dataset, &reference, GDALResampleAlg::GRA_Average).unwrap();
+    assert_eq!(matched.size(), reference.size());
+    assert_eq!(matched.count(), 3);
+    assert_eq!(matched.geo_transform(), reference.geo_transform());
+    assert_eq!(matched.projection(), reference.projection());
+
+    let nearest = reproject_to_match(&dataset, &dataset, GDALResampleAlg::GRA_NearestNeighbour).unwrap();
+    assert_eq!(
+        nearest.read_raster(1, Window::new(0, 0, 100, 50), RasterSize::new(100, 50)).unwrap().data,
+        dataset.read_raster(1, Window::new(0, 0, 100, 50), RasterSize::new(100, 50)).unwrap().data
+    );
+
+    let options = WarpOptions::new().num_threads(NumThreads::Count(2));
+    assert_eq!(options.options(), vec!(("NUM_THREADS".to_string(), "2".to_string())));
+    let threaded = reproject_to_match_with_options(&dataset, &reference, GDALResampleAlg::GRA_Average, &options).unwrap();
+    assert_eq!(
+        threaded.read_raster(1, Window::new(0, 0, 25, 13), RasterSize::new(25, 13)).unwrap().data,
+        matched.read_raster(1, Window::new(0, 0, 25, 13), RasterSize::new(25, 13)).unwrap().data
+    );
+}
+
+#[test]
+fn test_statistics() {
+    use super::{BandStatistics, Buffer};
+
+    let driver = Driver::get("MEM").unwrap();
+    let dataset = driver.create_with_band_type::<f64>("", 2, 2, 1).unwrap();
+    dataset.write_raster(1, Window::new(0, 0, 2, 2), Buffer::new((2, 2), vec!(1., 2., 3., 4.))).unwrap();
+    let band = dataset.rasterband(1).unwrap();
+    assert_eq!(band.statistics(false), None);
+
+    let statistics = band.compute_statistics(false).unwrap();
+    assert_eq!((statistics.min, statistics.max, statistics.mean), (1., 4., 2.5));
+    assert!((statistics.std_dev - 1.25f64.sqrt()).abs() < 1e-9);
+    assert_eq!(band.statistics(false), Some(statistics));
+
+    let stored = BandStatistics{min: 0., max: 10., mean: 5., std_dev: 1.};
+    band.set_statistics(&stored).unwrap();
+    assert_eq!(band.statistics(false), Some(stored));
+}
+
+#[test]
+fn test_to_physical() {
+    use super::Buffer;
+
+    let driver = Driver::get("MEM").unwrap();
+    let dataset = driver.create_with_band_type::<u16>("", 2, 2, 1).unwrap();
+    let band = dataset.rasterband(1).unwrap();
+    assert_eq!((band.scale(), band.offset()), (1., 0.));
+    band.set_scale(0.01).unwrap();
+    band.set_offset(-273.15).unwrap();
+    band.set_no_data_value(0.).unwrap();
+    assert_eq!((band.scale(), band.offset()), (0.01, -273.15));
+
+    let raw = Buffer::new((2, 2), vec!(0u16, 27315, 29315, 30000));
+    let physical = band.to_physical(&raw);
+    assert!(physical.data[0].is_nan());
+    assert!((physical.data[1] - 0.).abs() < 1e-9);
+    assert!((physical.data[2] - 20.).abs() < 1e-9);
+    assert!((physical.data[3] - 26.85).abs() < 1e-9);
+
+    let back = band.from_physical(&physical);
+    assert_eq!(back.data[0], 0.);
+    assert!((back.data[3] - 30000.).abs() < 1e-6);
+    dataset.write_raster(1, Window::new(0, 0, 2, 2), back).unwrap();
+    assert_eq!(dataset.read_raster_as::<u16>(1, Window::new(0, 0, 2, 2), RasterSize::new(2, 2)).unwrap().data, raw.data);
+}
+
+#[test]
+fn test_complex_band() {
+    use std::f64::consts::FRAC_PI_2;
+    use super::{Buffer, Complex};
+
+    let driver = Driver::get("MEM").unwrap();
+    let dataset = driver.create_with_band_type::<Complex<i16>>("", 2, 1, 1).unwrap();
+    assert_eq!(dataset.get_band_type(1), Some(GDALDataType::GDT_CInt16));
+    let values = vec!(Complex{re: 3, im: 4}, Complex{re: 0, im: -2});
+    dataset.write_raster(1, Window::new(0, 0, 2, 1), Buffer::new((2, 1), values.clone())).unwrap();
+    assert_eq!(dataset.read_raster_as::<Complex<i16>>(1, Window::new(0, 0, 2, 1), RasterSize::new(2, 1)).unwrap().data, values);
+
+    let band = dataset.rasterband(1).unwrap();
+    let (re, im) = band.read_re_im(Window::new(0, 0, 2, 1), RasterSize::new(2, 1)).unwrap();
+    assert_eq!((re.data, im.data), (vec!(3., 0.), vec!(4., -2.)));
+    let (magnitude, phase) = band.read_magnitude_phase(Window::new(0, 0, 2, 1), RasterSize::new(2, 1)).unwrap();
+    assert_eq!(magnitude.data, vec!(5., 2.));
+    assert!((phase.data[0] - (4f64).atan2(3.)).abs() < 1e-12);
+    assert!((phase.data[1] + FRAC_PI_2).abs() < 1e-12);
+}
+
+#[test]
+fn test_read_write_window() {
+    use super::Buffer;
+
+    let driver = Driver::get("MEM").unwrap();
+    let dataset = driver.create("", 4, 3, 1).unwrap();
+    let band = dataset.rasterband(1).unwrap();
+    let window = Window::new(1, 1, 2, 2);
+    assert_eq!(window.size(), RasterSize::new(2, 2));
+    band.write_window(window, &Buffer::new((2, 2), vec!(1u8, 2, 3, 4))).unwrap();
+
+    let full = band.read_as::<u8>(Window::full(RasterSize::new(4, 3)), RasterSize::new(4, 3)).unwrap();
+    assert_eq!(full.data, vec!(0, 0, 0, 0, 0, 1, 2, 0, 0, 3, 4, 0));
+    assert_eq!(band.read_as::<u8>(window, RasterSize::new(1, 1)).unwrap().size, (1, 1));
+
+    assert!(band.read_as::<u8>(Window::new(3, 0, 2, 1), RasterSize::new(2, 1)).is_err());
+    assert!(band.read_as::<u8>(Window::new(0, 0, 0, 1), RasterSize::new(1, 1)).is_err());
+    assert!(band.read_as::<u8>(window, RasterSize::new(0, 2)).is_err());
+    assert!(band.write_window(window, &Buffer::new((2, 2), vec!(1u8, 2, 3))).is_err());
+    assert!(band.write_window(Window::new(0, 2, 1, 2), &Buffer::new((1, 2), vec!(1u8, 2))).is_err());
+    // offsets that overflow are out of bounds, not wrapped around
+    assert!(Window::new(usize::MAX, 0, 2, 1).validate(RasterSize::new(4, 3)).is_err());
+    assert!(Window::new(0, 1, 1, usize::MAX).validate(RasterSize::new(4, 3)).is_err());
+    assert!(Window::from_tuples((-1, 0), (1, 1)).validate(RasterSize::new(4, 3)).is_err());
+
+    assert!(dataset.read_raster(2, window, window.size()).is_err());
+    assert!(dataset.write_raster(1, Window::new(3, 0, 2, 1), Buffer::new((2, 1), vec!(1u8, 2))).is_err());
+}
+
+#[test]
+fn test_focal() {
+    use super::Buffer;
+    use super::focal::{focal, Kernel};
+
+    let driver = Driver::get("MEM").unwrap();
+    let dataset = driver.create_with_band_type::<f64>("", 3, 3, 1).unwrap();
+    dataset.write_raster(1, Window::new(0, 0, 3, 3), Buffer::new((3, 3), vec!(1., 2., 3., 4., 5., 6., 7., 8., 9.))).unwrap();
+    let band = dataset.rasterband(1).unwrap();
+
+    let read = |output: &Dataset| output.read_raster_as::<f64>(1, Window::new(0, 0, 3, 3), RasterSize::new(3, 3)).unwrap().data;
+    let mean = focal(&band, &Kernel::Mean, 1, &driver, "").unwrap();
+    assert_eq!(read(&mean)[..5], [3., 3.5, 4., 4.5, 5.]);
+    let sum = focal(&band, &Kernel::Sum, 1, &driver, "").unwrap();
+    assert_eq!(read(&sum)[4], 45.);
+    assert_eq!(read(&focal(&band, &Kernel::Max, 1, &driver, "").unwrap())[0], 5.);
+    let shift = Kernel::Weights(vec!(0., 0., 0., 0., 0., 1., 0., 0., 0.));
+    assert_eq!(read(&focal(&band, &shift, 1, &driver, "").unwrap())[..3], [2., 3., 0.]);
+    assert!(focal(&band, &Kernel::Weights(vec!(1.)), 1, &driver, "").is_err());
+
+    // nodata pixels are left out, pixels without valid neighbors are nodata
+    band.set_no_data_value(5.).unwrap();
+    let min = focal(&band, &Kernel::Min, 0, &driver, "").unwrap();
+    assert_eq!(min.rasterband(1).unwrap().no_data_value(), Some(5.));
+    assert_eq!(read(&min)[3..6], [4., 5., 6.]);
+    assert_eq!(read(&focal(&band, &Kernel::Mean, 1, &driver, "").unwrap())[4], 5.);
+}
+
+#[test]
+fn test_terrain() {
+    use super::Buffer;
+    use super::terrain::{aspect, hillshade, slope, TerrainOptions};
+
+    let transform = [0., 1., 0., 0., 0., -1.];
+    let options = TerrainOptions::new();
+    // rising to the east, one unit per pixel
+    let east = Buffer::new((4, 3), (0..12).map(|i| (i % 4) as f64).collect());
+    assert!(slope(&east, &transform, &options).data.iter().all(|&s| (s - 45.).abs() < 1e-4));
+    assert!(aspect(&east, &transform, &options).data.iter().all(|&a| (a - 270.).abs() < 1e-4));
+    let lit = hillshade(&east, &transform, &options.clone().azimuth(270.));
+    assert!(lit.data.iter().all(|&h| (h - 255.).abs() < 1e-3));
+    let shaded = hillshade(&east, &transform, &options);
+    assert!((shaded.data[5] as f64 - 255. * (0.5 + 0.5f64.sqrt()) / 2f64.sqrt()).abs() < 1e-3);
+
+    // rising to the north, with a vertical exaggeration of 2
+    let north = Buffer::new((2, 3), vec!(2i16, 2, 1, 1, 0, 0));
+    let steep = slope(&north, &transform, &options.clone().z_factor(2.));
+    assert!((steep.data[2] as f64 - 2f64.atan().to_degrees()).abs() < 1e-4);
+    assert!((aspect(&north, &transform, &options).data[2] - 180.).abs() < 1e-4);
+
+    let flat = Buffer::new((2, 2), vec!(5u8; 4));
+    assert_eq!(slope(&flat, &transform, &options).data, vec!(0.; 4));
+    assert!(aspect(&flat, &transform, &options).data[0].is_nan());
+    assert!((hillshade(&flat, &transform, &options).data[0] as f64 - 255. * 0.5f64.sqrt()).abs() < 1e-3);
+}
+
+#[test]
+fn test_warped_view() {
+    use super::GDALResampleAlg;
+    use spatial_ref::SpatialRef;
+
+    let driver = Driver::get("MEM").unwrap();
+    let dataset = driver.create("", 10, 10, 1).unwrap();
+    dataset.set_geo_transform(&[8., 0.1, 0., 50., 0., -0.1]);
+    dataset.set_projection(&SpatialRef::from_epsg(4326).unwrap().to_wkt().unwrap()).unwrap();
+    dataset.write_raster(1, Window::new(0, 0, 10, 10), ByteBuffer::new((10, 10), vec!(7; 100))).unwrap();
+
+    let utm = SpatialRef::from_epsg(32632).unwrap();
+    let view = dataset.warped_view(&utm, GDALResampleAlg::GRA_NearestNeighbour, 0.125).unwrap();
+    assert!(SpatialRef::from_wkt(&view.projection()).unwrap().is_same(&utm));
+    let transform = view.geo_transform().unwrap();
+    // 8° E is 3° west of the central meridian of zone 32
+    assert!(transform[0] > 400000. && transform[0] < 500000.);
+    let (size_x, size_y) = (view.size().0 as usize, view.size().1 as usize);
+    let center = view.read_raster(1, Window::new(size_x / 2, size_y / 2, 1, 1), RasterSize::new(1, 1)).unwrap();
+    assert_eq!(center.data, vec!(7));
+
+    let no_projection = driver.create("", 1, 1, 1).unwrap();
+    assert!(no_projection.warped_view(&utm, GDALResampleAlg::GRA_Bilinear, 0.).is_err());
+}
+
+#[test]
+fn test_band_subset_view() {
+    let dataset = Dataset::open(fixture!("tinymarble.png")).unwrap();
+    let view = dataset.band_subset_view(&[3, 1]).unwrap();
+    assert_eq!(view.count(), 2);
+    assert_eq!(view.size(), dataset.size());
+    assert_eq!(
+        view.read_raster(1, Window::new(0, 0, 100, 50), RasterSize::new(100, 50)).unwrap().data,
+        dataset.read_raster(3, Window::new(0, 0, 100, 50), RasterSize::new(100, 50)).unwrap().data
+    );
+    assert_eq!(
+        view.read_raster(2, Window::new(0, 0, 100, 50), RasterSize::new(100, 50)).unwrap().data,
+        dataset.read_raster(1, Window::new(0, 0, 100, 50), RasterSize::new(100, 50)).unwrap().data
+    );
+    assert!(dataset.band_subset_view(&[4]).is_err());
+    assert!(dataset.band_subset_view(&[]).is_err());
+
+    let memory = Driver::get("MEM").unwrap().create("", 10, 10, 3).unwrap();
+    memory.write_raster(3, Window::new(0, 0, 10, 10), ByteBuffer{size: (1, 1), data: vec!(9u8)}).unwrap();
+    memory.rasterband(3).unwrap().set_no_data_value(0.).unwrap();
+    let view = memory.band_subset_view(&[3, 3, 1]).unwrap();
+    assert_eq!(view.count(), 3);
+    assert_eq!(view.read_raster(2, Window::new(4, 4, 1, 1), RasterSize::new(1, 1)).unwrap().data, vec!(9));
+    assert_eq!(view.read_raster(3, Window::new(4, 4, 1, 1), RasterSize::new(1, 1)).unwrap().data, vec!(0));
+    assert_eq!(view.rasterband(1).unwrap().no_data_value(), Some(0.));
+}
+
+#[test]
+fn test_unique_values() {
+    use super::Buffer;
+
+    let driver = Driver::get("MEM").unwrap();
+    let dataset = driver.create_with_band_type::<i16>("", 3, 2, 1).unwrap();
+    dataset.write_raster(1, Window::new(0, 0, 3, 2), Buffer::new((3, 2), vec!(1i16, 2, 2, -1, 0, 2))).unwrap();
+    let band = dataset.rasterband(1).unwrap();
+    band.set_no_data_value(0.).unwrap();
+    let counts = band.unique_values(10).unwrap();
+    assert_eq!(counts.len(), 3);
+    assert_eq!((counts[&1], counts[&2], counts[&-1]), (1, 3, 1));
+    assert!(band.unique_values(2).is_err());
+
+    let real = driver.create_with_band_type::<f32>("", 1, 1, 1).unwrap();
+    assert!(real.rasterband(1).unwrap().unique_values(10).is_err());
+
+    assert!(band.category_names().is_empty());
+    let names = vec!("none".to_string(), "water".to_string(), "forest".to_string());
+    band.set_category_names(&names).unwrap();
+    assert_eq!(band.category_names(), names);
+}
+
+#[test]
+fn test_polygonize() {
+    use super::{polygonize, polygonize_float, Connectedness, RasterBand};
+    use vector;
+    use vector::OFT_INTEGER;
+
+    let driver = Driver::get("MEM").unwrap();
+    let dataset = driver.create("", 3, 3, 1).unwrap();
+    dataset.write_raster(1, Window::new(0, 0, 3, 3), ByteBuffer::new((3, 3), vec!(1, 0, 0, 0, 1, 0, 0, 0, 1))).unwrap();
+    let band = dataset.rasterband(1).unwrap();
+
+    let values = |mask: Option<&RasterBand>, connectedness, float: bool| {
+        let mut ds = vector::Driver::get("Memory").unwrap().create(Path::new("")).unwrap();
+        let layer = ds.create_layer_ext("polygons", None, vector::WKB_POLYGON, &[]).unwrap();
+        layer.create_defn_fields(&[("value", OFT_INTEGER)]).unwrap();
+        match float {
+            true => polygonize_float(&band, mask, layer, Some(0), connectedness).unwrap(),
+            false => polygonize(&band, mask, layer, Some(0), connectedness).unwrap(),
+        };
+        layer.features().map(|f| f.field("value").unwrap().unwrap().as_int()).collect::<Vec<_>>()
+    };
+    assert_eq!(values(None, Connectedness::Four, false).len(), 5);
+    assert_eq!(values(None, Connectedness::Eight, false).len(), 2);
+    assert_eq!(values(None, Connectedness::Eight, true).len(), 2);
+    // the band as its own mask leaves out the zeros
+    assert_eq!(values(Some(&band), Connectedness::Four, false), vec!(1, 1, 1));
+    assert_eq!(values(Some(&band), Connectedness::Eight, false), vec!(1));
+}
+
+#[test]
+fn test_open_flags() {
+    use OpenFlags;
+    let dataset = Dataset::open(fixture!("tinymarble.png")).unwrap();
+    assert_eq!(dataset.open_flags(), OpenFlags::RASTER);
+    assert!(dataset.is_raster());
+    assert!(!dataset.is_vector());
+    assert!(!dataset.is_update());
+
+    let driver = Driver::get("MEM").unwrap();
+    let dataset = driver.create("", 10, 10, 1).unwrap();
+    assert!(dataset.is_update());
+    assert_eq!(dataset.open_flags().bits(), 0x03);
+
+    let dataset = Dataset::open_shared(fixture!("tinymarble.png")).unwrap();
+    assert_eq!(dataset.open_flags(), OpenFlags::RASTER | OpenFlags::SHARED);
+    let dataset = Dataset::open_ex(fixture!("tinymarble.png"), OpenFlags::VECTOR | OpenFlags::SHARED, &[]).unwrap();
+    assert!(dataset.is_raster());
+    assert!(dataset.is_vector());
+    assert!(dataset.open_flags().contains(OpenFlags::SHARED));
+}
+
+#[test]
+fn test_world_file() {
+    use std::fs;
+    use super::dataset::{read_world_file, write_world_file};
+
+    let tr = [10., 0.5, 0., 20., 0., -0.5];
+    write_world_file(fixture!("world_file.png"), "pgw", &tr).unwrap();
+    assert_eq!(read_world_file(fixture!("world_file.png"), None), Some(tr));
+    assert_eq!(read_world_file(fixture!("world_file.png"), Some("pgw")), Some(tr));
+    assert_eq!(read_world_file(fixture!("world_file.png"), Some("jgw")), None);
+
+    let driver = Driver::get("MEM").unwrap();
+    let dataset = driver.create("", 20, 10, 1).unwrap();
+    assert_eq!(dataset.load_world_file(fixture!("world_file.png"), None), Ok(tr));
+    assert_eq!(dataset.geo_transform(), Some(tr));
+    fs::remove_file(fixture!("world_file.pgw")).unwrap();
+
+    dataset.save_world_file(fixture!("world_file.png"), "wld").unwrap();
+    assert_eq!(read_world_file(fixture!("world_file.png"), Some("wld")), Some(tr));
+    fs::remove_file(fixture!("world_file.wld")).unwrap();
+}
+
+#[test]
+fn test_validate_cog() {
+    use std::fs;
+    use validate_cog;
+
+    let report = validate_cog(&Dataset::open(fixture!("tinymarble.png")).unwrap());
+    assert!(!report.is_valid());
+
+    let driver = Driver::get("GTiff").unwrap();
+    {
+        let stripped = driver.create_with_options::<u8>(fixture!("cog_stripped.tif").to_str().unwrap(), 2048, 64, 1, &[]).unwrap();
+        let report = validate_cog(&stripped);
+        assert!(!report.is_valid());
+        assert_eq!(report.block_size.0, 2048);
+        assert_eq!(report.overview_count, 0);
+    }
+    fs::remove_file(fixture!("cog_stripped.tif")).unwrap();
+
+    let tiled = GeoTiffOptions::new().tiled(256, 256);
+    {
+        let source = driver.create_with_options::<u8>(fixture!("cog_source.tif").to_str().unwrap(), 1024, 1024, 1, &tiled.clone().options().unwrap()).unwrap();
+        source.build_overviews("NEAREST", &[2, 4], OverviewLocation::Internal).unwrap();
+        let options = tiled.copy_src_overviews(true).options().unwrap();
+        let cog = source.create_copy_with_options(driver, fixture!("cog.tif").to_str().unwrap(), &options).unwrap();
+        let report = validate_cog(&cog);
+        assert_eq!(report.errors, Vec::<String>::new());
+        assert_eq!(report.block_size, (256, 256));
+        assert_eq!(report.overview_count, 2);
+    }
+    fs::remove_file(fixture!("cog_source.tif")).unwrap();
+    fs::remove_file(fixture!("cog.tif")).unwrap();
+}
+
+#[test]
+fn test_validate_cog_block_markers() {
+    use std::fs;
+    use std::io::{Seek, SeekFrom, Write};
+    use validate_cog;
+
+    // block leaders and trailers are written by the COG driver, GDAL 3.1
+    let driver = match Driver::get("COG") {
+        Some(driver) => driver,
+        None => return,
+    };
+    let path = fixture!("cog_markers.tif").to_path_buf();
+    {
+        let source = Driver::get("MEM").unwrap().create("", 1024, 1024, 1).unwrap();
+        source.create_copy_with_options(driver, path.to_str().unwrap(), &["COMPRESS=DEFLATE".to_string()]).unwrap();
+    }
+    let offset: u64 = {
+        let cog = Dataset::open(&path).unwrap();
+        let report = validate_cog(&cog);
+        assert_eq!(report.errors, Vec::<String>::new());
+        assert_eq!(report.layout, Some("COG".to_string()));
+        cog.rasterband(1).unwrap().metadata_item("BLOCK_OFFSET_0_0", "TIFF").unwrap().parse().unwrap()
+    };
+    {
+        let mut file = fs::OpenOptions::new().write(true).open(&path).unwrap();
+        file.seek(SeekFrom::Start(offset - 4)).unwrap();
+        file.write_all(&[0xff; 4]).unwrap();
+    }
+    let report = validate_cog(&Dataset::open(&path).unwrap());
+    assert_eq!(report.errors, vec!("the block leader of the first tile of the full resolution image doesn't match its size".to_string()));
+    fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn test_read_into_interleaved() {
+    use super::BufferLayout;
+    let dataset = Dataset::open(fixture!("tinymarble.png")).unwrap();
+    let window = Window::new(10, 5, 4, 3);
+    let size = window.size();
+    let layout = BufferLayout::pixel_interleaved(size, 4).unwrap();
+    let mut rgba = vec![255u8; layout.required_len(size, 4).unwrap()];
+    dataset.read_into(&[1, 2, 3], window, size, &mut rgba, layout).unwrap();
+    assert_eq!(rgba.len(), 4 * 3 * 4);
+
+    let red = dataset.read_raster(1, Window::new(10, 5, 4, 3), RasterSize::new(4, 3)).unwrap().data;
+    let blue = dataset.read_raster(3, Window::new(10, 5, 4, 3), RasterSize::new(4, 3)).unwrap().data;
+    for i in 0..12 {
+        assert_eq!(rgba[4 * i], red[i]);
+        assert_eq!(rgba[4 * i + 2], blue[i]);
+        // the alpha channel isn't touched
+        assert_eq!(rgba[4 * i + 3], 255);
+    }
+
+    // a single band into the green channel
+    let band = dataset.rasterband(2).unwrap();
+    let mut green = vec![0u8; layout.required_len(size, 4).unwrap()];
+    band.read_into(window, size, &mut green[1..], layout).unwrap();
+    assert_eq!(green[1], rgba[1]);
+    assert_eq!(green[45], rgba[45]);
+
+    assert!(dataset.read_into(&[1, 2, 3], window, size, &mut rgba[..40], layout).is_err());
+    assert!(dataset.read_into(&[4], window, size, &mut rgba, layout).is_err());
+
+    let driver = Driver::get("MEM").unwrap();
+    let copy = driver.create("", 4, 3, 3).unwrap();
+    copy.write_from(&[1, 2, 3], Window::full(RasterSize::new(4, 3)), size, &rgba, layout).unwrap();
+    assert_eq!(copy.read_raster(3, Window::new(0, 0, 4, 3), RasterSize::new(4, 3)).unwrap().data, blue);
+    assert_eq!(BufferLayout::band_sequential(size).unwrap().required_len(size, 3).unwrap(), 36);
+
+    let huge = RasterSize::new(usize::MAX, 2);
+    assert!(huge.pixels().is_err());
+    assert!(BufferLayout::band_sequential(huge).is_err());
+    assert!(BufferLayout::pixel_interleaved(huge, 4).is_err());
+    assert!(layout.required_len(huge, 4).is_err());
+}
+
+#[test]
+fn test_read_as_other_type() {
+    use super::Buffer;
+    let driver = Driver::get("MEM").unwrap();
+    let dataset = driver.create_with_band_type::<i16>("", 4, 1, 1).unwrap();
+    assert_eq!(dataset.get_band_type(1), Some(GDALDataType::GDT_Int16));
+    let band = dataset.rasterband(1).unwrap();
+    assert_eq!(band.band_type(), GDALDataType::GDT_Int16);
+    dataset.write_raster(1, Window::new(0, 0, 4, 1), Buffer::new((4, 1), vec![-300i16, -1, 42, 300])).unwrap();
+
+    assert_eq!(band.read_as::<f32>(Window::new(0, 0, 4, 1), RasterSize::new(4, 1)).unwrap().data, vec![-300., -1., 42., 300.]);
+    assert_eq!(band.read_as::<u8>(Window::new(0, 0, 4, 1), RasterSize::new(4, 1)).unwrap().data, vec![0, 0, 42, 255]);
+    assert_eq!(dataset.read_raster_as::<i32>(1, Window::new(0, 0, 4, 1), RasterSize::new(4, 1)).unwrap().data, vec![-300, -1, 42, 300]);
+
+    // floats are rounded and saturated when written to integer bands
+    dataset.write_raster(1, Window::new(0, 0, 4, 1), Buffer::new((4, 1), vec![1.4f64, 1.6, -1e6, 1e6])).unwrap();
+    assert_eq!(band.read_as::<i16>(Window::new(0, 0, 4, 1), RasterSize::new(4, 1)).unwrap().data, vec![1, 2, -32768, 32767]);
+}
+
+#[test]
+fn test_bounds_pixel_size() {
+    use vector::Envelope;
+    // a plain image without world file
+    let dataset = Dataset::open(fixture!("tinymarble.png")).unwrap();
+    assert!(!dataset.is_georeferenced());
+    assert!(!dataset.is_north_up());
+    assert_eq!(dataset.bounds(), None);
+
+    let driver = Driver::get("MEM").unwrap();
+    let dataset = driver.create("", 20, 10, 1).unwrap();
+    dataset.set_geo_transform(&[10., 0.5, 0., 60., 0., -0.25]);
+    assert!(dataset.is_georeferenced());
+    assert!(dataset.is_north_up());
+    assert_eq!(dataset.pixel_size(), Some((0.5, 0.25)));
+    assert_eq!(dataset.bounds(), Some(Envelope::new(10., 20., 57.5, 60.)));
+
+    // rotated by 90 degrees: columns run south, rows run west
+    dataset.set_geo_transform(&[100., 0., -2., 50., -2., 0.]);
+    assert!(dataset.is_georeferenced());
+    assert!(!dataset.is_north_up());
+    assert_eq!(dataset.pixel_size(), Some((2., 2.)));
+    assert_eq!(dataset.bounds(), Some(Envelope::new(80., 100., 10., 50.)));
+}
+
+#[test]
+fn test_virtual_mem() {
+    use super::Buffer;
+    let driver = Driver::get("MEM").unwrap();
+    let dataset = driver.create_with_band_type::<u16>("", 5, 3, 1).unwrap();
+    let data: Vec<u16> = (0..15).collect();
+    dataset.write_raster(1, Window::new(0, 0, 5, 3), Buffer::new((5, 3), data.clone())).unwrap();
+    let band = dataset.rasterband(1).unwrap();
+
+    let mapped = unsafe { band.virtual_mem::<u16>() }.unwrap();
+    assert_eq!(mapped.size(), (5, 3));
+    assert_eq!(mapped.pixel_space(), 2);
+    assert_eq!(mapped.get(3, 2), 13);
+    assert_eq!(mapped.row(1).unwrap(), &data[5..10]);
+    assert_eq!(mapped.as_slice().unwrap(), &data[..]);
+
+    assert!(unsafe { band.virtual_mem::<u8>() }.is_err());
+}
+
+#[test]
+fn test_rgb_to_paletted() {
+    use super::{ColorEntry, ColorTable};
+
+    let rgb = Dataset::open(fixture!("tinymarble.png")).unwrap();
+    let paletted = ::rgb_to_paletted(&rgb, 16).unwrap();
+    assert_eq!(paletted.size(), rgb.size());
+    assert_eq!(paletted.count(), 1);
+    let table = paletted.rasterband(1).unwrap().color_table().unwrap();
+    assert!(table.len() <= 16 && !table.is_empty());
+    let (size_x, size_y) = (100, 50);
+    let indices = paletted.read_raster(1, Window::new(0, 0, size_x, size_y), RasterSize::new(size_x, size_y)).unwrap().data;
+    assert!(indices.iter().all(|&i| (i as usize) < table.len()));
+
+    let expanded = ::expand_palette(&paletted, false).unwrap();
+    assert_eq!(expanded.count(), 3);
+    let red = expanded.read_raster(1, Window::new(0, 0, size_x, size_y), RasterSize::new(size_x, size_y)).unwrap().data;
+    let blue = expanded.read_raster(3, Window::new(0, 0, size_x, size_y), RasterSize::new(size_x, size_y)).unwrap().data;
+    for (i, &index) in indices.iter().enumerate() {
+        let color = table.entry(index as usize).unwrap();
+        assert_eq!((red[i], blue[i]), (color.r, color.b));
+    }
+
+    let mut table = ColorTable::from_colors(&[ColorEntry::rgb(255, 0, 0), ColorEntry::rgb(0, 0, 255)]);
+    table.set_entry(3, ColorEntry{r: 0, g: 255, b: 0, a: 128});
+    assert_eq!(table.len(), 4);
+    assert_eq!(table.entry(2), Some(ColorEntry{r: 0, g: 0, b: 0, a: 0}));
+    assert_eq!(table.clone().entries(), table.entries());
+    assert!(::expand_palette(&rgb, true).is_err());
+}
+
+#[test]
+fn test_read_strategy() {
+    use std::fs;
+    use super::{GDALDataType, Interleave, RasterLayout, ReadStrategy, WindowOrder};
+
+    let path = fixture!("output_strategy.tif").to_path_buf();
+    {
+        let driver = Driver::get("GTiff").unwrap();
+        let options = [
+            "TILED=YES".to_string(), "BLOCKXSIZE=64".to_string(), "BLOCKYSIZE=64".to_string(),
+            "COMPRESS=DEFLATE".to_string(),
+        ];
+        let dataset = driver.create_with_data_type(path.to_str().unwrap(), 256, 200, 3, GDALDataType::GDT_Byte, &options).unwrap();
+        let layout = dataset.layout().unwrap();
+        assert_eq!(layout.size, (256, 200));
+        assert_eq!((layout.band_count, layout.block_size, layout.tiled), (3, (64, 64), true));
+        assert_eq!(layout.compression, Some("DEFLATE".to_string()));
+        assert_eq!(layout.interleave, Interleave::Pixel);
+        assert_eq!(layout.value_bytes(), 1);
+
+        // a tile of all bands is 12 KiB: two rows of four tiles fit
+        let strategy = dataset.read_strategy(100 * 1024).unwrap();
+        assert!(strategy.all_bands && strategy.align_to_blocks);
+        assert_eq!(strategy.window_size, (256, 128));
+        assert_eq!(strategy.windows().count(), 2);
+    }
+    fs::remove_file(&path).unwrap();
+    {
+        // tiles wider than the raster, and plain strips
+        let driver = Driver::get("GTiff").unwrap();
+        let options = ["TILED=YES".to_string(), "BLOCKXSIZE=128".to_string(), "BLOCKYSIZE=16".to_string()];
+        let dataset = driver.create_with_data_type(path.to_str().unwrap(), 100, 200, 1, GDALDataType::GDT_Byte, &options).unwrap();
+        let layout = dataset.layout().unwrap();
+        assert_eq!((layout.block_size, layout.tiled), ((128, 16), true));
+        let strips = Driver::get("MEM").unwrap().create("", 100, 200, 1).unwrap();
+        assert!(!strips.layout().unwrap().tiled);
+    }
+    fs::remove_file(&path).unwrap();
+    {
+        // tiles as wide as the raster
+        let driver = Driver::get("GTiff").unwrap();
+        let options = ["TILED=YES".to_string(), "BLOCKXSIZE=256".to_string(), "BLOCKYSIZE=256".to_string()];
+        let dataset = driver.create_with_data_type(path.to_str().unwrap(), 256, 512, 1, GDALDataType::GDT_Byte, &options).unwrap();
+        let layout = dataset.layout().unwrap();
+        assert_eq!((layout.block_size, layout.tiled), ((256, 256), true));
+    }
+    fs::remove_file(&path).unwrap();
+    {
+        // a single tile larger than the raster
+        let driver = Driver::get("GTiff").unwrap();
+        let options = ["TILED=YES".to_string(), "BLOCKXSIZE=128".to_string(), "BLOCKYSIZE=128".to_string()];
+        let dataset = driver.create_with_data_type(path.to_str().unwrap(), 100, 50, 1, GDALDataType::GDT_Byte, &options).unwrap();
+        let layout = dataset.layout().unwrap();
+        assert_eq!((layout.block_size, layout.tiled), ((128, 128), true));
+    }
+    fs::remove_file(&path).unwrap();
+
+    let strips = RasterLayout{
+        size: (10000, 5000),
+        band_count: 1,
+        band_type: GDALDataType::GDT_Float32,
+        block_size: (10000, 16),
+        tiled: false,
+        compression: Some("LZW".to_string()),
+        interleave: Interleave::Band,
+        overview_count: 0,
+    };
+    // a strip is 640000 bytes, more than the budget
+    let strategy = ReadStrategy::for_layout(&strips, 200000);
+    assert_eq!(strategy.window_size, (10000, 5));
+    assert_eq!(strategy.order, WindowOrder::BlockMajor);
+    assert!(!strategy.all_bands && !strategy.align_to_blocks);
+    let strategy = ReadStrategy::for_layout(&strips, 2000000);
+    assert_eq!((strategy.window_size, strategy.order), ((10000, 48), WindowOrder::RowMajor));
+}
@@ -11,3 +11,29 @@ impl GdalType for i16   { fn gdal_type() -> GDALDataType { GDALDataType::GDT_Int
 impl GdalType for i32   { fn gdal_type() -> GDALDataType { GDALDataType::GDT_Int32 } }
 impl GdalType for f32   { fn gdal_type() -> GDALDataType { GDALDataType::GDT_Float32 } }
 impl GdalType for f64   { fn gdal_type() -> GDALDataType { GDALDataType::GDT_Float64 } }
+
+/// A complex pixel value, laid out like GDAL's complex types: the real part
+/// followed by the imaginary part.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Complex<T> {
+    pub re: T,
+    pub im: T,
+}
+
+impl Complex<f64> {
+    /// The absolute value, `sqrt(re² + im²)`.
+    pub fn magnitude(&self) -> f64 {
+        return self.re.hypot(self.im);
+    }
+
+    /// The angle to the real axis in radians, between -π and π.
+    pub fn phase(&self) -> f64 {
+        return self.im.atan2(self.re);
+    }
+}
+
+impl GdalType for Complex<i16> { fn gdal_type() -> GDALDataType { GDALDataType::GDT_CInt16 } }
+impl GdalType for Complex<i32> { fn gdal_type() -> GDALDataType { GDALDataType::GDT_CInt32 } }
+impl GdalType for Complex<f32> { fn gdal_type() -> GDALDataType { GDALDataType::GDT_CFloat32 } }
+impl GdalType for Complex<f64> { fn gdal_type() -> GDALDataType { GDALDataType::GDT_CFloat64 } }
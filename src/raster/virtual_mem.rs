@@ -0,0 +1,117 @@
+//! Memory mapped access to raster bands
+//!
+//! `RasterBand::virtual_mem` maps the pixels of a band into memory, so they
+//! can be read as slices instead of with windowed reads. Uncompressed
+//! formats, like raw binary files, untiled uncompressed GeoTIFFs or MEM
+//! datasets, are mapped directly; for other formats GDAL falls back to
+//! loading pages on first access, which needs Linux.
+//!
+//! Mapping is `unsafe` because directly mapped pixels change when the band
+//! is written to, even behind the slices handed out by `VirtualMem`. Don't
+//! write to the band or its dataset while a mapping is alive.
+//!
+//! ```no_run
+//! use std::path::Path;
+//! use gdal::raster::Dataset;
+//!
+//! let dataset = Dataset::open(Path::new("elevation.bil")).unwrap();
+//! let band = dataset.rasterband(1).unwrap();
+//! // nothing writes to the dataset while it's mapped
+//! let mapped = unsafe { band.virtual_mem::<i16>() }.unwrap();
+//! let highest = (0..mapped.size().1)
+//!     .filter_map(|y| mapped.row(y))
+//!     .flat_map(|row| row.iter().cloned())
+//!     .max();
+//! ```
+
+use std::marker::PhantomData;
+use std::mem;
+use std::ptr;
+use std::slice;
+use libc::c_void;
+use raster::gdal;
+
+/// The pixels of a band mapped read-only into memory, see
+/// `RasterBand::virtual_mem`. The mapping is released when this is
+/// dropped, and can't outlive the dataset of the band.
+pub struct VirtualMem<'a, T> {
+    c_mem: *const c_void,
+    data: *const u8,
+    len: usize,
+    size: (usize, usize),
+    pixel_space: usize,
+    line_space: usize,
+    _dataset: PhantomData<&'a T>,
+}
+
+impl<'a, T: Copy> VirtualMem<'a, T> {
+    pub unsafe fn _with_c_mem(c_mem: *const c_void, size: (usize, usize), pixel_space: usize, line_space: usize) -> VirtualMem<'a, T> {
+        return VirtualMem{
+            c_mem: c_mem,
+            data: gdal::CPLVirtualMemGetAddr(c_mem) as *const u8,
+            len: gdal::CPLVirtualMemGetSize(c_mem),
+            size: size,
+            pixel_space: pixel_space,
+            line_space: line_space,
+            _dataset: PhantomData,
+        };
+    }
+
+    /// Size of the band in pixels.
+    pub fn size(&self) -> (usize, usize) {
+        return self.size;
+    }
+
+    /// Bytes from one pixel to the next, the size of `T` unless the
+    /// mapping interleaves the bands of the file.
+    pub fn pixel_space(&self) -> usize {
+        return self.pixel_space;
+    }
+
+    /// Bytes from one row to the next.
+    pub fn line_space(&self) -> usize {
+        return self.line_space;
+    }
+
+    /// The value of pixel (`x`, `y`). Panics outside the band.
+    pub fn get(&self, x: usize, y: usize) -> T {
+        assert!(x < self.size.0 && y < self.size.1, "pixel ({}, {}) is outside the band", x, y);
+        let offset = y * self.line_space + x * self.pixel_space;
+        assert!(offset + mem::size_of::<T>() <= self.len);
+        return unsafe { ptr::read_unaligned(self.data.add(offset) as *const T) };
+    }
+
+    /// Row `y` as a slice, `None` if its pixels aren't contiguous or
+    /// aligned for `T`. Panics outside the band.
+    pub fn row(&self, y: usize) -> Option<&[T]> {
+        assert!(y < self.size.1, "row {} is outside the band", y);
+        let offset = y * self.line_space;
+        return self.slice(offset, self.size.0);
+    }
+
+    /// All pixels as a slice, row by row, `None` if they aren't contiguous
+    /// or aligned for `T`.
+    pub fn as_slice(&self) -> Option<&[T]> {
+        if self.line_space != self.size.0 * self.pixel_space {
+            return None;
+        }
+        return self.slice(0, self.size.0 * self.size.1);
+    }
+
+    fn slice(&self, offset: usize, count: usize) -> Option<&[T]> {
+        if self.pixel_space != mem::size_of::<T>() || count == 0 {
+            return None;
+        }
+        let start = unsafe { self.data.add(offset) };
+        if start.align_offset(mem::align_of::<T>()) != 0 || offset + count * self.pixel_space > self.len {
+            return None;
+        }
+        return Some(unsafe { slice::from_raw_parts(start as *const T, count) });
+    }
+}
+
+impl<'a, T> Drop for VirtualMem<'a, T> {
+    fn drop(&mut self) {
+        unsafe { gdal::CPLVirtualMemFree(self.c_mem) };
+    }
+}
@@ -0,0 +1,237 @@
+use std::fs::{self, File};
+use std::io::Write;
+use std::marker::PhantomData;
+use std::ops::Deref;
+use std::path::Path;
+use std::ptr::null;
+use libc::c_int;
+use raster::{gdal, Dataset, Driver, RasterBand};
+use utils::_string;
+use GdalError;
+
+/// Options for `stack_bands`.
+pub struct StackOptions {
+    /// Short name of the driver used for the output. With `"VRT"` (the
+    /// default) the stack only references the source files; any other
+    /// driver copies the pixels into a new file.
+    pub driver: String,
+    /// Creation options passed to the driver, ignored for VRT.
+    pub creation_options: Vec<String>,
+}
+
+impl Default for StackOptions {
+    fn default() -> StackOptions {
+        StackOptions{
+            driver: "VRT".to_string(),
+            creation_options: vec!(),
+        }
+    }
+}
+
+/// A virtual dataset exposing bands of another one, returned by
+/// `Dataset::band_subset_view`. It derefs to `Dataset` and can't outlive
+/// the source dataset it reads from.
+pub struct BandSubsetView<'a> {
+    dataset: Dataset,
+    _source: PhantomData<&'a Dataset>,
+}
+
+impl<'a> BandSubsetView<'a> {
+    pub unsafe fn _with_dataset(dataset: Dataset) -> BandSubsetView<'a> {
+        return BandSubsetView{dataset: dataset, _source: PhantomData};
+    }
+}
+
+impl<'a> Deref for BandSubsetView<'a> {
+    type Target = Dataset;
+
+    fn deref(&self) -> &Dataset {
+        return &self.dataset;
+    }
+}
+
+/// Stack `bands`, which may come from different files, into a single
+/// multiband dataset written to `dest`, e.g. to build a composite or a
+/// time stack. Band `i` of the result is `bands[i - 1]`, keeping its data
+/// type and nodata value.
+///
+/// All bands must be aligned: same size, geo-transform and projection (see
+/// `align`). A VRT references the source files by absolute path, see
+/// `stack_bands_vrt`; other drivers read the bands from the open datasets,
+/// so they work for any source, MEM datasets included.
+pub fn stack_bands(bands: &[&RasterBand], dest: &Path, options: &StackOptions) -> Result<Dataset, GdalError> {
+    let path = match dest.to_str() {
+        Some(path) => path,
+        None => return Err(GdalError::CallFailed{desc: "destination path is not valid UTF-8"}),
+    };
+
+    if options.driver == "VRT" {
+        let xml = stack_bands_vrt(bands)?;
+        let written = File::create(dest).and_then(|mut file| file.write_all(xml.as_bytes()));
+        if written.is_err() {
+            return Err(GdalError::CallFailed{desc: "failed to write VRT"});
+        }
+        return match Dataset::open(dest) {
+            Some(dataset) => Ok(dataset),
+            None => Err(GdalError::CallFailed{desc: "failed to open VRT"}),
+        };
+    }
+
+    let driver = match Driver::get(&options.driver) {
+        Some(driver) => driver,
+        None => return Err(GdalError::CallFailed{desc: "unknown raster driver"}),
+    };
+    // the sources outlive the VRT, which is dropped when copied
+    let vrt = unsafe { _stack_bands_in_memory(bands) }?;
+    return match vrt.create_copy_with_options(driver, path, &options.creation_options) {
+        Some(dataset) => Ok(dataset),
+        None => Err(GdalError::CallFailed{desc: "failed to create stacked dataset"}),
+    };
+}
+
+/// The VRT document `stack_bands` writes, for callers who want to keep it
+/// in memory or edit it further. Sources are referenced by the absolute
+/// path of the file they were opened from, or their `/vsi` path. Bands of
+/// datasets that can't be reopened by name, like MEM datasets or
+/// subdatasets, are an error, and open options aren't kept.
+pub fn stack_bands_vrt(bands: &[&RasterBand]) -> Result<String, GdalError> {
+    check_aligned(bands)?;
+    let reference = bands[0].owning_dataset();
+    let size = bands[0].size();
+    let transform = reference.geo_transform();
+    let projection = reference.projection();
+    let mut sources = Vec::with_capacity(bands.len());
+    for band in bands {
+        sources.push(source_filename(band)?);
+    }
+
+    let mut xml = format!("<VRTDataset rasterXSize=\"{}\" rasterYSize=\"{}\">\n", size.0, size.1);
+    if !projection.is_empty() {
+        xml.push_str(&format!("  <SRS>{}</SRS>\n", escape(&projection)));
+    }
+    if let Some(tr) = transform {
+        xml.push_str(&format!(
+            "  <GeoTransform>{}, {}, {}, {}, {}, {}</GeoTransform>\n",
+            tr[0], tr[1], tr[2], tr[3], tr[4], tr[5],
+        ));
+    }
+    for (i, band) in bands.iter().enumerate() {
+        let data_type = _string(unsafe { gdal::GDALGetDataTypeName(band.band_type() as c_int) });
+        xml.push_str(&format!("  <VRTRasterBand dataType=\"{}\" band=\"{}\">\n", data_type, i + 1));
+        if let Some(no_data) = band.no_data_value() {
+            xml.push_str(&format!("    <NoDataValue>{}</NoDataValue>\n", no_data));
+        }
+        xml.push_str("    <SimpleSource>\n");
+        xml.push_str(&format!(
+            "      <SourceFilename relativeToVRT=\"0\">{}</SourceFilename>\n",
+            escape(&sources[i]),
+        ));
+        xml.push_str(&format!("      <SourceBand>{}</SourceBand>\n", band.index()));
+        xml.push_str("    </SimpleSource>\n");
+        xml.push_str("  </VRTRasterBand>\n");
+    }
+    xml.push_str("</VRTDataset>\n");
+    return Ok(xml);
+}
+
+fn escape(text: &str) -> String {
+    return text
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;");
+}
+
+/// An in-memory VRT of `bands`, reading them through their open handles
+/// rather than by file name, so any source works, MEM datasets included.
+/// The VRT must not outlive the datasets of `bands`.
+pub unsafe fn _stack_bands_in_memory(bands: &[&RasterBand]) -> Result<Dataset, GdalError> {
+    check_aligned(bands)?;
+    let reference = bands[0].owning_dataset();
+    let (size_x, size_y) = bands[0].size();
+    let c_dataset = gdal::VRTCreate(size_x as c_int, size_y as c_int);
+    if c_dataset.is_null() {
+        return Err(GdalError::CallFailed{desc: "VRTCreate failed"});
+    }
+    let vrt = Dataset::_with_c_ptr(c_dataset);
+    let projection = reference.projection();
+    if !projection.is_empty() {
+        vrt.set_projection(&projection)?;
+    }
+    if let Some(transform) = reference.geo_transform() {
+        vrt.set_geo_transform_checked(&transform)?;
+    }
+    for band in bands {
+        if gdal::GDALAddBand(c_dataset, band.band_type(), null()) != 0 {
+            return Err(GdalError::CallFailed{desc: "GDALAddBand failed"});
+        }
+        let vrt_band = vrt.rasterband(vrt.count()).unwrap();
+        // the VRT takes a reference on the source dataset
+        let rv = gdal::VRTAddSimpleSource(
+            vrt_band._c_ptr(),
+            band._c_ptr(),
+            0, 0, size_x as c_int, size_y as c_int,
+            0, 0, size_x as c_int, size_y as c_int,
+            null(),
+            gdal::VRT_NODATA_UNSET,
+        );
+        if rv != 0 {
+            return Err(GdalError::CallFailed{desc: "VRTAddSimpleSource failed"});
+        }
+        if let Some(no_data) = band.no_data_value() {
+            vrt_band.set_no_data_value(no_data)?;
+        }
+    }
+    return Ok(vrt);
+}
+
+/// Check that `bands` are not empty, aligned and regular bands.
+fn check_aligned(bands: &[&RasterBand]) -> Result<(), GdalError> {
+    let first = match bands.first() {
+        Some(first) => first,
+        None => return Err(GdalError::CallFailed{desc: "no bands to stack"}),
+    };
+    let reference = first.owning_dataset();
+    let size = first.size();
+    let transform = reference.geo_transform();
+    let projection = reference.projection();
+    for band in bands {
+        let dataset = band.owning_dataset();
+        if band.size() != size || dataset.projection() != projection {
+            return Err(GdalError::CallFailed{desc: "bands are not aligned"});
+        }
+        let aligned = match (dataset.geo_transform(), transform) {
+            (Some(tr), Some(reference_tr)) => tr.iter().zip(reference_tr.iter())
+                .all(|(a, b)| (a - b).abs() <= 1e-9 * a.abs().max(b.abs()).max(1.)),
+            (None, None) => true,
+            _ => false,
+        };
+        if !aligned {
+            return Err(GdalError::CallFailed{desc: "bands are not aligned"});
+        }
+        if band.index() < 1 {
+            return Err(GdalError::CallFailed{desc: "mask bands and overviews can't be stacked"});
+        }
+    }
+    return Ok(());
+}
+
+/// The name a VRT file can reopen the dataset of `band` with.
+fn source_filename(band: &RasterBand) -> Result<String, GdalError> {
+    let dataset = band.owning_dataset();
+    if dataset.driver().short_name() == "MEM" {
+        return Err(GdalError::CallFailed{desc: "bands of MEM datasets can't be referenced by a VRT file"});
+    }
+    let description = dataset.description();
+    if description.starts_with("/vsi") {
+        return Ok(description);
+    }
+    let path = match fs::canonicalize(&description) {
+        Ok(path) => path,
+        Err(_) => return Err(GdalError::CallFailed{desc: "band source is not a file a VRT can reference"}),
+    };
+    return match path.to_str() {
+        Some(path) => Ok(path.to_string()),
+        None => Err(GdalError::CallFailed{desc: "band source path is not valid UTF-8"}),
+    };
+}
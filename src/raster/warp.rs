@@ -1,22 +1,243 @@
-use libc::c_double;
-use std::ptr::null;
-use raster::{gdal, Dataset};
+use libc::{c_char, c_double, c_void};
+use std::marker::PhantomData;
+use std::ops::Deref;
+use std::ptr::{null, null_mut};
+use raster::{gdal, Dataset, Driver};
+use raster::dataset::GeoTransform;
 use raster::gdal_enums::GDALResampleAlg;
+use config::NumThreads;
+use utils::_cstring;
+use GdalError;
 
 pub fn reproject(src: &Dataset, dst: &Dataset) {
-    let rv = unsafe {
+    let rv = _reproject(src, dst, GDALResampleAlg::GRA_Bilinear);
+    assert!(rv == 0);
+}
+
+fn _reproject(src: &Dataset, dst: &Dataset, resampling: GDALResampleAlg) -> isize {
+    return _reproject_with_options(src, dst, resampling, null());
+}
+
+fn _reproject_with_options(src: &Dataset, dst: &Dataset, resampling: GDALResampleAlg, c_options: *const c_void) -> isize {
+    return unsafe {
         gdal::GDALReprojectImage(
                 src._c_ptr(),
                 null(),
                 dst._c_ptr(),
                 null(),
-                GDALResampleAlg::GRA_Bilinear,
+                resampling,
                 gdal::REPROJECT_MEMORY_LIMIT,
                 0.0 as c_double,
                 null(),
                 null(),
-                null()
+                c_options
             )
     } as isize;
-    assert!(rv == 0);
+}
+
+/// Builder for the warp options of `align_with_options` and
+/// `reproject_to_match_with_options`, the `papszWarpOptions` of GDAL's
+/// warper.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct WarpOptions {
+    num_threads: Option<NumThreads>,
+}
+
+impl WarpOptions {
+    pub fn new() -> WarpOptions {
+        return WarpOptions::default();
+    }
+
+    /// Warp chunks with several threads. Defaults to `GDAL_NUM_THREADS`,
+    /// see `config::set_num_threads`.
+    pub fn num_threads(mut self, threads: NumThreads) -> WarpOptions {
+        self.num_threads = Some(threads);
+        return self;
+    }
+
+    pub fn options(&self) -> Vec<(String, String)> {
+        let mut options = Vec::new();
+        if let Some(threads) = self.num_threads {
+            options.push(("NUM_THREADS".to_string(), threads.value()));
+        }
+        return options;
+    }
+}
+
+/// A virtual dataset reprojecting another one on the fly, returned by
+/// `Dataset::warped_view`. It derefs to `Dataset` and can't outlive the
+/// source dataset it reads from.
+pub struct WarpedView<'a> {
+    dataset: Dataset,
+    _source: PhantomData<&'a Dataset>,
+}
+
+impl<'a> WarpedView<'a> {
+    pub unsafe fn _with_c_ptr(c_dataset: *const c_void) -> WarpedView<'a> {
+        return WarpedView{dataset: Dataset::_with_c_ptr(c_dataset), _source: PhantomData};
+    }
+}
+
+impl<'a> Deref for WarpedView<'a> {
+    type Target = Dataset;
+
+    fn deref(&self) -> &Dataset {
+        return &self.dataset;
+    }
+}
+
+/// A target raster grid for `align`: a north-up grid of `size` pixels
+/// whose top left corner is at `origin`, in the coordinate system
+/// described by the WKT `projection`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GridSpec {
+    pub origin: (f64, f64),
+    /// Pixel width and height. The height is positive, even though rows
+    /// go south.
+    pub pixel_size: (f64, f64),
+    pub size: (usize, usize),
+    pub projection: String,
+}
+
+impl GridSpec {
+    /// The grid of an existing dataset, to co-register other rasters
+    /// with it.
+    pub fn from_dataset(dataset: &Dataset) -> Option<GridSpec> {
+        let transform = dataset.geo_transform()?;
+        let (size_x, size_y) = dataset.size();
+        return Some(GridSpec{
+            origin: (transform[0], transform[3]),
+            pixel_size: (transform[1], -transform[5]),
+            size: (size_x as usize, size_y as usize),
+            projection: dataset.projection(),
+        });
+    }
+
+    /// The grid covering the extent `(min_x, min_y, max_x, max_y)` with
+    /// square pixels of `pixel_size`, rounding the size up to whole pixels.
+    pub fn from_extent(extent: (f64, f64, f64, f64), pixel_size: f64, projection: &str) -> GridSpec {
+        let (min_x, min_y, max_x, max_y) = extent;
+        return GridSpec{
+            origin: (min_x, max_y),
+            pixel_size: (pixel_size, pixel_size),
+            size: (
+                ((max_x - min_x) / pixel_size).ceil() as usize,
+                ((max_y - min_y) / pixel_size).ceil() as usize,
+            ),
+            projection: projection.to_string(),
+        };
+    }
+
+    pub fn geo_transform(&self) -> GeoTransform {
+        return [self.origin.0, self.pixel_size.0, 0., self.origin.1, 0., -self.pixel_size.1];
+    }
+}
+
+/// Warp `dataset` onto `grid` in memory, so rasters from different sources
+/// can be combined pixel by pixel. Every band is carried over with its
+/// data type and nodata value; areas of the grid not covered by the
+/// source are left at zero. Warping uses as many threads as
+/// `GDAL_NUM_THREADS` allows, see `config::with_num_threads`.
+pub fn align(dataset: &Dataset, grid: &GridSpec) -> Result<Dataset, GdalError> {
+    return align_with_options(dataset, grid, &WarpOptions::new());
+}
+
+/// Same as `align`, with warp `options`, e.g. the number of threads.
+pub fn align_with_options(dataset: &Dataset, grid: &GridSpec, options: &WarpOptions) -> Result<Dataset, GdalError> {
+    return warp_onto(
+        dataset,
+        grid.size,
+        &grid.geo_transform(),
+        &grid.projection,
+        GDALResampleAlg::GRA_Bilinear,
+        options,
+    );
+}
+
+/// Warp `src` onto exactly the grid of `reference`: same size,
+/// geo-transform and projection, using `resampling`. The result is an in
+/// memory dataset with the bands of `src`, like with `align`.
+pub fn reproject_to_match(src: &Dataset, reference: &Dataset, resampling: GDALResampleAlg) -> Result<Dataset, GdalError> {
+    return reproject_to_match_with_options(src, reference, resampling, &WarpOptions::new());
+}
+
+/// Same as `reproject_to_match`, with warp `options`, e.g. the number of
+/// threads.
+pub fn reproject_to_match_with_options(
+    src: &Dataset,
+    reference: &Dataset,
+    resampling: GDALResampleAlg,
+    options: &WarpOptions,
+) -> Result<Dataset, GdalError> {
+    let geo_transform = match reference.geo_transform() {
+        Some(geo_transform) => geo_transform,
+        None => return Err(GdalError::CallFailed{desc: "reference dataset has no geo-transform"}),
+    };
+    let (size_x, size_y) = reference.size();
+    return warp_onto(
+        src,
+        (size_x as usize, size_y as usize),
+        &geo_transform,
+        &reference.projection(),
+        resampling,
+        options,
+    );
+}
+
+fn warp_onto(
+    dataset: &Dataset,
+    size: (usize, usize),
+    geo_transform: &GeoTransform,
+    projection: &str,
+    resampling: GDALResampleAlg,
+    options: &WarpOptions,
+) -> Result<Dataset, GdalError> {
+    let mut c_names_values = vec!();
+    for (name, value) in options.options() {
+        c_names_values.push((_cstring(&name)?, _cstring(&value)?));
+    }
+    let band_type = match dataset.get_band_type(1) {
+        Some(band_type) => band_type,
+        None => return Err(GdalError::CallFailed{desc: "dataset has no bands"}),
+    };
+    let driver = match Driver::get("MEM") {
+        Some(driver) => driver,
+        None => return Err(GdalError::CallFailed{desc: "MEM driver not available"}),
+    };
+    let warped = match driver.create_with_data_type(
+        "",
+        size.0 as isize,
+        size.1 as isize,
+        dataset.count(),
+        band_type,
+        &[],
+    ) {
+        Some(warped) => warped,
+        None => return Err(GdalError::CallFailed{desc: "failed to create warped raster"}),
+    };
+    warped.set_geo_transform_checked(geo_transform)?;
+    warped.set_projection(projection)?;
+    for band_index in 1..dataset.count() + 1 {
+        let no_data = dataset.rasterband(band_index).and_then(|band| band.no_data_value());
+        if let Some(no_data) = no_data {
+            warped.rasterband(band_index).unwrap().set_no_data_value(no_data)?;
+        }
+    }
+    let rv = unsafe {
+        let c_options = gdal::GDALCreateWarpOptions();
+        let mut c_warp_options: *mut *mut c_char = null_mut();
+        for (c_name, c_value) in &c_names_values {
+            c_warp_options = gdal::CSLSetNameValue(c_warp_options, c_name.as_ptr(), c_value.as_ptr());
+        }
+        // papszWarpOptions is the first member of GDALWarpOptions, and
+        // GDALDestroyWarpOptions frees it with the rest
+        *(c_options as *mut *mut *mut c_char) = c_warp_options;
+        let rv = _reproject_with_options(dataset, &warped, resampling, c_options);
+        gdal::GDALDestroyWarpOptions(c_options);
+        rv
+    };
+    return match rv {
+        0 => Ok(warped),
+        _ => Err(GdalError::CallFailed{desc: "GDALReprojectImage failed"}),
+    };
 }
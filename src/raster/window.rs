@@ -0,0 +1,288 @@
+use std::mem;
+use GdalError;
+
+/// Size of a raster or buffer in pixels.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RasterSize {
+    pub width: usize,
+    pub height: usize,
+}
+
+impl RasterSize {
+    pub fn new(width: usize, height: usize) -> RasterSize {
+        return RasterSize{width: width, height: height};
+    }
+
+    /// Number of pixels. Fails if it doesn't fit into `usize`.
+    pub fn pixels(&self) -> Result<usize, GdalError> {
+        return match self.width.checked_mul(self.height) {
+            Some(pixels) => Ok(pixels),
+            None => Err(GdalError::CallFailed{desc: "raster size overflows"}),
+        };
+    }
+}
+
+/// Where the pixels of a caller-owned buffer are, counted in elements of
+/// the buffer: pixel `(x, y)` of band `b` is at
+/// `y * line_space + x * pixel_space + b * band_space`. This is how GDAL
+/// reads straight into, or writes straight from, interleaved buffers like
+/// RGBA textures, see `RasterBand::read_into` and `Dataset::read_into`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BufferLayout {
+    pub pixel_space: usize,
+    pub line_space: usize,
+    pub band_space: usize,
+}
+
+impl BufferLayout {
+    /// One band after the other, each `width` pixels wide row by row, like
+    /// the data of a `Buffer`. Fails if a band doesn't fit into `usize`
+    /// elements.
+    pub fn band_sequential(size: RasterSize) -> Result<BufferLayout, GdalError> {
+        return Ok(BufferLayout{pixel_space: 1, line_space: size.width, band_space: size.pixels()?});
+    }
+
+    /// The values of all `band_count` bands of a pixel next to each other,
+    /// e.g. RGBA for 4 bands. Fails if a row doesn't fit into `usize`
+    /// elements.
+    pub fn pixel_interleaved(size: RasterSize, band_count: usize) -> Result<BufferLayout, GdalError> {
+        let line_space = match size.width.checked_mul(band_count) {
+            Some(line_space) => line_space,
+            None => return Err(GdalError::CallFailed{desc: "raster size overflows"}),
+        };
+        return Ok(BufferLayout{pixel_space: band_count, line_space: line_space, band_space: 1});
+    }
+
+    /// Number of elements a buffer of `size` pixels and `band_count` bands
+    /// needs with this layout. Fails if it doesn't fit into `usize`.
+    pub fn required_len(&self, size: RasterSize, band_count: usize) -> Result<usize, GdalError> {
+        if size.pixels()? == 0 || band_count == 0 {
+            return Ok(0);
+        }
+        let last = (size.height - 1).checked_mul(self.line_space)
+            .and_then(|len| (size.width - 1).checked_mul(self.pixel_space).and_then(|x| len.checked_add(x)))
+            .and_then(|len| (band_count - 1).checked_mul(self.band_space).and_then(|b| len.checked_add(b)));
+        return match last.and_then(|last| last.checked_add(1)) {
+            Some(len) => Ok(len),
+            None => Err(GdalError::CallFailed{desc: "buffer layout overflows"}),
+        };
+    }
+}
+
+/// A rectangle of pixels of a raster, `x_size` by `y_size` pixels starting
+/// at column `x_off` and row `y_off`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Window {
+    pub x_off: usize,
+    pub y_off: usize,
+    pub x_size: usize,
+    pub y_size: usize,
+}
+
+impl Window {
+    pub fn new(x_off: usize, y_off: usize, x_size: usize, y_size: usize) -> Window {
+        return Window{x_off: x_off, y_off: y_off, x_size: x_size, y_size: y_size};
+    }
+
+    /// The window of `size` pixels at `offset`, as yielded by `Windows`.
+    /// Negative offsets turn into windows that fail `validate`.
+    pub fn from_tuples(offset: (isize, isize), size: (usize, usize)) -> Window {
+        return Window::new(offset.0 as usize, offset.1 as usize, size.0, size.1);
+    }
+
+    /// The window covering a whole raster of `size`.
+    pub fn full(size: RasterSize) -> Window {
+        return Window::new(0, 0, size.width, size.height);
+    }
+
+    pub fn size(&self) -> RasterSize {
+        return RasterSize::new(self.x_size, self.y_size);
+    }
+
+    /// Check that the window isn't empty and lies inside a raster of
+    /// `raster_size`.
+    pub fn validate(&self, raster_size: RasterSize) -> Result<(), GdalError> {
+        if self.x_size == 0 || self.y_size == 0 {
+            return Err(GdalError::CallFailed{desc: "empty window"});
+        }
+        let x_end = self.x_off.checked_add(self.x_size);
+        let y_end = self.y_off.checked_add(self.y_size);
+        match (x_end, y_end) {
+            (Some(x_end), Some(y_end)) if x_end <= raster_size.width && y_end <= raster_size.height => {},
+            _ => return Err(GdalError::CallFailed{desc: "window out of bounds"}),
+        }
+        return Ok(());
+    }
+}
+
+/// Order in which `Windows` visits the raster.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum WindowOrder {
+    /// Left to right, top to bottom.
+    RowMajor,
+    /// Visit every window inside a block before moving on to the next
+    /// block, so each compressed block only has to be decoded once.
+    BlockMajor,
+    /// Follow a Hilbert curve over the window grid, which keeps
+    /// consecutive windows spatially close in both directions.
+    Hilbert,
+}
+
+/// Iterator over the windows covering a raster, yielding
+/// `(window, window_size)` tuples, see `Window::from_tuples` for passing
+/// them to `read_raster`.
+///
+/// ```no_run
+/// use std::path::Path;
+/// use gdal::raster::{Dataset, Window, WindowOrder};
+///
+/// let dataset = Dataset::open(Path::new("fixtures/tinymarble.png")).unwrap();
+/// let band = dataset.rasterband(1).unwrap();
+/// for (window, window_size) in band.windows((64, 64), WindowOrder::BlockMajor, true) {
+///     let window = Window::from_tuples(window, window_size);
+///     let buffer = band.read_as::<u8>(window, window.size()).unwrap();
+///     // process buffer
+/// }
+/// ```
+pub struct Windows {
+    windows: Vec<((isize, isize), (usize, usize))>,
+    window_size: (usize, usize),
+    next_id: usize,
+}
+
+impl Windows {
+    /// Lay out windows of `window_size` over a raster of `raster_size`
+    /// whose natural block size is `block_size`. With `align_to_blocks`
+    /// the window size is rounded up to a whole number of blocks, so that
+    /// no window straddles a block boundary. Windows on the right and
+    /// bottom edges are clipped to the raster.
+    ///
+    /// Window and block sizes should be positive; a zero width or height is
+    /// taken as 1.
+    pub fn new(
+        raster_size: (usize, usize),
+        block_size: (usize, usize),
+        window_size: (usize, usize),
+        order: WindowOrder,
+        align_to_blocks: bool,
+    ) -> Windows {
+        let window_size = (window_size.0.max(1), window_size.1.max(1));
+        let block_size = (block_size.0.max(1), block_size.1.max(1));
+        let window_size = match align_to_blocks {
+            true  => (round_up(window_size.0, block_size.0), round_up(window_size.1, block_size.1)),
+            false => window_size,
+        };
+        let cols = (raster_size.0 + window_size.0 - 1) / window_size.0;
+        let rows = (raster_size.1 + window_size.1 - 1) / window_size.1;
+
+        let mut cells: Vec<(usize, usize)> = Vec::with_capacity(cols * rows);
+        for row in 0..rows {
+            for col in 0..cols {
+                cells.push((col, row));
+            }
+        }
+
+        match order {
+            WindowOrder::RowMajor => {},
+            WindowOrder::BlockMajor => {
+                cells.sort_by_key(|&(col, row)| {
+                    let block_col = col * window_size.0 / block_size.0;
+                    let block_row = row * window_size.1 / block_size.1;
+                    (block_row, block_col, row, col)
+                });
+            },
+            WindowOrder::Hilbert => {
+                let mut side = 1;
+                while side < cols.max(rows) {
+                    side *= 2;
+                }
+                cells.sort_by_key(|&(col, row)| hilbert_index(side, col, row));
+            },
+        }
+
+        let windows = cells.iter()
+            .map(|&(col, row)| {
+                let x = col * window_size.0;
+                let y = row * window_size.1;
+                let size_x = window_size.0.min(raster_size.0 - x);
+                let size_y = window_size.1.min(raster_size.1 - y);
+                ((x as isize, y as isize), (size_x, size_y))
+            })
+            .collect();
+        return Windows{windows: windows, window_size: window_size, next_id: 0};
+    }
+
+    /// The size of the windows that are not clipped by the raster edges,
+    /// after rounding up to whole blocks with `align_to_blocks`.
+    pub fn window_size(&self) -> (usize, usize) {
+        return self.window_size;
+    }
+
+    /// Whether a window of `size` yielded by this iterator was clipped by
+    /// the right or bottom edge of the raster, i.e. is smaller than
+    /// `window_size()`.
+    pub fn is_partial(&self, size: (usize, usize)) -> bool {
+        return size != self.window_size;
+    }
+
+    /// Yield `(window, window_size, is_partial)` tuples instead, for
+    /// algorithms that need to handle clipped edge windows on their own,
+    /// e.g. convolution kernels assuming full windows.
+    pub fn with_partial(self) -> PartialWindows {
+        return PartialWindows{windows: self};
+    }
+}
+
+/// Iterator returned by `Windows::with_partial`.
+pub struct PartialWindows {
+    windows: Windows,
+}
+
+impl Iterator for PartialWindows {
+    type Item = ((isize, isize), (usize, usize), bool);
+
+    fn next(&mut self) -> Option<((isize, isize), (usize, usize), bool)> {
+        let (window, size) = self.windows.next()?;
+        return Some((window, size, self.windows.is_partial(size)));
+    }
+}
+
+impl Iterator for Windows {
+    type Item = ((isize, isize), (usize, usize));
+
+    #[inline]
+    fn next(&mut self) -> Option<((isize, isize), (usize, usize))> {
+        if self.next_id == self.windows.len() {
+            return None;
+        }
+        let window = self.windows[self.next_id];
+        self.next_id += 1;
+        return Some(window);
+    }
+}
+
+fn round_up(value: usize, multiple: usize) -> usize {
+    return (value + multiple - 1) / multiple * multiple;
+}
+
+/// Position of cell (x, y) along a Hilbert curve filling a `side` x `side`
+/// square, where `side` is a power of two.
+fn hilbert_index(side: usize, x: usize, y: usize) -> usize {
+    let (mut x, mut y) = (x, y);
+    let mut d = 0;
+    let mut s = side / 2;
+    while s > 0 {
+        let rx = if x & s > 0 { 1 } else { 0 };
+        let ry = if y & s > 0 { 1 } else { 0 };
+        d += s * s * ((3 * rx) ^ ry);
+        if ry == 0 {
+            if rx == 1 {
+                x = side - 1 - x;
+                y = side - 1 - y;
+            }
+            mem::swap(&mut x, &mut y);
+        }
+        s /= 2;
+    }
+    return d;
+}
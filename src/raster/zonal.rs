@@ -0,0 +1,172 @@
+use std::collections::HashMap;
+use std::ptr::null;
+use libc::{c_int, c_double};
+use raster::{gdal, Driver, RasterBand, Window};
+use raster::dataset::{apply_geo_transform, invert_geo_transform};
+use vector::Layer;
+use GdalError;
+
+/// A statistic `zonal_stats` can compute.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Statistic {
+    Count,
+    Sum,
+    Mean,
+    Min,
+    Max,
+    /// Population standard deviation.
+    Std,
+}
+
+/// Statistics of the pixels covered by one feature. Only the requested
+/// statistics are set; `mean`, `min`, `max` and `std` are also `None` when
+/// the feature doesn't cover any valid pixel.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ZonalStats {
+    pub count: Option<usize>,
+    pub sum: Option<f64>,
+    pub mean: Option<f64>,
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+    pub std: Option<f64>,
+}
+
+/// Compute `stats` over the pixels of `band` covered by each polygon of
+/// `layer`, returning the results keyed by feature id. A pixel is covered
+/// if its center is inside the polygon, and pixels masked out by the band
+/// (nodata, alpha) are ignored. Features without a geometry or feature id
+/// are skipped.
+///
+/// The layer must use the same coordinate system as the raster, and the
+/// raster must have a geo-transform.
+pub fn zonal_stats(
+    band: &RasterBand,
+    layer: &Layer,
+    stats: &[Statistic],
+) -> Result<HashMap<u64, ZonalStats>, GdalError> {
+    let transform = match band.owning_dataset().geo_transform() {
+        Some(transform) => transform,
+        None => return Err(GdalError::CallFailed{desc: "dataset has no geo-transform"}),
+    };
+    let inverse = match invert_geo_transform(&transform) {
+        Some(inverse) => inverse,
+        None => return Err(GdalError::CallFailed{desc: "geo-transform is not invertible"}),
+    };
+    let mem_driver = match Driver::get("MEM") {
+        Some(driver) => driver,
+        None => return Err(GdalError::CallFailed{desc: "MEM driver not available"}),
+    };
+    let (size_x, size_y) = band.size();
+
+    let mut results = HashMap::new();
+    for feature in layer.features() {
+        let fid = match feature.fid() {
+            Some(fid) => fid,
+            None => continue,
+        };
+        let geometry = match feature.geometry() {
+            Some(geometry) => geometry,
+            None => continue,
+        };
+
+        // pixel window covering the envelope, clipped to the raster
+        let (min_x, max_x, min_y, max_y) = geometry._envelope();
+        let corners = [
+            apply_geo_transform(&inverse, min_x, min_y),
+            apply_geo_transform(&inverse, min_x, max_y),
+            apply_geo_transform(&inverse, max_x, min_y),
+            apply_geo_transform(&inverse, max_x, max_y),
+        ];
+        let x0 = corners.iter().fold(size_x as f64, |acc, c| acc.min(c.0)).floor().max(0.) as usize;
+        let y0 = corners.iter().fold(size_y as f64, |acc, c| acc.min(c.1)).floor().max(0.) as usize;
+        let x1 = corners.iter().fold(0., |acc: f64, c| acc.max(c.0)).ceil().min(size_x as f64) as usize;
+        let y1 = corners.iter().fold(0., |acc: f64, c| acc.max(c.1)).ceil().min(size_y as f64) as usize;
+
+        let mut accumulator = Accumulator::new();
+        if x1 > x0 && y1 > y0 {
+            let window = (x0 as isize, y0 as isize);
+            let window_size = (x1 - x0, y1 - y0);
+
+            let zone = match mem_driver.create("", window_size.0 as isize, window_size.1 as isize, 1) {
+                Some(zone) => zone,
+                None => return Err(GdalError::CallFailed{desc: "failed to create zone raster"}),
+            };
+            let (origin_x, origin_y) = apply_geo_transform(&transform, x0 as f64, y0 as f64);
+            zone.set_geo_transform_checked(&[origin_x, transform[1], transform[2], origin_y, transform[4], transform[5]])?;
+            let band_list: [c_int; 1] = [1];
+            let geometries = [unsafe { geometry.c_geometry() }];
+            let burn_values: [c_double; 1] = [1.];
+            let rv = unsafe { gdal::GDALRasterizeGeometries(
+                zone._c_ptr(),
+                1,
+                band_list.as_ptr(),
+                1,
+                geometries.as_ptr(),
+                null(),
+                null(),
+                burn_values.as_ptr(),
+                null(),
+                null(),
+                null()
+            ) };
+            if rv != 0 {
+                return Err(GdalError::CallFailed{desc: "failed to rasterize geometry"});
+            }
+
+            let window = Window::from_tuples(window, window_size);
+            let inside = zone.read_raster(1, Window::full(window.size()), window.size())?;
+            let valid = band.read_mask(window, window.size())?;
+            let values = band.read_as::<f64>(window, window.size())?;
+            for i in 0..values.data.len() {
+                if inside.data[i] != 0 && valid.data[i] != 0 {
+                    accumulator.add(values.data[i]);
+                }
+            }
+        }
+        results.insert(fid, accumulator.stats(stats));
+    }
+    return Ok(results);
+}
+
+struct Accumulator {
+    count: usize,
+    sum: f64,
+    sum_squares: f64,
+    min: f64,
+    max: f64,
+}
+
+impl Accumulator {
+    fn new() -> Accumulator {
+        return Accumulator{count: 0, sum: 0., sum_squares: 0., min: f64::INFINITY, max: f64::NEG_INFINITY};
+    }
+
+    fn add(&mut self, value: f64) {
+        self.count += 1;
+        self.sum += value;
+        self.sum_squares += value * value;
+        self.min = self.min.min(value);
+        self.max = self.max.max(value);
+    }
+
+    fn stats(&self, stats: &[Statistic]) -> ZonalStats {
+        let mut rv = ZonalStats::default();
+        let has_values = self.count > 0;
+        let mean = self.sum / self.count as f64;
+        for stat in stats {
+            match *stat {
+                Statistic::Count => rv.count = Some(self.count),
+                Statistic::Sum => rv.sum = Some(self.sum),
+                Statistic::Mean if has_values => rv.mean = Some(mean),
+                Statistic::Min if has_values => rv.min = Some(self.min),
+                Statistic::Max if has_values => rv.max = Some(self.max),
+                Statistic::Std if has_values => {
+                    let variance = self.sum_squares / self.count as f64 - mean * mean;
+                    rv.std = Some(variance.max(0.).sqrt());
+                },
+                _ => {},
+            }
+        }
+        return rv;
+    }
+}
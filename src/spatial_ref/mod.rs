@@ -0,0 +1,9 @@
+//! Spatial reference systems
+
+pub use spatial_ref::srs::{SpatialRef, CoordTransform};
+
+mod osr;
+pub mod srs;
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,25 @@
+use libc::{c_int, c_char, c_double, c_void};
+
+#[link(name="gdal")]
+extern {
+    pub fn OSRNewSpatialReference(pszWKT: *const c_char) -> *const c_void;
+    pub fn OSRClone(hSRS: *const c_void) -> *const c_void;
+    pub fn OSRRelease(hSRS: *const c_void);
+    pub fn OSRImportFromEPSG(hSRS: *const c_void, nCode: c_int) -> c_int;
+    pub fn OSRImportFromProj4(hSRS: *const c_void, pszProj4: *const c_char) -> c_int;
+    pub fn OSRExportToWkt(hSRS: *const c_void, ppszReturn: &mut *const c_char) -> c_int;
+    pub fn OSRExportToProj4(hSRS: *const c_void, ppszReturn: &mut *const c_char) -> c_int;
+    pub fn OSRIsSame(hSRS1: *const c_void, hSRS2: *const c_void) -> c_int;
+    pub fn OSRIsGeographic(hSRS: *const c_void) -> c_int;
+    pub fn OSRCloneGeogCS(hSRS: *const c_void) -> *const c_void;
+    pub fn OSRCopyGeogCSFrom(hSRS: *const c_void, hSrcSRS: *const c_void) -> c_int;
+    pub fn OSRSetLAEA(hSRS: *const c_void, dfCenterLat: c_double, dfCenterLong: c_double, dfFalseEasting: c_double, dfFalseNorthing: c_double) -> c_int;
+    pub fn OSRGetSemiMajor(hSRS: *const c_void, pnErr: *mut c_int) -> c_double;
+    pub fn OSRGetInvFlattening(hSRS: *const c_void, pnErr: *mut c_int) -> c_double;
+    pub fn OCTNewCoordinateTransformation(hSourceSRS: *const c_void, hTargetSRS: *const c_void) -> *const c_void;
+    pub fn OCTDestroyCoordinateTransformation(hCT: *const c_void);
+    pub fn OCTTransform(hCT: *const c_void, nCount: c_int, x: *mut c_double, y: *mut c_double, z: *mut c_double) -> c_int;
+    pub fn VSIFree(ptr: *mut c_void);
+}
+
+pub const OGRERR_NONE: c_int = 0;
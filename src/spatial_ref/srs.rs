@@ -0,0 +1,231 @@
+use std::ptr::{null, null_mut};
+use libc::{c_char, c_int, c_double, c_void};
+use spatial_ref::osr;
+use utils::{_cstring, _string};
+use GdalError;
+
+/// OGR Spatial Reference System
+///
+/// ```no_run
+/// use gdal::spatial_ref::SpatialRef;
+///
+/// let wgs84 = SpatialRef::from_epsg(4326).unwrap();
+/// println!("{}", wgs84.to_wkt().unwrap());
+/// ```
+pub struct SpatialRef {
+    c_srs: *const c_void,
+}
+
+
+impl SpatialRef {
+    pub unsafe fn _with_c_srs(c_srs: *const c_void) -> SpatialRef {
+        return SpatialRef{c_srs: c_srs};
+    }
+
+    /// Copy a spatial reference owned by someone else, e.g. a layer.
+    pub unsafe fn _clone_from_c_srs(c_srs: *const c_void) -> SpatialRef {
+        let c_clone = osr::OSRClone(c_srs);
+        assert!(!c_clone.is_null());
+        return SpatialRef{c_srs: c_clone};
+    }
+
+    pub unsafe fn _c_ptr(&self) -> *const c_void {
+        return self.c_srs;
+    }
+
+    fn empty() -> SpatialRef {
+        let c_srs = unsafe { osr::OSRNewSpatialReference(null()) };
+        assert!(!c_srs.is_null());
+        return SpatialRef{c_srs: c_srs};
+    }
+
+    /// Create a spatial reference from its WKT definition.
+    pub fn from_wkt(wkt: &str) -> Result<SpatialRef, GdalError> {
+        let c_wkt = _cstring(wkt)?;
+        let c_srs = unsafe { osr::OSRNewSpatialReference(c_wkt.as_ptr()) };
+        return match c_srs.is_null() {
+            true  => Err(GdalError::CallFailed{desc: "invalid WKT spatial reference"}),
+            false => Ok(SpatialRef{c_srs: c_srs}),
+        };
+    }
+
+    /// Create a spatial reference from an EPSG code, e.g. 4326 for WGS84.
+    pub fn from_epsg(epsg_code: u32) -> Result<SpatialRef, GdalError> {
+        let srs = SpatialRef::empty();
+        let rv = unsafe { osr::OSRImportFromEPSG(srs.c_srs, epsg_code as c_int) };
+        return match rv {
+            osr::OGRERR_NONE => Ok(srs),
+            _ => Err(GdalError::CallFailed{desc: "unknown EPSG code"}),
+        };
+    }
+
+    /// Create a spatial reference from a PROJ.4 string, e.g.
+    /// `+proj=longlat +datum=WGS84 +no_defs`.
+    pub fn from_proj4(proj4: &str) -> Result<SpatialRef, GdalError> {
+        let c_proj4 = _cstring(proj4)?;
+        let srs = SpatialRef::empty();
+        let rv = unsafe { osr::OSRImportFromProj4(srs.c_srs, c_proj4.as_ptr()) };
+        return match rv {
+            osr::OGRERR_NONE => Ok(srs),
+            _ => Err(GdalError::CallFailed{desc: "invalid PROJ.4 spatial reference"}),
+        };
+    }
+
+    pub fn to_wkt(&self) -> Result<String, GdalError> {
+        let mut c_wkt: *const c_char = null();
+        let rv = unsafe { osr::OSRExportToWkt(self.c_srs, &mut c_wkt) };
+        return export_result(rv, c_wkt);
+    }
+
+    pub fn to_proj4(&self) -> Result<String, GdalError> {
+        let mut c_proj4: *const c_char = null();
+        let rv = unsafe { osr::OSRExportToProj4(self.c_srs, &mut c_proj4) };
+        return export_result(rv, c_proj4);
+    }
+
+    /// Whether both spatial references describe the same coordinate
+    /// system, even if their definitions are written differently.
+    pub fn is_same(&self, other: &SpatialRef) -> bool {
+        return unsafe { osr::OSRIsSame(self.c_srs, other.c_srs) } != 0;
+    }
+
+    /// Whether coordinates are longitudes and latitudes.
+    pub fn is_geographic(&self) -> bool {
+        return unsafe { osr::OSRIsGeographic(self.c_srs) } != 0;
+    }
+
+    /// The geographic coordinate system this one is based on, e.g. WGS84
+    /// for UTM zones.
+    pub fn geog_cs(&self) -> Result<SpatialRef, GdalError> {
+        let c_srs = unsafe { osr::OSRCloneGeogCS(self.c_srs) };
+        return match c_srs.is_null() {
+            true  => Err(GdalError::CallFailed{desc: "spatial reference has no geographic coordinate system"}),
+            false => Ok(SpatialRef{c_srs: c_srs}),
+        };
+    }
+
+    /// A Lambert azimuthal equal-area projection centered on `center_lon`
+    /// and `center_lat`, on the geographic coordinate system of this one.
+    pub fn lambert_azimuthal_equal_area(&self, center_lon: f64, center_lat: f64) -> Result<SpatialRef, GdalError> {
+        let srs = SpatialRef::empty();
+        let rv = unsafe { osr::OSRCopyGeogCSFrom(srs.c_srs, self.c_srs) };
+        if rv != osr::OGRERR_NONE {
+            return Err(GdalError::CallFailed{desc: "OSRCopyGeogCSFrom failed"});
+        }
+        let rv = unsafe { osr::OSRSetLAEA(srs.c_srs, center_lat as c_double, center_lon as c_double, 0., 0.) };
+        return match rv {
+            osr::OGRERR_NONE => Ok(srs),
+            _ => Err(GdalError::CallFailed{desc: "OSRSetLAEA failed"}),
+        };
+    }
+
+    /// The semi-major axis of the ellipsoid in meters.
+    pub fn semi_major(&self) -> Result<f64, GdalError> {
+        let mut rv: c_int = osr::OGRERR_NONE;
+        let semi_major = unsafe { osr::OSRGetSemiMajor(self.c_srs, &mut rv) };
+        return match rv {
+            osr::OGRERR_NONE => Ok(semi_major as f64),
+            _ => Err(GdalError::CallFailed{desc: "OSRGetSemiMajor failed"}),
+        };
+    }
+
+    /// The inverse flattening of the ellipsoid, 0 for spheres.
+    pub fn inv_flattening(&self) -> Result<f64, GdalError> {
+        let mut rv: c_int = osr::OGRERR_NONE;
+        let inv_flattening = unsafe { osr::OSRGetInvFlattening(self.c_srs, &mut rv) };
+        return match rv {
+            osr::OGRERR_NONE => Ok(inv_flattening as f64),
+            _ => Err(GdalError::CallFailed{desc: "OSRGetInvFlattening failed"}),
+        };
+    }
+}
+
+
+fn export_result(rv: c_int, c_text: *const c_char) -> Result<String, GdalError> {
+    let result = match rv {
+        osr::OGRERR_NONE => Ok(_string(c_text)),
+        _ => Err(GdalError::CallFailed{desc: "failed to export spatial reference"}),
+    };
+    if !c_text.is_null() {
+        unsafe { osr::VSIFree(c_text as *mut c_void) };
+    }
+    return result;
+}
+
+
+impl Clone for SpatialRef {
+    fn clone(&self) -> SpatialRef {
+        return unsafe { SpatialRef::_clone_from_c_srs(self.c_srs) };
+    }
+}
+
+
+/// Spatial references are reference counted by GDAL, e.g. geometries
+/// keep a reference to theirs, so dropping only releases ours.
+impl Drop for SpatialRef {
+    fn drop(&mut self) {
+        unsafe { osr::OSRRelease(self.c_srs) };
+    }
+}
+
+
+/// Transformation of coordinates from one spatial reference to another.
+///
+/// ```no_run
+/// use gdal::spatial_ref::{SpatialRef, CoordTransform};
+///
+/// let wgs84 = SpatialRef::from_epsg(4326).unwrap();
+/// let mercator = SpatialRef::from_epsg(3857).unwrap();
+/// let transform = CoordTransform::new(&wgs84, &mercator).unwrap();
+/// let (mut xs, mut ys, mut zs) = (vec![26.1], vec![44.4], vec![0.]);
+/// transform.transform_coords(&mut xs, &mut ys, &mut zs).unwrap();
+/// ```
+pub struct CoordTransform {
+    c_transform: *const c_void,
+}
+
+
+impl CoordTransform {
+    /// Create a transformation from `source` to `target`. Fails if PROJ
+    /// can't transform between them.
+    pub fn new(source: &SpatialRef, target: &SpatialRef) -> Result<CoordTransform, GdalError> {
+        let c_transform = unsafe { osr::OCTNewCoordinateTransformation(source.c_srs, target.c_srs) };
+        return match c_transform.is_null() {
+            true  => Err(GdalError::CallFailed{desc: "OCTNewCoordinateTransformation failed"}),
+            false => Ok(CoordTransform{c_transform: c_transform}),
+        };
+    }
+
+    pub unsafe fn _c_ptr(&self) -> *const c_void {
+        return self.c_transform;
+    }
+
+    /// Transform the points `(xs[i], ys[i], zs[i])` in place. `zs` may be
+    /// empty for 2D points; otherwise all slices need the same length.
+    pub fn transform_coords(&self, xs: &mut [f64], ys: &mut [f64], zs: &mut [f64]) -> Result<(), GdalError> {
+        if xs.len() != ys.len() || (!zs.is_empty() && zs.len() != xs.len()) {
+            return Err(GdalError::CallFailed{desc: "coordinate slices of different lengths"});
+        }
+        let rv = unsafe { osr::OCTTransform(
+            self.c_transform,
+            xs.len() as c_int,
+            xs.as_mut_ptr() as *mut c_double,
+            ys.as_mut_ptr() as *mut c_double,
+            match zs.is_empty() {
+                true  => null_mut(),
+                false => zs.as_mut_ptr() as *mut c_double,
+            },
+        ) };
+        return match rv {
+            0 => Err(GdalError::CallFailed{desc: "OCTTransform failed"}),
+            _ => Ok(()),
+        };
+    }
+}
+
+
+impl Drop for CoordTransform {
+    fn drop(&mut self) {
+        unsafe { osr::OCTDestroyCoordinateTransformation(self.c_transform) };
+    }
+}
@@ -0,0 +1,45 @@
+use super::{SpatialRef, CoordTransform};
+
+
+#[test]
+fn test_from_epsg() {
+    let wgs84 = SpatialRef::from_epsg(4326).unwrap();
+    assert_eq!(wgs84.to_wkt().unwrap().chars().take(16).collect::<String>(), "GEOGCS[\"WGS 84\",");
+    assert!(SpatialRef::from_epsg(1).is_err());
+}
+
+
+#[test]
+fn test_from_wkt_and_proj4() {
+    let wgs84 = SpatialRef::from_epsg(4326).unwrap();
+    let from_wkt = SpatialRef::from_wkt(&wgs84.to_wkt().unwrap()).unwrap();
+    assert!(from_wkt.is_same(&wgs84));
+
+    let from_proj4 = SpatialRef::from_proj4("+proj=longlat +datum=WGS84 +no_defs").unwrap();
+    assert!(from_proj4.is_same(&wgs84));
+    assert!(from_proj4.to_proj4().unwrap().contains("+proj=longlat"));
+
+    let mercator = SpatialRef::from_epsg(3857).unwrap();
+    assert!(!mercator.is_same(&wgs84));
+    assert!(mercator.clone().is_same(&mercator));
+
+    let laea = wgs84.lambert_azimuthal_equal_area(10., 52.).unwrap();
+    assert!(!laea.is_geographic());
+    assert!(laea.geog_cs().unwrap().is_same(&wgs84));
+    assert!(laea.to_proj4().unwrap().contains("+proj=laea +lat_0=52 +lon_0=10"));
+}
+
+
+#[test]
+fn test_coord_transform() {
+    let wgs84 = SpatialRef::from_epsg(4326).unwrap();
+    let mercator = SpatialRef::from_epsg(3857).unwrap();
+    let transform = CoordTransform::new(&wgs84, &mercator).unwrap();
+    let mut xs = [0., 180.];
+    let mut ys = [0., 0.];
+    transform.transform_coords(&mut xs, &mut ys, &mut []).unwrap();
+    assert_eq!(xs[0], 0.);
+    assert!((xs[1] - 20037508.34).abs() < 0.01);
+    assert!(ys[1].abs() < 1e-6);
+    assert!(transform.transform_coords(&mut xs, &mut [0.], &mut []).is_err());
+}
@@ -1,9 +1,68 @@
 use libc::c_char;
-use std::ffi::CStr;
+use std::ffi::{CStr, CString};
+use std::ptr::null;
 use std::str;
+use std::str::Utf8Error;
+use GdalError;
 
 
 pub fn _string(raw_ptr: *const c_char) -> String {
     let c_str = unsafe { CStr::from_ptr(raw_ptr) };
     return str::from_utf8(c_str.to_bytes()).unwrap().to_string();
 }
+
+pub fn _string_checked(raw_ptr: *const c_char) -> Result<String, Utf8Error> {
+    let c_str = unsafe { CStr::from_ptr(raw_ptr) };
+    return str::from_utf8(c_str.to_bytes()).map(|s| s.to_string());
+}
+
+pub fn _string_lossy(raw_ptr: *const c_char) -> String {
+    let c_str = unsafe { CStr::from_ptr(raw_ptr) };
+    return c_str.to_string_lossy().into_owned();
+}
+
+/// Convert `string` for passing to GDAL, failing if it contains a NUL
+/// byte instead of panicking.
+pub fn _cstring(string: &str) -> Result<CString, GdalError> {
+    return Ok(CString::new(string.as_bytes())?);
+}
+
+pub fn _string_array(raw_ptr: *const *const c_char) -> Vec<String> {
+    let mut strings = Vec::new();
+    if raw_ptr.is_null() {
+        return strings;
+    }
+    let mut i = 0;
+    loop {
+        let next = unsafe { *raw_ptr.offset(i) };
+        if next.is_null() {
+            break;
+        }
+        strings.push(_string(next));
+        i += 1;
+    }
+    return strings;
+}
+
+/// A NULL terminated list of C strings, as expected by the `papszOptions`
+/// arguments of GDAL functions.
+pub struct CslStringList {
+    _strings: Vec<CString>,
+    ptrs: Vec<*const c_char>,
+}
+
+impl CslStringList {
+    pub fn new(strings: &[String]) -> Result<CslStringList, GdalError> {
+        let mut c_strings = Vec::with_capacity(strings.len());
+        for s in strings {
+            c_strings.push(_cstring(s)?);
+        }
+        let mut ptrs: Vec<*const c_char> = c_strings.iter().map(|s| s.as_ptr()).collect();
+        ptrs.push(null());
+        return Ok(CslStringList{_strings: c_strings, ptrs: ptrs});
+    }
+
+    pub fn as_ptr(&self) -> *const *const c_char {
+        return self.ptrs.as_ptr();
+    }
+}
@@ -0,0 +1,144 @@
+//! Bulk writing of Arrow record batches with `OGR_L_WriteArrowBatch`.
+//! Only available with the `arrow` feature, and needs GDAL 3.8.
+//!
+//! Batches are passed through the [Arrow C data
+//! interface](https://arrow.apache.org/docs/format/CDataInterface.html),
+//! so they can come from any Arrow implementation without this crate
+//! depending on one: export the schema and struct array of a record batch
+//! (e.g. with `arrow::ffi::FFI_ArrowSchema` and `FFI_ArrowArray`, or
+//! polars' `export_field_to_c` and `export_array_to_c`) and cast the
+//! pointers to `ArrowSchema` and `ArrowArray`.
+//!
+//! ```no_run
+//! use std::path::Path;
+//! use gdal::vector::Driver;
+//! use gdal::vector::arrow::{ArrowArray, ArrowSchema};
+//!
+//! # fn export() -> (ArrowSchema, ArrowArray) { unimplemented!() }
+//! let (schema, mut array) = export();
+//! let mut dataset = Driver::get("GPKG").unwrap().create(Path::new("out.gpkg")).unwrap();
+//! let layer = dataset.create_layer().unwrap();
+//! layer.create_fields_from_arrow_schema(&schema, &[]).unwrap();
+//! layer.write_arrow_batch(&schema, &mut array, &[]).unwrap();
+//! // GDAL may have moved the array; release what's left of it
+//! if let Some(release) = array.release {
+//!     unsafe { release(&mut array) };
+//! }
+//! ```
+
+use std::ptr::null_mut;
+use libc::{c_char, c_int, c_void};
+use utils::CslStringList;
+use vector::{ogr, Layer};
+use GdalError;
+
+/// `struct ArrowSchema` of the Arrow C data interface.
+#[repr(C)]
+pub struct ArrowSchema {
+    pub format: *const c_char,
+    pub name: *const c_char,
+    pub metadata: *const c_char,
+    pub flags: i64,
+    pub n_children: i64,
+    pub children: *mut *mut ArrowSchema,
+    pub dictionary: *mut ArrowSchema,
+    pub release: Option<unsafe extern "C" fn(*mut ArrowSchema)>,
+    pub private_data: *mut c_void,
+}
+
+/// `struct ArrowArray` of the Arrow C data interface.
+#[repr(C)]
+pub struct ArrowArray {
+    pub length: i64,
+    pub null_count: i64,
+    pub offset: i64,
+    pub n_buffers: i64,
+    pub n_children: i64,
+    pub buffers: *mut *const c_void,
+    pub children: *mut *mut ArrowArray,
+    pub dictionary: *mut ArrowArray,
+    pub release: Option<unsafe extern "C" fn(*mut ArrowArray)>,
+    pub private_data: *mut c_void,
+}
+
+impl ArrowSchema {
+    /// An empty, released schema, to be filled by an exporter.
+    pub fn empty() -> ArrowSchema {
+        return ArrowSchema{
+            format: null_mut(), name: null_mut(), metadata: null_mut(), flags: 0, n_children: 0,
+            children: null_mut(), dictionary: null_mut(), release: None, private_data: null_mut(),
+        };
+    }
+}
+
+impl ArrowArray {
+    /// An empty, released array, to be filled by an exporter.
+    pub fn empty() -> ArrowArray {
+        return ArrowArray{
+            length: 0, null_count: 0, offset: 0, n_buffers: 0, n_children: 0, buffers: null_mut(),
+            children: null_mut(), dictionary: null_mut(), release: None, private_data: null_mut(),
+        };
+    }
+}
+
+impl Layer {
+    /// Create a field for every child of the struct `schema`, except the
+    /// geometry columns with the `ogc.wkb` extension type, which are
+    /// written to the geometry field of the layer. `options` are those of
+    /// `OGR_L_CreateFieldFromArrowSchema`.
+    pub fn create_fields_from_arrow_schema(&mut self, schema: &ArrowSchema, options: &[String]) -> Result<(), GdalError> {
+        let c_options = CslStringList::new(options)?;
+        for i in 0..schema.n_children as isize {
+            let child = unsafe { *schema.children.offset(i) };
+            let metadata = unsafe { (*child).metadata };
+            if !metadata.is_null() && unsafe { extension_name(metadata) } == Some(&b"ogc.wkb"[..]) {
+                continue;
+            }
+            let rv = unsafe { ogr::OGR_L_CreateFieldFromArrowSchema(self._c_layer(), child, c_options.as_ptr()) };
+            if !rv {
+                return Err(GdalError::CallFailed{desc: "OGR_L_CreateFieldFromArrowSchema failed"});
+            }
+        }
+        return Ok(());
+    }
+
+    /// Write the features of the struct `array` described by `schema`,
+    /// whose fields must exist in the layer. Geometries are read from the
+    /// child with the `ogc.wkb` extension type, or named by the
+    /// `GEOMETRY_NAME` option; `FID` names the child holding feature ids.
+    ///
+    /// GDAL may move the array, leaving its `release` callback unset. If
+    /// it's still set after the call, the caller has to release the array
+    /// as usual.
+    pub fn write_arrow_batch(&mut self, schema: &ArrowSchema, array: &mut ArrowArray, options: &[String]) -> Result<(), GdalError> {
+        let c_options = CslStringList::new(options)?;
+        let rv = unsafe { ogr::OGR_L_WriteArrowBatch(self._c_layer(), schema, array, c_options.as_ptr()) };
+        return match rv {
+            true => Ok(()),
+            false => Err(GdalError::CallFailed{desc: "OGR_L_WriteArrowBatch failed"}),
+        };
+    }
+
+}
+
+/// The value of `ARROW:extension:name` in the metadata of a schema, which
+/// is encoded as a count of pairs followed by length prefixed keys and
+/// values, all lengths as native endian int32.
+unsafe fn extension_name<'a>(metadata: *const c_char) -> Option<&'a [u8]> {
+    let read_int = |p: *const u8| (p as *const c_int).read_unaligned() as usize;
+    let mut p = metadata as *const u8;
+    let pairs = read_int(p);
+    p = p.offset(4);
+    for _ in 0..pairs {
+        let key_len = read_int(p);
+        let key = ::std::slice::from_raw_parts(p.offset(4), key_len);
+        p = p.offset(4 + key_len as isize);
+        let value_len = read_int(p);
+        let value = ::std::slice::from_raw_parts(p.offset(4), value_len);
+        p = p.offset(4 + value_len as isize);
+        if key == b"ARROW:extension:name" {
+            return Some(value);
+        }
+    }
+    return None;
+}
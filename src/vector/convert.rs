@@ -0,0 +1,56 @@
+use vector::{ogr, Layer};
+use utils::_string;
+use GdalError;
+
+/// What `convert_features` does when a feature can't be written.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum OnError {
+    /// Record the failure in the report and go on with the next feature.
+    Skip,
+    /// Stop, returning the error.
+    Abort,
+}
+
+/// A feature that `convert_features` couldn't write.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FeatureError {
+    /// Feature id in the source layer, if it has one.
+    pub fid: Option<u64>,
+    pub error: GdalError,
+    /// The last message GDAL reported for the feature, e.g. why the driver
+    /// refused its geometry.
+    pub message: String,
+}
+
+/// Outcome of `convert_features`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ConversionReport {
+    /// Number of features written.
+    pub written: usize,
+    pub errors: Vec<FeatureError>,
+}
+
+/// Copy the features of `src` to `dst`, like `Layer::create_feature_from`,
+/// without letting one bad feature (a geometry type the driver doesn't
+/// accept, a value it can't store, ...) abort the whole conversion. With
+/// `OnError::Skip` the failures are collected in the report instead.
+pub fn convert_features(src: &Layer, dst: &mut Layer, on_error: OnError) -> Result<ConversionReport, GdalError> {
+    let mut report = ConversionReport::default();
+    for feature in src.features() {
+        unsafe { ogr::CPLErrorReset() };
+        match dst.create_feature_from(&feature) {
+            Ok(()) => report.written += 1,
+            Err(error) => {
+                if on_error == OnError::Abort {
+                    return Err(error);
+                }
+                report.errors.push(FeatureError{
+                    fid: feature.fid(),
+                    error: error,
+                    message: _string(unsafe { ogr::CPLGetLastErrorMsg() }),
+                });
+            },
+        }
+    }
+    return Ok(report);
+}
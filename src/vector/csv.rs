@@ -0,0 +1,237 @@
+//! CSV open and layer creation options
+//!
+//! ```no_run
+//! use std::path::Path;
+//! use gdal::vector::csv::{CsvOpenOptions, CsvLayerOptions, GeometryEncoding, write_csv};
+//!
+//! let mut points = CsvOpenOptions::new()
+//!     .x_y_columns("lon", "lat")
+//!     .autodetect_type(true)
+//!     .open(Path::new("points.csv"))
+//!     .unwrap();
+//! let layer = points.layer(0).unwrap();
+//! let options = CsvLayerOptions::new().geometry(GeometryEncoding::AsWkt);
+//! write_csv(layer, Path::new("points_wkt.csv"), &options).unwrap();
+//! ```
+
+use std::path::Path;
+use libc::c_int;
+use vector::{Dataset, Driver, Layer, WKB_UNKNOWN};
+use GdalError;
+
+/// Field separator of a CSV file.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Separator {
+    Comma,
+    Semicolon,
+    Tab,
+    Space,
+}
+
+impl Separator {
+    pub fn name(&self) -> &'static str {
+        match *self {
+            Separator::Comma => "COMMA",
+            Separator::Semicolon => "SEMICOLON",
+            Separator::Tab => "TAB",
+            Separator::Space => "SPACE",
+        }
+    }
+}
+
+/// How geometries are written, value of the `GEOMETRY` layer creation
+/// option.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GeometryEncoding {
+    /// A `WKT` column.
+    AsWkt,
+    /// `X`, `Y` columns, for points only.
+    AsXy,
+    /// `X`, `Y`, `Z` columns, for points only.
+    AsXyz,
+    /// `Y`, `X` columns, for points only.
+    AsYx,
+}
+
+/// Builder for CSV open options, rendered with `options()` to the string
+/// list expected by `Dataset::open_with_options`, or used directly with
+/// `open`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CsvOpenOptions {
+    x_names: Vec<String>,
+    y_names: Vec<String>,
+    z_names: Vec<String>,
+    geometry_names: Vec<String>,
+    keep_geometry_columns: Option<bool>,
+    autodetect_type: bool,
+    separator: Option<Separator>,
+}
+
+impl CsvOpenOptions {
+    pub fn new() -> CsvOpenOptions {
+        return CsvOpenOptions::default();
+    }
+
+    /// Build point geometries from the `x` and `y` columns. Column names
+    /// may contain `*` wildcards.
+    pub fn x_y_columns(mut self, x: &str, y: &str) -> CsvOpenOptions {
+        self.x_names.push(x.to_string());
+        self.y_names.push(y.to_string());
+        return self;
+    }
+
+    /// Also read Z from column `z`, together with `x_y_columns`.
+    pub fn z_column(mut self, z: &str) -> CsvOpenOptions {
+        self.z_names.push(z.to_string());
+        return self;
+    }
+
+    /// Read geometries from the WKT column `column`.
+    pub fn wkt_column(mut self, column: &str) -> CsvOpenOptions {
+        self.geometry_names.push(column.to_string());
+        return self;
+    }
+
+    /// Whether the columns geometries are read from are also reported as
+    /// regular fields. GDAL keeps them by default.
+    pub fn keep_geometry_columns(mut self, keep: bool) -> CsvOpenOptions {
+        self.keep_geometry_columns = Some(keep);
+        return self;
+    }
+
+    /// Detect integer and real columns instead of reading every field as
+    /// a string.
+    pub fn autodetect_type(mut self, autodetect: bool) -> CsvOpenOptions {
+        self.autodetect_type = autodetect;
+        return self;
+    }
+
+    /// The field separator, detected by default.
+    pub fn separator(mut self, separator: Separator) -> CsvOpenOptions {
+        self.separator = Some(separator);
+        return self;
+    }
+
+    pub fn options(&self) -> Vec<String> {
+        let mut options = Vec::new();
+        if !self.x_names.is_empty() {
+            options.push(format!("X_POSSIBLE_NAMES={}", self.x_names.join(",")));
+        }
+        if !self.y_names.is_empty() {
+            options.push(format!("Y_POSSIBLE_NAMES={}", self.y_names.join(",")));
+        }
+        if !self.z_names.is_empty() {
+            options.push(format!("Z_POSSIBLE_NAMES={}", self.z_names.join(",")));
+        }
+        if !self.geometry_names.is_empty() {
+            options.push(format!("GEOM_POSSIBLE_NAMES={}", self.geometry_names.join(",")));
+        }
+        if let Some(keep) = self.keep_geometry_columns {
+            options.push(format!("KEEP_GEOM_COLUMNS={}", if keep { "YES" } else { "NO" }));
+        }
+        if self.autodetect_type {
+            options.push("AUTODETECT_TYPE=YES".to_string());
+        }
+        if let Some(separator) = self.separator {
+            options.push(format!("SEPARATOR={}", separator.name()));
+        }
+        return options;
+    }
+
+    /// Open the CSV file at `path` with these options.
+    pub fn open(&self, path: &Path) -> Option<Dataset> {
+        return Dataset::open_with_options(path, &["CSV".to_string()], &self.options());
+    }
+}
+
+/// Builder for CSV layer creation options, rendered with `options()` to
+/// the string list expected by `Dataset::create_layer_ext`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CsvLayerOptions {
+    geometry: Option<GeometryEncoding>,
+    separator: Option<Separator>,
+    create_csvt: bool,
+    write_bom: bool,
+}
+
+impl CsvLayerOptions {
+    pub fn new() -> CsvLayerOptions {
+        return CsvLayerOptions::default();
+    }
+
+    /// Write geometries, which are dropped by default.
+    pub fn geometry(mut self, encoding: GeometryEncoding) -> CsvLayerOptions {
+        self.geometry = Some(encoding);
+        return self;
+    }
+
+    pub fn separator(mut self, separator: Separator) -> CsvLayerOptions {
+        self.separator = Some(separator);
+        return self;
+    }
+
+    /// Write a `.csvt` sidecar file with the field types, so they survive
+    /// a round trip.
+    pub fn create_csvt(mut self, create: bool) -> CsvLayerOptions {
+        self.create_csvt = create;
+        return self;
+    }
+
+    /// Start the file with a UTF-8 byte order mark, which some spreadsheet
+    /// applications need to detect the encoding.
+    pub fn write_bom(mut self, write: bool) -> CsvLayerOptions {
+        self.write_bom = write;
+        return self;
+    }
+
+    pub fn options(&self) -> Vec<String> {
+        let mut options = Vec::new();
+        if let Some(encoding) = self.geometry {
+            let value = match encoding {
+                GeometryEncoding::AsWkt => "AS_WKT",
+                GeometryEncoding::AsXy => "AS_XY",
+                GeometryEncoding::AsXyz => "AS_XYZ",
+                GeometryEncoding::AsYx => "AS_YX",
+            };
+            options.push(format!("GEOMETRY={}", value));
+        }
+        if let Some(separator) = self.separator {
+            options.push(format!("SEPARATOR={}", separator.name()));
+        }
+        if self.create_csvt {
+            options.push("CREATE_CSVT=YES".to_string());
+        }
+        if self.write_bom {
+            options.push("WRITE_BOM=YES".to_string());
+        }
+        return options;
+    }
+}
+
+/// Write the features of `layer` to the CSV file at `path`, with the same
+/// fields. Features are read from the current position of the layer, so
+/// a layer that was already iterated over is written empty.
+pub fn write_csv(layer: &Layer, path: &Path, options: &CsvLayerOptions) -> Result<(), GdalError> {
+    let driver = match Driver::get("CSV") {
+        Some(driver) => driver,
+        None => return Err(GdalError::CallFailed{desc: "CSV driver not available"}),
+    };
+    let mut dataset = match driver.create(path) {
+        Some(dataset) => dataset,
+        None => return Err(GdalError::CallFailed{desc: "failed to create CSV file"}),
+    };
+    let name = path.file_stem().and_then(|stem| stem.to_str()).unwrap_or("layer");
+    let output = dataset.create_layer_ext(name, None, WKB_UNKNOWN, &options.options())?;
+
+    let fields: Vec<(String, c_int)> = layer.defn().fields()
+        .map(|field| (field.name(), field.field_type()))
+        .collect();
+    let fields_def: Vec<(&str, c_int)> = fields.iter()
+        .map(|&(ref name, field_type)| (name.as_str(), field_type))
+        .collect();
+    output.create_defn_fields(&fields_def)?;
+    for feature in layer.features() {
+        output.create_feature_from(&feature)?;
+    }
+    return Ok(());
+}
@@ -1,9 +1,15 @@
-use std::ffi::CString;
+use std::ops::Deref;
 use std::path::Path;
 use std::ptr::null;
 use libc::{c_int, c_void};
-use vector::{ogr, Layer};
+use vector::{ogr, Driver, Geometry, Layer};
 use vector::driver::_register_drivers;
+use spatial_ref::SpatialRef;
+use utils::{_cstring, _string, CslStringList};
+use vector::SqlDialect;
+use vector::sql::{quote_identifier, quote_literal};
+use {GdalError, OpenFlags};
+use config;
 
 /// Vector dataset
 ///
@@ -16,32 +22,224 @@ use vector::driver::_register_drivers;
 /// ```
 pub struct Dataset {
     c_dataset: *const c_void,
+    open_flags: OpenFlags,
     layers: Vec<Layer>,
 }
 
 
 impl Dataset {
+    /// Wrap a dataset created by a driver, which is always writable.
     pub unsafe fn _with_c_dataset(c_dataset: *const c_void) -> Dataset {
-        Dataset{c_dataset: c_dataset, layers: vec!()}
+        Dataset{c_dataset: c_dataset, open_flags: OpenFlags::VECTOR | OpenFlags::UPDATE, layers: vec!()}
     }
 
     /// Open the dataset at `path`.
     pub fn open(path: &Path) -> Option<Dataset> {
         _register_drivers();
         let filename = path.to_str().unwrap();
-        let c_filename = CString::new(filename.as_bytes()).unwrap();
+        let c_filename = _cstring(filename).ok()?;
         let c_dataset = unsafe { ogr::OGROpen(c_filename.as_ptr(), 0, null()) };
         return match c_dataset.is_null() {
             true  => None,
-            false => Some(Dataset{c_dataset: c_dataset, layers: vec!()}),
+            false => Some(Dataset{c_dataset: c_dataset, open_flags: OpenFlags::VECTOR, layers: vec!()}),
         };
     }
 
+    /// Open the dataset at `path`, decoding the attributes of shapefiles
+    /// from `encoding` (e.g. `"ISO-8859-1"`, `"CP1252"`) instead of the
+    /// encoding declared in their `.cpg` file or DBF header. Pass `""` to
+    /// skip recoding. String fields are then converted to UTF-8 by GDAL.
+    pub fn open_with_encoding(path: &Path, encoding: &str) -> Option<Dataset> {
+        return config::with_thread_local_config_option("SHAPE_ENCODING", encoding, || Dataset::open(path)).ok()?;
+    }
+
+    /// Open the dataset at `path`, passing driver specific open options as
+    /// `NAME=VALUE` strings. If `drivers` isn't empty, only the drivers
+    /// with these short names are tried.
+    pub fn open_with_options(path: &Path, drivers: &[String], options: &[String]) -> Option<Dataset> {
+        _register_drivers();
+        let filename = path.to_str().unwrap();
+        let c_filename = _cstring(filename).ok()?;
+        let c_drivers = CslStringList::new(drivers).ok()?;
+        let c_options = CslStringList::new(options).ok()?;
+        let c_dataset = unsafe { ogr::GDALOpenEx(
+            c_filename.as_ptr(),
+            OpenFlags::VECTOR.bits(),
+            // an empty list would allow no driver at all
+            match drivers.is_empty() {
+                true  => null(),
+                false => c_drivers.as_ptr(),
+            },
+            c_options.as_ptr(),
+            null(),
+        ) };
+        return match c_dataset.is_null() {
+            true  => None,
+            false => Some(Dataset{c_dataset: c_dataset, open_flags: OpenFlags::VECTOR, layers: vec!()}),
+        };
+    }
+
+    /// The driver this dataset was opened or created with.
+    pub fn driver(&self) -> Driver {
+        return unsafe { Driver::_with_c_driver(ogr::OGR_DS_GetDriver(self.c_dataset)) };
+    }
+
+    /// Short name of the driver of this dataset.
+    pub fn driver_name(&self) -> String {
+        return self.driver().short_name();
+    }
+
+    /// Define a view called `name` over the result of the `SELECT`
+    /// statement `sql`, so derived layers can be reused by anyone opening
+    /// the dataset. Only SQLite and GeoPackage datasets support views.
+    ///
+    /// GeoPackage views are registered as attribute tables, so their
+    /// geometry column isn't recognized; use `create_gpkg_feature_view`
+    /// for a spatial view. The new layer is visible once the dataset is
+    /// opened again.
+    pub fn create_view(&mut self, name: &str, sql: &str, dialect: SqlDialect) -> Result<(), GdalError> {
+        let driver = self.driver_name();
+        if driver != "SQLite" && driver != "GPKG" {
+            return Err(GdalError::CallFailed{desc: "driver doesn't support views"});
+        }
+        self._execute_ddl(&format!("CREATE VIEW {} AS {}", quote_identifier(name), sql), dialect)?;
+        if driver == "GPKG" {
+            self._execute_ddl(&format!(
+                "INSERT INTO gpkg_contents (table_name, identifier, data_type) VALUES ({0}, {0}, 'attributes')",
+                quote_literal(name),
+            ), SqlDialect::Default)?;
+        }
+        return Ok(());
+    }
+
+    /// Define a GeoPackage view with the geometry column `geometry_column`
+    /// in the spatial reference `srs_id` (an id of the `gpkg_spatial_ref_sys`
+    /// table, usually the EPSG code). The view must expose the feature id
+    /// as a column, e.g. `SELECT t.fid AS OGC_FID, ...`.
+    pub fn create_gpkg_feature_view(
+        &mut self,
+        name: &str,
+        sql: &str,
+        geometry_column: &str,
+        srs_id: i32,
+    ) -> Result<(), GdalError> {
+        if self.driver_name() != "GPKG" {
+            return Err(GdalError::CallFailed{desc: "not a GeoPackage"});
+        }
+        self._execute_ddl(&format!("CREATE VIEW {} AS {}", quote_identifier(name), sql), SqlDialect::Default)?;
+        self._execute_ddl(&format!(
+            "INSERT INTO gpkg_contents (table_name, identifier, data_type, srs_id) VALUES ({0}, {0}, 'features', {1})",
+            quote_literal(name), srs_id,
+        ), SqlDialect::Default)?;
+        self._execute_ddl(&format!(
+            "INSERT INTO gpkg_geometry_columns (table_name, column_name, geometry_type_name, srs_id, z, m) \
+             VALUES ({}, {}, 'GEOMETRY', {}, 0, 0)",
+            quote_literal(name), quote_literal(geometry_column), srs_id,
+        ), SqlDialect::Default)?;
+        return Ok(());
+    }
+
+    fn _execute_ddl(&mut self, sql: &str, dialect: SqlDialect) -> Result<(), GdalError> {
+        self.execute_sql(sql, dialect)?;
+        return Ok(());
+    }
+
+    /// Run the SQL statement `sql`. `SELECT` statements return their result
+    /// as a layer, other statements `None`.
+    ///
+    /// With `SqlDialect::Sqlite` the statement can use the
+    /// [spatial functions](http://www.gdal.org/ogr_sql_sqlite.html) of
+    /// SpatiaLite (`ST_Buffer`, `ST_Intersects`, ...) on any format, if GDAL
+    /// is built against it. Failures, like syntax errors or unknown
+    /// functions, are reported as `GdalError::SqlFailed` with GDAL's message.
+    ///
+    /// ```
+    /// use std::path::Path;
+    /// use gdal::vector::{Dataset, SqlDialect};
+    ///
+    /// let mut dataset = Dataset::open(Path::new("fixtures/roads.geojson")).unwrap();
+    /// let name = dataset.layer(0).unwrap().name();
+    /// let sql = format!("SELECT kind FROM \"{}\" WHERE kind = 'path'", name);
+    /// let result = dataset.execute_sql(&sql, SqlDialect::Ogr).unwrap().unwrap();
+    /// println!("{} paths", result.features().count());
+    /// ```
+    pub fn execute_sql(&self, sql: &str, dialect: SqlDialect) -> Result<Option<SqlResultLayer<'_>>, GdalError> {
+        return self._execute_sql(sql, None, dialect);
+    }
+
+    /// Same as `execute_sql`, but only the features of the tables queried by
+    /// `sql` that intersect `spatial_filter` are considered.
+    pub fn execute_sql_filtered(
+        &self,
+        sql: &str,
+        spatial_filter: &Geometry,
+        dialect: SqlDialect,
+    ) -> Result<Option<SqlResultLayer<'_>>, GdalError> {
+        return self._execute_sql(sql, Some(spatial_filter), dialect);
+    }
+
+    fn _execute_sql(
+        &self,
+        sql: &str,
+        spatial_filter: Option<&Geometry>,
+        dialect: SqlDialect,
+    ) -> Result<Option<SqlResultLayer<'_>>, GdalError> {
+        let c_sql = _cstring(sql)?;
+        let c_dialect = match dialect.name() {
+            Some(name) => Some(_cstring(name)?),
+            None => None,
+        };
+        unsafe { ogr::CPLErrorReset() };
+        let c_layer = unsafe { ogr::OGR_DS_ExecuteSQL(
+            self.c_dataset,
+            c_sql.as_ptr(),
+            match spatial_filter {
+                Some(geometry) => geometry.c_geometry(),
+                None => null(),
+            },
+            match c_dialect {
+                Some(ref c_dialect) => c_dialect.as_ptr(),
+                None => null(),
+            },
+        ) };
+        let result = match c_layer.is_null() {
+            true  => None,
+            false => Some(SqlResultLayer{dataset: self, layer: unsafe { Layer::_with_c_layer(c_layer) }}),
+        };
+        if unsafe { ogr::CPLGetLastErrorType() } >= ogr::CE_FAILURE {
+            return Err(GdalError::SqlFailed{message: _string(unsafe { ogr::CPLGetLastErrorMsg() })});
+        }
+        return Ok(result);
+    }
+
     /// Get number of layers.
     pub fn count(&self) -> isize {
         return unsafe { ogr::OGR_DS_GetLayerCount(self.c_dataset) } as isize;
     }
 
+    /// The flags this dataset was opened with, as passed to GDAL. A vector
+    /// `Dataset` is always opened for its layers; datasets created by a
+    /// driver are writable.
+    pub fn open_flags(&self) -> OpenFlags {
+        return self.open_flags;
+    }
+
+    /// Whether this dataset can be written to, e.g. to create layers or
+    /// features.
+    pub fn is_update(&self) -> bool {
+        return unsafe { ogr::GDALGetAccess(self.c_dataset) } == ogr::GA_UPDATE;
+    }
+
+    /// Whether this dataset was opened for its raster bands.
+    pub fn is_raster(&self) -> bool {
+        return self.open_flags().contains(OpenFlags::RASTER);
+    }
+
+    /// Whether this dataset was opened for its vector layers.
+    pub fn is_vector(&self) -> bool {
+        return self.open_flags().contains(OpenFlags::VECTOR);
+    }
+
     fn _child_layer(&mut self, c_layer: *const c_void) -> &Layer {
         let layer = unsafe { Layer::_with_c_layer(c_layer) };
         self.layers.push(layer);
@@ -57,23 +255,94 @@ impl Dataset {
         };
     }
 
+    /// Get all layers, in order.
+    pub fn layers(&mut self) -> &[Layer] {
+        let start = self.layers.len();
+        for idx in 0..self.count() {
+            let c_layer = unsafe { ogr::OGR_DS_GetLayer(self.c_dataset, idx as c_int) };
+            self._child_layer(c_layer);
+        }
+        return &self.layers[start..];
+    }
+
+    /// Get the layer called `name`.
+    pub fn layer_by_name(&mut self, name: &str) -> Option<&Layer> {
+        let c_name = _cstring(name).ok()?;
+        let c_layer = unsafe { ogr::OGR_DS_GetLayerByName(self.c_dataset, c_name.as_ptr()) };
+        return match c_layer.is_null() {
+            true  => None,
+            false => Some(self._child_layer(c_layer)),
+        };
+    }
+
     /// Create a new layer with a blank definition.
-    pub fn create_layer(&mut self) -> &mut Layer {
-        let c_name = CString::new("".as_bytes()).unwrap();
+    pub fn create_layer(&mut self) -> Result<&mut Layer, GdalError> {
+        return self.create_layer_ext("", None, ogr::WKB_UNKNOWN, &[]);
+    }
+
+    /// Create a new layer called `name` with a blank definition. The
+    /// spatial reference is written to the output (e.g. the `.prj` file of
+    /// a shapefile or the `crs` member of GeoJSON). `geometry_type` is one
+    /// of the `WKB_*` constants and `options` are driver specific layer
+    /// creation options as `NAME=VALUE` strings.
+    pub fn create_layer_ext(
+        &mut self,
+        name: &str,
+        srs: Option<&SpatialRef>,
+        geometry_type: c_int,
+        options: &[String],
+    ) -> Result<&mut Layer, GdalError> {
+        let c_name = _cstring(name)?;
+        let c_srs = match srs {
+            Some(srs) => unsafe { srs._c_ptr() },
+            None => null(),
+        };
+        let c_options = CslStringList::new(options)?;
         let c_layer = unsafe { ogr::OGR_DS_CreateLayer(
             self.c_dataset,
             c_name.as_ptr(),
-            null(),
-            ogr::WKB_UNKNOWN,
-            null(),
+            c_srs,
+            geometry_type,
+            c_options.as_ptr(),
         ) };
-        match c_layer.is_null() {
-            true  => panic!("Layer creation failed"),
-            false => {
-                self._child_layer(c_layer);
-                return self.layers.last_mut().unwrap();
-            }
-        };
+        if c_layer.is_null() {
+            return Err(GdalError::CallFailed{desc: "OGR_DS_CreateLayer failed"});
+        }
+        self._child_layer(c_layer);
+        return Ok(self.layers.last_mut().unwrap());
+    }
+}
+
+
+/// The result of a `SELECT` statement run by `Dataset::execute_sql`, used
+/// like any other `Layer`. It borrows the dataset and is released when
+/// dropped, so it can't outlive the dataset:
+///
+/// ```compile_fail
+/// use std::path::Path;
+/// use gdal::vector::{Dataset, SqlDialect};
+///
+/// let dataset = Dataset::open(Path::new("fixtures/roads.geojson")).unwrap();
+/// let result = dataset.execute_sql("SELECT * FROM roads", SqlDialect::Ogr).unwrap().unwrap();
+/// drop(dataset);
+/// result.features().count();
+/// ```
+pub struct SqlResultLayer<'a> {
+    dataset: &'a Dataset,
+    layer: Layer,
+}
+
+impl<'a> Deref for SqlResultLayer<'a> {
+    type Target = Layer;
+
+    fn deref(&self) -> &Layer {
+        return &self.layer;
+    }
+}
+
+impl<'a> Drop for SqlResultLayer<'a> {
+    fn drop(&mut self) {
+        unsafe { ogr::OGR_DS_ReleaseResultSet(self.dataset.c_dataset, self.layer._c_layer()) };
     }
 }
 
@@ -66,4 +66,20 @@ impl<'a> Field<'a> {
         let rv = unsafe { ogr::OGR_Fld_GetNameRef(self.c_field_defn) };
         return _string(rv);
     }
+
+    /// The type of this field, one of the `OFT_*` constants.
+    pub fn field_type(&self) -> c_int {
+        return unsafe { ogr::OGR_Fld_GetType(self.c_field_defn) };
+    }
+
+    /// The maximum width of values, e.g. the number of bytes of a string
+    /// field in a shapefile. 0 means unlimited or unknown.
+    pub fn width(&self) -> usize {
+        return unsafe { ogr::OGR_Fld_GetWidth(self.c_field_defn) } as usize;
+    }
+
+    /// The number of decimals of a real field, 0 if unknown.
+    pub fn precision(&self) -> usize {
+        return unsafe { ogr::OGR_Fld_GetPrecision(self.c_field_defn) } as usize;
+    }
 }
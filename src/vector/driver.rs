@@ -1,9 +1,8 @@
-use std::ffi::CString;
-use std::ptr::null;
 use std::sync::{Once, ONCE_INIT};
 use std::path::Path;
 use libc::{c_void};
 use vector::{ogr, Dataset};
+use utils::{_cstring, _string, CslStringList};
 
 
 static START: Once = ONCE_INIT;
@@ -26,7 +25,7 @@ pub struct Driver {
 impl Driver {
     pub fn get(name: &str) -> Option<Driver> {
         _register_drivers();
-        let c_name = CString::new(name.as_bytes()).unwrap();
+        let c_name = _cstring(name).ok()?;
         let c_driver = unsafe { ogr::OGRGetDriverByName(c_name.as_ptr()) };
         return match c_driver.is_null() {
             true  => None,
@@ -34,13 +33,34 @@ impl Driver {
         };
     }
 
+    pub unsafe fn _with_c_driver(c_driver: *const c_void) -> Driver {
+        return Driver{c_driver: c_driver};
+    }
+
+    /// The name used to get the driver, e.g. `"GPKG"`.
+    pub fn short_name(&self) -> String {
+        return _string(unsafe { ogr::OGR_Dr_GetName(self.c_driver) });
+    }
+
+    /// A human readable name, e.g. `"GeoPackage"`.
+    pub fn long_name(&self) -> String {
+        return _string(unsafe { ogr::GDALGetDriverLongName(self.c_driver) });
+    }
+
     pub fn create(&self, path: &Path) -> Option<Dataset> {
+        return self.create_with_options(path, &[]);
+    }
+
+    /// Create a dataset, passing driver specific dataset creation options
+    /// as `NAME=VALUE` strings.
+    pub fn create_with_options(&self, path: &Path, options: &[String]) -> Option<Dataset> {
         let filename = path.to_str().unwrap();
-        let c_filename = CString::new(filename.as_bytes()).unwrap();
+        let c_filename = _cstring(filename).ok()?;
+        let c_options = CslStringList::new(options).ok()?;
         let c_dataset = unsafe { ogr::OGR_Dr_CreateDataSource(
             self.c_driver,
             c_filename.as_ptr(),
-            null(),
+            c_options.as_ptr(),
         ) };
         return match c_dataset.is_null() {
             true  => None,
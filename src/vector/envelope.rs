@@ -0,0 +1,229 @@
+use vector::Geometry;
+
+/// Half the width of the EPSG:3857 (web mercator) world, in meters.
+const WEB_MERCATOR_EXTENT: f64 = 20037508.342789244;
+
+/// Highest zoom level of web mercator tiles, the last one where tile
+/// indices and tile counts per row fit into `u32`.
+pub const MAX_TILE_ZOOM: u8 = 31;
+
+const GEOHASH_ALPHABET: &[u8] = b"0123456789bcdefghjkmnpqrstuvwxyz";
+
+/// An axis aligned bounding box, in the coordinates of whatever it bounds.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Envelope {
+    pub min_x: f64,
+    pub max_x: f64,
+    pub min_y: f64,
+    pub max_y: f64,
+}
+
+/// An axis aligned bounding box including Z, see `Geometry::envelope_3d`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Envelope3D {
+    pub min_x: f64,
+    pub max_x: f64,
+    pub min_y: f64,
+    pub max_y: f64,
+    pub min_z: f64,
+    pub max_z: f64,
+}
+
+impl Envelope3D {
+    /// The 2D part of the envelope.
+    pub fn to_2d(&self) -> Envelope {
+        return Envelope::new(self.min_x, self.max_x, self.min_y, self.max_y);
+    }
+}
+
+impl Envelope {
+    pub fn new(min_x: f64, max_x: f64, min_y: f64, max_y: f64) -> Envelope {
+        return Envelope{min_x: min_x, max_x: max_x, min_y: min_y, max_y: max_y};
+    }
+
+    /// The envelope of `geometry`, same as `geometry.envelope()`.
+    pub fn of(geometry: &Geometry) -> Envelope {
+        return geometry.envelope();
+    }
+
+    /// The envelope as a rectangular polygon, see `Geometry::bbox`.
+    pub fn to_geometry(&self) -> Geometry {
+        return Geometry::bbox(self.min_x, self.min_y, self.max_x, self.max_y);
+    }
+
+    /// The EPSG:3857 bounds of the web mercator tile `x`, `y` at `zoom`,
+    /// with `y` counted from the top as in XYZ tile URLs. `None` if `zoom`
+    /// is above `MAX_TILE_ZOOM` or there's no such tile at `zoom`.
+    pub fn from_tile(x: u32, y: u32, zoom: u8) -> Option<Envelope> {
+        if zoom > MAX_TILE_ZOOM || x as u64 >> zoom != 0 || y as u64 >> zoom != 0 {
+            return None;
+        }
+        let size = tile_size(zoom);
+        let min_x = -WEB_MERCATOR_EXTENT + x as f64 * size;
+        let max_y = WEB_MERCATOR_EXTENT - y as f64 * size;
+        return Some(Envelope::new(min_x, min_x + size, max_y - size, max_y));
+    }
+
+    /// The longitude/latitude cell of `geohash`, `None` if it's empty or
+    /// not a valid geohash.
+    pub fn from_geohash(geohash: &str) -> Option<Envelope> {
+        if geohash.is_empty() {
+            return None;
+        }
+        let (mut lon, mut lat) = ((-180., 180.), (-90., 90.));
+        let mut even = true;
+        for c in geohash.bytes() {
+            let bits = GEOHASH_ALPHABET.iter().position(|&a| a == c.to_ascii_lowercase())?;
+            for shift in (0..5).rev() {
+                // bits alternate between longitude and latitude
+                let range: &mut (f64, f64) = if even { &mut lon } else { &mut lat };
+                let mid = (range.0 + range.1) / 2.;
+                if bits >> shift & 1 == 1 {
+                    range.0 = mid;
+                } else {
+                    range.1 = mid;
+                }
+                even = !even;
+            }
+        }
+        return Some(Envelope::new(lon.0, lon.1, lat.0, lat.1));
+    }
+
+    /// The range of web mercator tiles at `zoom` intersecting this
+    /// envelope, which must be in EPSG:3857 coordinates. Parts outside the
+    /// mercator world are ignored. `None` if `zoom` is above
+    /// `MAX_TILE_ZOOM`.
+    pub fn tile_range(&self, zoom: u8) -> Option<TileRange> {
+        if zoom > MAX_TILE_ZOOM {
+            return None;
+        }
+        let size = tile_size(zoom);
+        let last = ((1u64 << zoom) - 1) as f64;
+        let column = |x: f64| (x + WEB_MERCATOR_EXTENT) / size;
+        let row = |y: f64| (WEB_MERCATOR_EXTENT - y) / size;
+        let min_x = column(self.min_x).floor().max(0.).min(last);
+        let min_y = row(self.max_y).floor().max(0.).min(last);
+        // a maximum on a tile edge doesn't reach into the next tile, so
+        // clamp only after stepping back from the edge
+        let max_x = (column(self.max_x).ceil() - 1.).min(last).max(min_x);
+        let max_y = (row(self.min_y).ceil() - 1.).min(last).max(min_y);
+        return Some(TileRange{
+            zoom: zoom,
+            min_x: min_x as u32,
+            max_x: max_x as u32,
+            min_y: min_y as u32,
+            max_y: max_y as u32,
+        });
+    }
+
+    pub fn width(&self) -> f64 {
+        return self.max_x - self.min_x;
+    }
+
+    pub fn height(&self) -> f64 {
+        return self.max_y - self.min_y;
+    }
+
+    /// Whether the point (`x`, `y`) is inside the envelope or on its edge.
+    pub fn contains(&self, x: f64, y: f64) -> bool {
+        return self.min_x <= x && x <= self.max_x && self.min_y <= y && y <= self.max_y;
+    }
+
+    /// Whether `other` is inside this envelope, edges included.
+    pub fn contains_envelope(&self, other: &Envelope) -> bool {
+        return self.min_x <= other.min_x && other.max_x <= self.max_x &&
+            self.min_y <= other.min_y && other.max_y <= self.max_y;
+    }
+
+    /// Whether the envelopes overlap or touch.
+    pub fn intersects(&self, other: &Envelope) -> bool {
+        return self.min_x <= other.max_x && other.min_x <= self.max_x &&
+            self.min_y <= other.max_y && other.min_y <= self.max_y;
+    }
+
+    /// The smallest envelope containing both envelopes.
+    pub fn union(&self, other: &Envelope) -> Envelope {
+        return Envelope::new(
+            self.min_x.min(other.min_x),
+            self.max_x.max(other.max_x),
+            self.min_y.min(other.min_y),
+            self.max_y.max(other.max_y),
+        );
+    }
+
+    /// The area shared by both envelopes, `None` if they don't intersect.
+    /// Envelopes that only touch give an envelope of zero width or height.
+    pub fn intersection(&self, other: &Envelope) -> Option<Envelope> {
+        if !self.intersects(other) {
+            return None;
+        }
+        return Some(Envelope::new(
+            self.min_x.max(other.min_x),
+            self.max_x.min(other.max_x),
+            self.min_y.max(other.min_y),
+            self.max_y.min(other.max_y),
+        ));
+    }
+
+    /// The envelope grown by `distance` on every side, or shrunk for a
+    /// negative `distance`.
+    pub fn buffer(&self, distance: f64) -> Envelope {
+        return Envelope::new(
+            self.min_x - distance,
+            self.max_x + distance,
+            self.min_y - distance,
+            self.max_y + distance,
+        );
+    }
+}
+
+fn tile_size(zoom: u8) -> f64 {
+    return 2. * WEB_MERCATOR_EXTENT / (1u64 << zoom) as f64;
+}
+
+/// An inclusive range of XYZ web mercator tiles at one zoom level.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TileRange {
+    pub zoom: u8,
+    pub min_x: u32,
+    pub max_x: u32,
+    pub min_y: u32,
+    pub max_y: u32,
+}
+
+impl TileRange {
+    /// Number of tiles in the range.
+    pub fn tile_count(&self) -> u64 {
+        return (self.max_x - self.min_x + 1) as u64 * (self.max_y - self.min_y + 1) as u64;
+    }
+
+    /// Iterate over the `(x, y)` tiles of the range, row by row.
+    pub fn tiles(&self) -> TileIterator {
+        return TileIterator{range: *self, x: self.min_x, y: self.min_y};
+    }
+}
+
+/// Iterator over the tiles of a `TileRange`.
+pub struct TileIterator {
+    range: TileRange,
+    x: u32,
+    y: u32,
+}
+
+impl Iterator for TileIterator {
+    type Item = (u32, u32);
+
+    fn next(&mut self) -> Option<(u32, u32)> {
+        if self.y > self.range.max_y {
+            return None;
+        }
+        let tile = (self.x, self.y);
+        if self.x == self.range.max_x {
+            self.x = self.range.min_x;
+            self.y += 1;
+        } else {
+            self.x += 1;
+        }
+        return Some(tile);
+    }
+}
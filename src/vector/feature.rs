@@ -1,16 +1,20 @@
-use std::ffi::CString;
-use libc::{c_void};
+#[cfg(feature = "chrono")]
+use std::convert::TryFrom;
+use std::fmt;
+use std::slice;
+use std::str::Utf8Error;
+use libc::{c_char, c_float, c_int, c_void};
 use vector::Defn;
-use utils::_string;
+use utils::{_cstring, _string, _string_checked, _string_lossy};
 use vector::ogr;
-use vector::geometry::Geometry;
+use vector::geometry::{Geometry, GeometryRef};
+use GdalError;
 
 
 /// OGR Feature
 pub struct Feature<'a> {
     _defn: &'a Defn,
     c_feature: *const c_void,
-    geometry: Geometry,
 }
 
 
@@ -19,41 +23,180 @@ impl<'a> Feature<'a> {
         return Feature{
             _defn: defn,
             c_feature: c_feature,
-            geometry: Geometry::lazy_feature_geometry(),
         };
     }
 
+    pub unsafe fn _c_ptr(&self) -> *const c_void {
+        return self.c_feature;
+    }
+
     /// Get the value of a named field. If the field exists, it returns a
     /// `FieldValue` wrapper, that you need to unpack to a base type
     /// (string, float, etc). If the field is missing, returns `None`.
-    pub fn field(&self, name: &str) -> Option<FieldValue> {
-        let c_name = CString::new(name.as_bytes()).unwrap();
+    /// Field types without a `FieldValue` variant (dates without a time,
+    /// times, lists, ...) are returned as their string representation, as
+    /// are date and time fields that aren't set.
+    ///
+    /// String values that aren't valid UTF-8 are an error. That happens with
+    /// shapefiles in legacy encodings whose encoding GDAL doesn't know; see
+    /// `field_lossy` and `Dataset::open_with_encoding`.
+    pub fn field(&self, name: &str) -> Option<Result<FieldValue, Utf8Error>> {
+        return self.field_id(name).map(|field_id| self.field_value(field_id, _string_checked));
+    }
+
+    /// Like `field`, but replaces invalid UTF-8 sequences in string values
+    /// with U+FFFD.
+    pub fn field_lossy(&self, name: &str) -> Option<FieldValue> {
+        return self.field_id(name)
+            .map(|field_id| self.field_value(field_id, |rv| Ok(_string_lossy(rv))).unwrap());
+    }
+
+    /// Iterate over all fields of the feature as `(name, value)` pairs, in
+    /// schema order. The value is `None` for fields that aren't set. Like
+    /// with `field`, string values that aren't valid UTF-8 are an error.
+    pub fn fields(&self) -> FieldValueIterator<'_> {
+        let total = unsafe { ogr::OGR_F_GetFieldCount(self.c_feature) };
+        return FieldValueIterator{feature: self, next_id: 0, total: total};
+    }
+
+    fn field_id(&self, name: &str) -> Option<c_int> {
+        let c_name = _cstring(name).ok()?;
         let field_id = unsafe { ogr::OGR_F_GetFieldIndex(self.c_feature, c_name.as_ptr()) };
-        if field_id == -1 {
-            return None;
-        }
+        return match field_id {
+            -1 => None,
+            _ => Some(field_id),
+        };
+    }
+
+    fn field_value<F>(&self, field_id: c_int, string: F) -> Result<FieldValue, Utf8Error>
+        where F: Fn(*const c_char) -> Result<String, Utf8Error>
+    {
         let field_defn = unsafe { ogr::OGR_F_GetFieldDefnRef(self.c_feature, field_id) };
         let field_type = unsafe { ogr::OGR_Fld_GetType(field_defn) };
         return match field_type {
-            ogr::OFT_STRING => {
-                let rv = unsafe { ogr::OGR_F_GetFieldAsString(self.c_feature, field_id) };
-                return Some(FieldValue::StringValue(_string(rv)));
+            ogr::OFT_INTEGER => {
+                let rv = unsafe { ogr::OGR_F_GetFieldAsInteger(self.c_feature, field_id) };
+                Ok(FieldValue::IntegerValue(rv as i32))
             },
             ogr::OFT_REAL => {
                 let rv = unsafe { ogr::OGR_F_GetFieldAsDouble(self.c_feature, field_id) };
-                return Some(FieldValue::RealValue(rv as f64));
+                Ok(FieldValue::RealValue(rv as f64))
             },
-            _ => panic!("Unknown field type {}", field_type)
+            ogr::OFT_DATETIME => match self.field_datetime(field_id) {
+                Some(value) => Ok(FieldValue::DateTimeValue(value)),
+                // unset or null, read as the empty string like other types
+                // without a value
+                None => string(unsafe { ogr::OGR_F_GetFieldAsString(self.c_feature, field_id) }).map(FieldValue::StringValue),
+            },
+            _ => {
+                let rv = unsafe { ogr::OGR_F_GetFieldAsString(self.c_feature, field_id) };
+                string(rv).map(FieldValue::StringValue)
+            },
+        };
+    }
+
+    /// The bytes of a binary field, borrowed from the feature without
+    /// copying. Returns `None` if the field is missing, and an empty slice
+    /// if it isn't set or isn't a binary field.
+    pub fn field_as_bytes(&self, name: &str) -> Option<&[u8]> {
+        let field_id = self.field_id(name)?;
+        let mut len: c_int = 0;
+        let rv = unsafe { ogr::OGR_F_GetFieldAsBinary(self.c_feature, field_id, &mut len) };
+        let field_defn = unsafe { ogr::OGR_F_GetFieldDefnRef(self.c_feature, field_id) };
+        if rv.is_null() || len <= 0 || unsafe { ogr::OGR_Fld_GetType(field_defn) } != ogr::OFT_BINARY {
+            return Some(&[]);
         }
+        return Some(unsafe { slice::from_raw_parts(rv, len as usize) });
     }
 
-    /// Get the field's geometry.
-    pub fn geometry(&self) -> &Geometry {
-        if ! self.geometry.has_gdal_ptr() {
-            let c_geom = unsafe { ogr::OGR_F_GetGeometryRef(self.c_feature) };
-            unsafe { self.geometry.set_c_geometry(c_geom) };
+    /// Set a binary field to a copy of `bytes`. This only changes the
+    /// feature in memory, write it to persist the change.
+    pub fn set_field_bytes(&mut self, name: &str, bytes: &[u8]) -> Result<(), GdalError> {
+        let field_id = match self.field_id(name) {
+            Some(field_id) => field_id,
+            None => return Err(GdalError::CallFailed{desc: "no such field"}),
+        };
+        if bytes.len() > c_int::MAX as usize {
+            return Err(GdalError::CallFailed{desc: "binary value too large"});
         }
-        return &self.geometry;
+        unsafe { ogr::OGR_F_SetFieldBinary(self.c_feature, field_id, bytes.len() as c_int, bytes.as_ptr()) };
+        return Ok(());
+    }
+
+    fn field_datetime(&self, field_id: c_int) -> Option<DateTime> {
+        let (mut year, mut month, mut day, mut hour, mut minute, mut tz_flag) = (0, 0, 0, 0, 0, 0);
+        let mut second: c_float = 0.;
+        let rv = unsafe { ogr::OGR_F_GetFieldAsDateTimeEx(
+            self.c_feature, field_id,
+            &mut year, &mut month, &mut day, &mut hour, &mut minute, &mut second, &mut tz_flag,
+        ) };
+        if rv == 0 {
+            return None;
+        }
+        return Some(DateTime{
+            year: year,
+            month: month as u8,
+            day: day as u8,
+            hour: hour as u8,
+            minute: minute as u8,
+            second: second,
+            time_zone: TimeZone::from_tz_flag(tz_flag),
+        });
+    }
+
+    /// The OGR style string of the feature, if it has one.
+    pub fn style_string(&self) -> Option<String> {
+        let rv = unsafe { ogr::OGR_F_GetStyleString(self.c_feature) };
+        return match rv.is_null() {
+            true  => None,
+            false => Some(_string(rv)),
+        };
+    }
+
+    /// The feature id, or `None` if the feature hasn't been assigned one.
+    pub fn fid(&self) -> Option<u64> {
+        let rv = unsafe { ogr::OGR_F_GetFID(self.c_feature) };
+        return match rv {
+            ogr::OGR_NULL_FID => None,
+            _ => Some(rv as u64),
+        };
+    }
+
+    /// Get the feature's geometry, `None` if it has none. It's borrowed
+    /// from the feature, call `to_owned` on it to keep it longer.
+    pub fn geometry(&self) -> Option<GeometryRef<'_>> {
+        let c_geom = unsafe { ogr::OGR_F_GetGeometryRef(self.c_feature) };
+        return match c_geom.is_null() {
+            true  => None,
+            false => Some(unsafe { GeometryRef::_with_c_geometry(c_geom) }),
+        };
+    }
+
+    /// Whether the feature has a geometry at all.
+    pub fn has_geometry(&self) -> bool {
+        return !unsafe { ogr::OGR_F_GetGeometryRef(self.c_feature) }.is_null();
+    }
+
+    /// Replace the geometry of the feature. This only changes the feature
+    /// in memory, write it to persist the change.
+    pub fn set_geometry(&mut self, geometry: Geometry) -> Result<(), GdalError> {
+        let rv = unsafe { ogr::OGR_F_SetGeometryDirectly(self.c_feature, geometry.into_c_geometry()) };
+        return match rv {
+            ogr::OGRERR_NONE => Ok(()),
+            _ => Err(GdalError::CallFailed{desc: "OGR_F_SetGeometryDirectly failed"}),
+        };
+    }
+}
+
+
+/// Cloning copies the geometry and fields, so the copy stays valid when the
+/// iterator it came from moves on. See `Layer::create_feature_from` to
+/// write it to another layer.
+impl<'a> Clone for Feature<'a> {
+    fn clone(&self) -> Feature<'a> {
+        let c_feature = unsafe { ogr::OGR_F_Clone(self.c_feature) };
+        assert!(!c_feature.is_null());
+        return unsafe { Feature::_with_c_feature(self._defn, c_feature) };
     }
 }
 
@@ -65,9 +208,42 @@ impl<'a> Drop for Feature<'a> {
 }
 
 
+pub struct FieldValueIterator<'a> {
+    feature: &'a Feature<'a>,
+    next_id: c_int,
+    total: c_int,
+}
+
+
+impl<'a> Iterator for FieldValueIterator<'a> {
+    type Item = (String, Option<Result<FieldValue, Utf8Error>>);
+
+    #[inline]
+    fn next(&mut self) -> Option<(String, Option<Result<FieldValue, Utf8Error>>)> {
+        if self.next_id == self.total {
+            return None;
+        }
+        let field_id = self.next_id;
+        self.next_id += 1;
+        let c_feature = self.feature.c_feature;
+        let field_defn = unsafe { ogr::OGR_F_GetFieldDefnRef(c_feature, field_id) };
+        let name = _string_lossy(unsafe { ogr::OGR_Fld_GetNameRef(field_defn) });
+        let value = match unsafe { ogr::OGR_F_IsFieldSet(c_feature, field_id) } {
+            0 => None,
+            _ => Some(self.feature.field_value(field_id, _string_checked)),
+        };
+        return Some((name, value));
+    }
+}
+
+
+#[derive(Clone)]
 pub enum FieldValue {
     StringValue(String),
+    IntegerValue(i32),
     RealValue(f64),
+    /// The value of a date and time field, with its time zone.
+    DateTimeValue(DateTime),
 }
 
 
@@ -80,6 +256,14 @@ impl FieldValue {
         }
     }
 
+    /// Interpret the value as `i32`. Panics if the value is something else.
+    pub fn as_int(self) -> i32 {
+        match self {
+            FieldValue::IntegerValue(rv) => rv,
+            _ => panic!("not an IntegerValue")
+        }
+    }
+
     /// Interpret the value as `f64`. Panics if the value is something else.
     pub fn as_real(self) -> f64 {
         match self {
@@ -87,4 +271,167 @@ impl FieldValue {
             _ => panic!("not a RealValue")
         }
     }
+
+    /// Interpret the value as `DateTime`. Panics if the value is something
+    /// else.
+    pub fn as_datetime(self) -> DateTime {
+        match self {
+            FieldValue::DateTimeValue(rv) => rv,
+            _ => panic!("not a DateTimeValue")
+        }
+    }
+}
+
+
+/// The time zone of a `DateTime`, as far as OGR knows it.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TimeZone {
+    /// Nothing is known about the time zone.
+    Unknown,
+    /// Local time of some unspecified time zone.
+    Local,
+    Utc,
+    /// A fixed offset from UTC, in minutes east of Greenwich. OGR stores
+    /// offsets in multiples of 15 minutes.
+    Offset(i32),
+}
+
+
+impl TimeZone {
+    /// Decode an OGR TZ flag: 0 unknown, 1 local, 100 UTC and 100 plus or
+    /// minus the offset in quarter hours.
+    pub fn from_tz_flag(flag: c_int) -> TimeZone {
+        return match flag {
+            0 => TimeZone::Unknown,
+            1 => TimeZone::Local,
+            100 => TimeZone::Utc,
+            flag if flag > 1 => TimeZone::Offset((flag - 100) * 15),
+            _ => TimeZone::Unknown,
+        };
+    }
+
+    pub fn to_tz_flag(&self) -> c_int {
+        return match *self {
+            TimeZone::Unknown => 0,
+            TimeZone::Local => 1,
+            TimeZone::Utc => 100,
+            TimeZone::Offset(minutes) => (100 + minutes / 15) as c_int,
+        };
+    }
+
+    /// The offset from UTC in minutes, `None` if it isn't known.
+    pub fn utc_offset(&self) -> Option<i32> {
+        return match *self {
+            TimeZone::Utc => Some(0),
+            TimeZone::Offset(minutes) => Some(minutes),
+            TimeZone::Unknown | TimeZone::Local => None,
+        };
+    }
+}
+
+
+/// The value of an OGR date and time field. OGR keeps the time zone next
+/// to the local date and time instead of converting to UTC, so values
+/// round-trip unchanged.
+///
+/// It formats as ISO 8601 with `Display`, which with a known offset is
+/// also RFC 3339. With the `chrono` feature, values with a known offset
+/// convert to and from `chrono::DateTime<FixedOffset>`, and all values to
+/// `chrono::NaiveDateTime`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DateTime {
+    pub year: i32,
+    pub month: u8,
+    pub day: u8,
+    pub hour: u8,
+    pub minute: u8,
+    /// Seconds with their fraction, OGR keeps milliseconds.
+    pub second: f32,
+    pub time_zone: TimeZone,
+}
+
+
+impl DateTime {
+    pub fn new(year: i32, month: u8, day: u8, hour: u8, minute: u8, second: f32, time_zone: TimeZone) -> DateTime {
+        return DateTime{
+            year: year, month: month, day: day,
+            hour: hour, minute: minute, second: second,
+            time_zone: time_zone,
+        };
+    }
+}
+
+
+impl fmt::Display for DateTime {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}T{:02}:{:02}:", self.year, self.month, self.day, self.hour, self.minute)?;
+        match self.second.fract() == 0. {
+            true => write!(f, "{:02}", self.second)?,
+            false => write!(f, "{:06.3}", self.second)?,
+        }
+        return match self.time_zone.utc_offset() {
+            Some(0) => write!(f, "Z"),
+            Some(minutes) => {
+                let sign = if minutes < 0 { '-' } else { '+' };
+                write!(f, "{}{:02}:{:02}", sign, minutes.abs() / 60, minutes.abs() % 60)
+            },
+            None => Ok(()),
+        };
+    }
+}
+
+
+/// Fails for values without a known offset and for invalid dates.
+#[cfg(feature = "chrono")]
+impl TryFrom<DateTime> for chrono::DateTime<chrono::FixedOffset> {
+    type Error = GdalError;
+
+    fn try_from(value: DateTime) -> Result<chrono::DateTime<chrono::FixedOffset>, GdalError> {
+        let offset = match value.time_zone.utc_offset().and_then(|minutes| chrono::FixedOffset::east_opt(minutes * 60)) {
+            Some(offset) => offset,
+            None => return Err(GdalError::CallFailed{desc: "date and time has no known UTC offset"}),
+        };
+        let naive = chrono::NaiveDateTime::try_from(value)?;
+        return match naive.and_local_timezone(offset).single() {
+            Some(datetime) => Ok(datetime),
+            None => Err(GdalError::CallFailed{desc: "invalid date and time"}),
+        };
+    }
+}
+
+
+/// The local date and time, ignoring the time zone. Fails for invalid
+/// dates.
+#[cfg(feature = "chrono")]
+impl TryFrom<DateTime> for chrono::NaiveDateTime {
+    type Error = GdalError;
+
+    fn try_from(value: DateTime) -> Result<chrono::NaiveDateTime, GdalError> {
+        let whole_seconds = value.second.trunc();
+        // OGR keeps milliseconds, don't make up digits from f32 rounding
+        let millis = (((value.second - whole_seconds) * 1000.).round() as u32).min(999);
+        let naive = chrono::NaiveDate::from_ymd_opt(value.year, value.month as u32, value.day as u32)
+            .and_then(|date| date.and_hms_milli_opt(value.hour as u32, value.minute as u32, whole_seconds as u32, millis));
+        return match naive {
+            Some(naive) => Ok(naive),
+            None => Err(GdalError::CallFailed{desc: "invalid date and time"}),
+        };
+    }
+}
+
+
+#[cfg(feature = "chrono")]
+impl From<chrono::DateTime<chrono::FixedOffset>> for DateTime {
+    fn from(value: chrono::DateTime<chrono::FixedOffset>) -> DateTime {
+        use chrono::{Datelike, Timelike};
+        let time_zone = match value.offset().local_minus_utc() / 60 {
+            0 => TimeZone::Utc,
+            minutes => TimeZone::Offset(minutes),
+        };
+        let second = value.second() as f32 + (value.nanosecond() / 1_000_000) as f32 / 1000.;
+        return DateTime::new(
+            value.year(), value.month() as u8, value.day() as u8,
+            value.hour() as u8, value.minute() as u8, second, time_zone,
+        );
+    }
 }
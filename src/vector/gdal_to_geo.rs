@@ -1,9 +1,10 @@
-use vector::{Geometry, ogr};
+use vector::{Geometry, GeometryType, ogr};
 use geo;
 
 impl geo::ToGeo for Geometry {
     fn to_geo(&self) -> geo::Geometry {
-        let geometry_type = unsafe { ogr::OGR_G_GetGeometryType(self.c_geometry()) };
+        // geo is 2D only, so Z and M coordinates are dropped
+        let geometry_type = GeometryType::flatten(self.wkb_type());
 
         let ring = |n: usize| {
             let ring = unsafe { self._get_geometry(n) };
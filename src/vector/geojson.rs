@@ -0,0 +1,77 @@
+//! GeoJSON layer creation options
+//!
+//! GDAL 2.x has no driver independent way to declare coordinate precision
+//! (`OGRGeomCoordinatePrecision` needs GDAL 3.9), but the GeoJSON driver
+//! rounds coordinates on output, which shrinks files considerably.
+//!
+//! ```no_run
+//! use std::path::Path;
+//! use gdal::vector::{Driver, WKB_POINT};
+//! use gdal::vector::geojson::GeoJsonLayerOptions;
+//!
+//! let options = GeoJsonLayerOptions::new().coordinate_precision(6).write_bbox(true);
+//! let driver = Driver::get("GeoJSON").unwrap();
+//! let mut dataset = driver.create(Path::new("/tmp/points.geojson")).unwrap();
+//! let layer = dataset.create_layer_ext("points", None, WKB_POINT, &options.options()).unwrap();
+//! ```
+
+/// Builder for GeoJSON layer creation options, rendered with `options()`
+/// to the string list expected by `Dataset::create_layer_ext`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GeoJsonLayerOptions {
+    coordinate_precision: Option<usize>,
+    significant_figures: Option<usize>,
+    write_bbox: bool,
+    rfc7946: bool,
+}
+
+impl GeoJsonLayerOptions {
+    pub fn new() -> GeoJsonLayerOptions {
+        return GeoJsonLayerOptions::default();
+    }
+
+    /// Round coordinates to `decimals` digits after the decimal point.
+    pub fn coordinate_precision(mut self, decimals: usize) -> GeoJsonLayerOptions {
+        self.coordinate_precision = Some(decimals);
+        return self;
+    }
+
+    /// Write real field values with at most `digits` significant digits,
+    /// 17 by default.
+    pub fn significant_figures(mut self, digits: usize) -> GeoJsonLayerOptions {
+        self.significant_figures = Some(digits);
+        return self;
+    }
+
+    /// Write the bounding box of every feature and of the layer.
+    pub fn write_bbox(mut self, write: bool) -> GeoJsonLayerOptions {
+        self.write_bbox = write;
+        return self;
+    }
+
+    /// Write [RFC 7946](https://tools.ietf.org/html/rfc7946) GeoJSON
+    /// instead of the 2008 format (GDAL 2.2). Geometries are reprojected to
+    /// WGS84 and coordinates rounded to 7 decimals unless
+    /// `coordinate_precision` says otherwise.
+    pub fn rfc7946(mut self, rfc7946: bool) -> GeoJsonLayerOptions {
+        self.rfc7946 = rfc7946;
+        return self;
+    }
+
+    pub fn options(&self) -> Vec<String> {
+        let mut options = Vec::new();
+        if let Some(decimals) = self.coordinate_precision {
+            options.push(format!("COORDINATE_PRECISION={}", decimals));
+        }
+        if let Some(digits) = self.significant_figures {
+            options.push(format!("SIGNIFICANT_FIGURES={}", digits));
+        }
+        if self.write_bbox {
+            options.push("WRITE_BBOX=YES".to_string());
+        }
+        if self.rfc7946 {
+            options.push("RFC7946=YES".to_string());
+        }
+        return options;
+    }
+}
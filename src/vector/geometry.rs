@@ -1,46 +1,79 @@
+use std::fmt;
 use std::ptr::null;
 use libc::{c_char, c_int, c_double, c_void};
-use std::ffi::CString;
-use std::cell::RefCell;
-use utils::_string;
+use std::marker::PhantomData;
+use std::ops::Deref;
+use utils::{_cstring, _string, CslStringList};
 use vector::ogr;
+use vector::envelope::{Envelope, Envelope3D};
+use vector::geometry_type::GeometryType;
+use spatial_ref::{SpatialRef, CoordTransform};
+use GdalError;
+
+/// GML version written by `Geometry::gml`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GmlFormat {
+    Gml2,
+    Gml3,
+    /// GML 3.2, e.g. for WFS 2.0.
+    Gml32,
+}
+
+impl GmlFormat {
+    /// The value of the `FORMAT` export option.
+    pub fn name(&self) -> &'static str {
+        match *self {
+            GmlFormat::Gml2 => "GML2",
+            GmlFormat::Gml3 => "GML3",
+            GmlFormat::Gml32 => "GML32",
+        }
+    }
+}
 
 /// OGR Geometry
 pub struct Geometry {
-    c_geometry_ref: RefCell<Option<*const c_void>>,
+    c_geometry: *const c_void,
     owned: bool,
 }
 
 
-impl Geometry {
-    pub unsafe fn lazy_feature_geometry() -> Geometry {
-        // Geometry objects created with this method map to a Feature's
-        // geometry whose memory is managed by the GDAL feature.
-        // This object has a tricky lifecycle:
-        //
-        // * Initially it's created with a null c_geometry
-        // * The first time `Feature::geometry` is called, it gets
-        //   c_geometry from GDAL and calls `set_c_geometry` with it.
-        // * When the Feature is destroyed, this object is also destroyed,
-        //   which is good, because that's when c_geometry (which is managed
-        //   by the GDAL feature) becomes invalid. Because `self.owned` is
-        //   `true`, we don't call `OGR_G_DestroyGeometry`.
-        return Geometry{c_geometry_ref: RefCell::new(None), owned: false};
+/// A geometry owned by someone else, typically a `Feature`, borrowed for
+/// lifetime `'a`. It derefs to `Geometry`; use `to_owned` to keep a copy
+/// around after the owner is gone.
+pub struct GeometryRef<'a> {
+    geometry: Geometry,
+    _owner: PhantomData<&'a ()>,
+}
+
+
+impl<'a> GeometryRef<'a> {
+    pub unsafe fn _with_c_geometry(c_geometry: *const c_void) -> GeometryRef<'a> {
+        return GeometryRef{
+            geometry: Geometry::with_c_geometry(c_geometry, false),
+            _owner: PhantomData,
+        };
     }
 
-    pub fn has_gdal_ptr(&self) -> bool {
-        return self.c_geometry_ref.borrow().is_some();
+    /// Clone the geometry into one that isn't tied to its owner.
+    pub fn to_owned(&self) -> Geometry {
+        return self.geometry.clone();
     }
+}
+
+
+impl<'a> Deref for GeometryRef<'a> {
+    type Target = Geometry;
 
-    pub unsafe fn set_c_geometry(&self, c_geometry: *const c_void) {
-        assert!(! self.has_gdal_ptr());
-        assert_eq!(self.owned, false);
-        *(self.c_geometry_ref.borrow_mut()) = Some(c_geometry);
+    fn deref(&self) -> &Geometry {
+        return &self.geometry;
     }
+}
+
 
+impl Geometry {
     unsafe fn with_c_geometry(c_geom: *const c_void, owned: bool) -> Geometry {
         return Geometry{
-            c_geometry_ref: RefCell::new(Some(c_geom)),
+            c_geometry: c_geom,
             owned: owned,
         };
     }
@@ -53,25 +86,36 @@ impl Geometry {
 
     /// Create a geometry by parsing a
     /// [WKT](https://en.wikipedia.org/wiki/Well-known_text) string.
-    pub fn from_wkt(wkt: &str) -> Geometry {
-        let c_wkt = CString::new(wkt.as_bytes()).unwrap();
+    pub fn from_wkt(wkt: &str) -> Result<Geometry, GdalError> {
+        let c_wkt = _cstring(wkt)?;
         let mut c_wkt_ptr: *const c_char = c_wkt.as_ptr();
         let mut c_geom: *const c_void = null();
         let rv = unsafe { ogr::OGR_G_CreateFromWkt(&mut c_wkt_ptr, null(), &mut c_geom) };
-        assert_eq!(rv, ogr::OGRERR_NONE);
-        return unsafe { Geometry::with_c_geometry(c_geom, true) };
+        return match rv {
+            ogr::OGRERR_NONE => Ok(unsafe { Geometry::with_c_geometry(c_geom, true) }),
+            _ => Err(GdalError::CallFailed{desc: "invalid WKT geometry"}),
+        };
     }
 
     /// Create a rectangular geometry from West, South, East and North values.
     pub fn bbox(w: f64, s: f64, e: f64, n: f64) -> Geometry {
-        Geometry::from_wkt(&format!(
-            "POLYGON (({} {}, {} {}, {} {}, {} {}, {} {}))",
-            w, n,
-            e, n,
-            e, s,
-            w, s,
-            w, n,
-        ))
+        let mut ring = Geometry::empty(ogr::WKB_LINEARRING);
+        for &(x, y) in &[(w, n), (e, n), (e, s), (w, s), (w, n)] {
+            ring.add_point_2d((x, y));
+        }
+        let mut polygon = Geometry::empty(ogr::WKB_POLYGON);
+        polygon.add_geometry(ring);
+        return polygon;
+    }
+
+    /// The kind of this geometry, without its Z and M dimensions.
+    pub fn geometry_type(&self) -> GeometryType {
+        return GeometryType::from_wkb(self.wkb_type());
+    }
+
+    /// The raw OGR geometry type code, including the Z and M dimensions.
+    pub fn wkb_type(&self) -> c_int {
+        return unsafe { ogr::OGR_G_GetGeometryType(self.c_geometry()) };
     }
 
     /// Serialize the geometry as JSON.
@@ -82,6 +126,51 @@ impl Geometry {
         return rv;
     }
 
+    /// Serialize the geometry as JSON, rounding coordinates to `decimals`
+    /// digits after the decimal point.
+    pub fn json_with_precision(&self, decimals: usize) -> String {
+        let c_options = CslStringList::new(&[format!("COORDINATE_PRECISION={}", decimals)]).unwrap();
+        let c_json = unsafe { ogr::OGR_G_ExportToJsonEx(self.c_geometry(), c_options.as_ptr()) };
+        let rv = _string(c_json);
+        unsafe { ogr::VSIFree(c_json as *mut c_void) };
+        return rv;
+    }
+
+    /// Serialize the geometry as a GML fragment of `format`.
+    pub fn gml(&self, format: GmlFormat) -> String {
+        let c_options = CslStringList::new(&[format!("FORMAT={}", format.name())]).unwrap();
+        let c_gml = unsafe { ogr::OGR_G_ExportToGMLEx(self.c_geometry(), c_options.as_ptr()) };
+        let rv = _string(c_gml);
+        unsafe { ogr::VSIFree(c_gml as *mut c_void) };
+        return rv;
+    }
+
+    /// Serialize the geometry as a KML fragment. Coordinates are written
+    /// as they are, so the geometry should be in WGS84 already.
+    pub fn kml(&self) -> String {
+        let c_kml = unsafe { ogr::OGR_G_ExportToKML(self.c_geometry(), null()) };
+        let rv = _string(c_kml);
+        unsafe { ogr::VSIFree(c_kml as *mut c_void) };
+        return rv;
+    }
+
+    /// Parse a GML geometry fragment, e.g. `<gml:Point>...</gml:Point>`,
+    /// in any GML version. `None` if it can't be parsed.
+    pub fn from_gml(gml: &str) -> Option<Geometry> {
+        let c_gml = _cstring(gml).ok()?;
+        let c_geom = unsafe { ogr::OGR_G_CreateFromGML(c_gml.as_ptr()) };
+        return Geometry::from_c_result(c_geom);
+    }
+
+    /// Parse a GeoJSON geometry object, e.g.
+    /// `{"type": "Point", "coordinates": [1, 2]}`, the inverse of `json`.
+    /// Returns `None` if it can't be parsed.
+    pub fn from_geojson(json: &str) -> Option<Geometry> {
+        let c_json = _cstring(json).ok()?;
+        let c_geom = unsafe { ogr::OGR_G_CreateGeometryFromJson(c_json.as_ptr()) };
+        return Geometry::from_c_result(c_geom);
+    }
+
     /// Serialize the geometry as WKT.
     pub fn wkt(&self) -> String {
         let mut c_wkt: *const c_char = null();
@@ -93,7 +182,7 @@ impl Geometry {
     }
 
     pub unsafe fn c_geometry(&self) -> *const c_void {
-        return self.c_geometry_ref.borrow().unwrap();
+        return self.c_geometry;
     }
 
     pub unsafe fn into_c_geometry(mut self) -> *const c_void {
@@ -112,6 +201,93 @@ impl Geometry {
         ) };
     }
 
+    /// Set point `i` with a Z coordinate, making the geometry 3D. The
+    /// geometry grows if `i` is past its last point.
+    pub fn set_point(&mut self, i: usize, p: (f64, f64, f64)) {
+        let (x, y, z) = p;
+        unsafe { ogr::OGR_G_SetPoint(
+            self.c_geometry(),
+            i as c_int,
+            x as c_double,
+            y as c_double,
+            z as c_double,
+        ) };
+    }
+
+    /// Append a point with a Z coordinate to a line string or ring, making
+    /// the geometry 3D.
+    pub fn add_point(&mut self, p: (f64, f64, f64)) {
+        let (x, y, z) = p;
+        unsafe { ogr::OGR_G_AddPoint(
+            self.c_geometry(),
+            x as c_double,
+            y as c_double,
+            z as c_double,
+        ) };
+    }
+
+    /// Append a point to a line string or ring.
+    pub fn add_point_2d(&mut self, p: (f64, f64)) {
+        let (x, y) = p;
+        unsafe { ogr::OGR_G_AddPoint_2D(
+            self.c_geometry(),
+            x as c_double,
+            y as c_double,
+        ) };
+    }
+
+    /// Set point `i` with an M coordinate, making the geometry measured.
+    /// Needs GDAL 2.1, like the other M functions.
+    pub fn set_point_m(&mut self, i: usize, p: (f64, f64, f64)) {
+        let (x, y, m) = p;
+        unsafe { ogr::OGR_G_SetPointM(
+            self.c_geometry(),
+            i as c_int,
+            x as c_double,
+            y as c_double,
+            m as c_double,
+        ) };
+    }
+
+    /// Set point `i` with Z and M coordinates, making the geometry 3D and
+    /// measured.
+    pub fn set_point_zm(&mut self, i: usize, p: (f64, f64, f64, f64)) {
+        let (x, y, z, m) = p;
+        unsafe { ogr::OGR_G_SetPointZM(
+            self.c_geometry(),
+            i as c_int,
+            x as c_double,
+            y as c_double,
+            z as c_double,
+            m as c_double,
+        ) };
+    }
+
+    /// Append a point with an M coordinate to a line string or ring,
+    /// making the geometry measured.
+    pub fn add_point_m(&mut self, p: (f64, f64, f64)) {
+        let (x, y, m) = p;
+        unsafe { ogr::OGR_G_AddPointM(
+            self.c_geometry(),
+            x as c_double,
+            y as c_double,
+            m as c_double,
+        ) };
+    }
+
+    /// Append a point with Z and M coordinates to a line string or ring,
+    /// making the geometry 3D and measured.
+    pub fn add_point_zm(&mut self, p: (f64, f64, f64, f64)) {
+        let (x, y, z, m) = p;
+        unsafe { ogr::OGR_G_AddPointZM(
+            self.c_geometry(),
+            x as c_double,
+            y as c_double,
+            z as c_double,
+            m as c_double,
+        ) };
+    }
+
     pub fn get_point(&self, i: i32) -> (f64, f64, f64) {
         let mut x: c_double = 0.;
         let mut y: c_double = 0.;
@@ -125,17 +301,534 @@ impl Geometry {
         return (0..length).map(|i| self.get_point(i)).collect();
     }
 
+    /// Point `i` as `(x, y, z, m)`, with 0 for the coordinates the
+    /// geometry doesn't have.
+    pub fn get_point_zm(&self, i: i32) -> (f64, f64, f64, f64) {
+        let mut x: c_double = 0.;
+        let mut y: c_double = 0.;
+        let mut z: c_double = 0.;
+        let mut m: c_double = 0.;
+        unsafe { ogr::OGR_G_GetPointZM(self.c_geometry(), i, &mut x, &mut y, &mut z, &mut m) };
+        return (x as f64, y as f64, z as f64, m as f64);
+    }
+
+    pub fn get_point_vec_zm(&self) -> Vec<(f64, f64, f64, f64)> {
+        let length = unsafe{ ogr::OGR_G_GetPointCount(self.c_geometry()) };
+        return (0..length).map(|i| self.get_point_zm(i)).collect();
+    }
+
+    /// Whether this geometry has M coordinates.
+    pub fn is_measured(&self) -> bool {
+        return unsafe { ogr::OGR_G_IsMeasured(self.c_geometry()) } != 0;
+    }
+
+    /// Add or remove the M coordinates of this geometry and its parts.
+    /// Added M coordinates are 0.
+    pub fn set_measured(&mut self, measured: bool) {
+        unsafe { ogr::OGR_G_SetMeasured(self.c_geometry(), measured as c_int) };
+    }
+
+    /// Whether this geometry has Z coordinates.
+    pub fn is_3d(&self) -> bool {
+        return unsafe { ogr::OGR_G_Is3D(self.c_geometry()) } != 0;
+    }
+
+    /// Add or remove the Z coordinates of this geometry and its parts.
+    /// Added Z coordinates are 0.
+    pub fn set_3d(&mut self, is_3d: bool) {
+        unsafe { ogr::OGR_G_Set3D(self.c_geometry(), is_3d as c_int) };
+    }
+
+    /// Number of coordinates per point: 2 for XY, 3 for XYZ or XYM and 4
+    /// for XYZM.
+    pub fn coordinate_dimension(&self) -> usize {
+        return unsafe { ogr::OGR_G_CoordinateDimension(self.c_geometry()) } as usize;
+    }
+
+    /// Whether this is a curve geometry or contains one: a circular string,
+    /// compound curve, curve polygon, multi curve or multi surface, even if
+    /// made of straight segments only. Needs GDAL 2.0, like
+    /// `get_linear_geometry`.
+    pub fn has_curve_geometry(&self) -> bool {
+        return unsafe { ogr::OGR_G_HasCurveGeometry(self.c_geometry(), 0) } != 0;
+    }
+
+    /// A copy of this geometry with curves approximated by straight
+    /// segments, e.g. a line string for a circular string and a polygon for
+    /// a curve polygon. Arcs are split into steps of at most
+    /// `max_angle_step` degrees, 0 for GDAL's default of 4 degrees.
+    /// Geometries without curves are just copied.
+    pub fn get_linear_geometry(&self, max_angle_step: f64) -> Geometry {
+        let c_geom = unsafe { ogr::OGR_G_GetLinearGeometry(self.c_geometry(), max_angle_step as c_double, null()) };
+        assert!(!c_geom.is_null());
+        return unsafe { Geometry::with_c_geometry(c_geom, true) };
+    }
+
+    /// Swap the X and Y coordinates of every point in place, e.g. to fix
+    /// latitude/longitude data read as longitude/latitude.
+    pub fn swap_xy(&mut self) {
+        unsafe { ogr::OGR_G_SwapXY(self.c_geometry()) };
+    }
+
+    /// Area of the surfaces of this geometry in the squared units of its
+    /// spatial reference; 0 for points and lines.
+    pub fn area(&self) -> f64 {
+        return unsafe { ogr::OGR_G_Area(self.c_geometry()) } as f64;
+    }
+
+    /// Length of the curves of this geometry in the units of its spatial
+    /// reference; 0 for points and polygons. Needs GDAL 2.2.
+    pub fn length(&self) -> f64 {
+        return unsafe { ogr::OGR_G_Length(self.c_geometry()) } as f64;
+    }
+
+    /// Area of the surfaces of this geometry on the ellipsoid of its
+    /// spatial reference in square meters, so also for longitudes and
+    /// latitudes where `area` returns square degrees. Edges are followed
+    /// in longitude and latitude, densified to 0.1 degrees, and measured in
+    /// a Lambert azimuthal equal-area projection centered on the geometry.
+    /// Fails for geometries without a spatial reference.
+    ///
+    /// Geometries crossing the antimeridian aren't handled: their edges
+    /// are followed the long way around the globe. `OGR_G_GeodesicArea`
+    /// would handle them but needs GDAL 3.9, this works with GDAL 2.
+    pub fn geodesic_area(&self) -> Result<f64, GdalError> {
+        let geog_cs = self.geodesic_cs()?;
+        let mut geometry = self.clone();
+        geometry.transform_to(&geog_cs)?;
+        let (lon, lat) = match geometry._centroid_xy() {
+            Some(center) => center,
+            None => return Ok(0.),
+        };
+        let equal_area = geog_cs.lambert_azimuthal_equal_area(lon, lat)?;
+        geometry.segmentize(0.1);
+        geometry.transform_to(&equal_area)?;
+        return Ok(geometry.area());
+    }
+
+    /// Length of the lines and the perimeter of the polygons of this
+    /// geometry along geodesics on the ellipsoid of its spatial reference,
+    /// in meters. Segments are measured with Vincenty's formula, which is
+    /// accurate to a millimeter, and on a sphere for the rare nearly
+    /// antipodal points where it doesn't converge. Fails for geometries
+    /// without a spatial reference. Segments crossing the antimeridian are
+    /// measured the long way around, like with `geodesic_area`.
+    pub fn geodesic_length(&self) -> Result<f64, GdalError> {
+        let geog_cs = self.geodesic_cs()?;
+        let semi_major = geog_cs.semi_major()?;
+        let inv_flattening = geog_cs.inv_flattening()?;
+        let flattening = if inv_flattening == 0. { 0. } else { 1. / inv_flattening };
+        let mut geometry = self.get_linear_geometry(0.);
+        geometry.transform_to(&geog_cs)?;
+        let path_length = |points: Vec<(f64, f64, f64)>| -> f64 {
+            points.windows(2)
+                .map(|pair| geodesic_distance(semi_major, flattening, (pair[0].0, pair[0].1), (pair[1].0, pair[1].1)))
+                .sum()
+        };
+        let mut length = 0.;
+        for leaf in geometry.flatten() {
+            length += match leaf.geometry_count() {
+                0 => path_length(leaf.get_point_vec()),
+                _ => leaf.sub_geometries().map(|ring| path_length(ring.get_point_vec())).sum(),
+            };
+        }
+        return Ok(length);
+    }
+
+    /// The geographic coordinate system of this geometry with longitude
+    /// first. GDAL 3 follows the authority's axis order, latitude first
+    /// for EPSG:4326, but never for coordinate systems defined by PROJ.4
+    /// strings; converting through one forces the traditional order with
+    /// GDAL 2 and 3 alike.
+    fn geodesic_cs(&self) -> Result<SpatialRef, GdalError> {
+        let geog_cs = match self.spatial_ref() {
+            Some(srs) => srs.geog_cs()?,
+            None => return Err(GdalError::CallFailed{desc: "geometry has no spatial reference"}),
+        };
+        return SpatialRef::from_proj4(&geog_cs.to_proj4()?);
+    }
+
+    /// Shortest distance between this geometry and `other` in the units of
+    /// their spatial reference, ignoring Z; 0 if they intersect and -1 if
+    /// GDAL fails, e.g. without GEOS.
+    pub fn distance(&self, other: &Geometry) -> f64 {
+        return unsafe { ogr::OGR_G_Distance(self.c_geometry(), other.c_geometry()) } as f64;
+    }
+
+    /// Like `distance`, but taking Z into account. Needs GDAL 2.2 and SFCGAL
+    /// for anything but points.
+    pub fn distance_3d(&self, other: &Geometry) -> f64 {
+        return unsafe { ogr::OGR_G_Distance3D(self.c_geometry(), other.c_geometry()) } as f64;
+    }
+
+    /// The spatial reference of this geometry, if it has one. Geometries
+    /// read from a layer have the spatial reference of the layer.
+    pub fn spatial_ref(&self) -> Option<SpatialRef> {
+        let c_srs = unsafe { ogr::OGR_G_GetSpatialReference(self.c_geometry()) };
+        return match c_srs.is_null() {
+            true  => None,
+            // the geometry keeps its reference
+            false => Some(unsafe { SpatialRef::_clone_from_c_srs(c_srs) }),
+        };
+    }
+
+    /// Declare the spatial reference of this geometry, without changing
+    /// its coordinates; see `transform_to` to reproject it. The geometry
+    /// gets its own copy of `srs`, and passes it on to its clones.
+    pub fn set_spatial_ref(&mut self, srs: &SpatialRef) {
+        let copy = srs.clone();
+        unsafe { ogr::OGR_G_AssignSpatialReference(self.c_geometry(), copy._c_ptr()) };
+    }
+
+    /// Reproject the coordinates of this geometry in place with
+    /// `transform`. The geometry then takes the target spatial reference of
+    /// the transformation.
+    pub fn transform(&mut self, transform: &CoordTransform) -> Result<(), GdalError> {
+        let rv = unsafe { ogr::OGR_G_Transform(self.c_geometry(), transform._c_ptr()) };
+        return match rv {
+            ogr::OGRERR_NONE => Ok(()),
+            _ => Err(GdalError::CallFailed{desc: "OGR_G_Transform failed"}),
+        };
+    }
+
+    /// Reproject this geometry in place to `srs`. Only works for geometries
+    /// that know their spatial reference, e.g. those read from a layer.
+    pub fn transform_to(&mut self, srs: &SpatialRef) -> Result<(), GdalError> {
+        let rv = unsafe { ogr::OGR_G_TransformTo(self.c_geometry(), srs._c_ptr()) };
+        return match rv {
+            ogr::OGRERR_NONE => Ok(()),
+            _ => Err(GdalError::CallFailed{desc: "OGR_G_TransformTo failed"}),
+        };
+    }
+
+    /// Add points in place along the edges of this geometry so no segment
+    /// is longer than `max_length`, e.g. to keep the shape of long edges
+    /// when reprojecting them. The existing points are kept.
+    pub fn segmentize(&mut self, max_length: f64) {
+        unsafe { ogr::OGR_G_Segmentize(self.c_geometry(), max_length as c_double) };
+    }
+
+    /// Close the rings of polygons in place, adding their first point at
+    /// the end where it's missing.
+    pub fn close_rings(&mut self) {
+        unsafe { ogr::OGR_G_CloseRings(self.c_geometry()) };
+    }
+
+    /// Drop the Z coordinates of this geometry in place.
+    pub fn flatten_to_2d(&mut self) {
+        unsafe { ogr::OGR_G_FlattenTo2D(self.c_geometry()) };
+    }
+
+    /// A copy of this geometry as a polygon, e.g. from a multi-polygon of
+    /// a single part or a curve polygon. Geometries that can't be converted
+    /// are copied as they are.
+    pub fn to_polygon(&self) -> Geometry {
+        return self.force(ogr::OGR_G_ForceToPolygon);
+    }
+
+    /// A copy of this geometry as a line string, joining the parts of a
+    /// multi-line string where they touch. See `to_polygon`.
+    pub fn to_line_string(&self) -> Geometry {
+        return self.force(ogr::OGR_G_ForceToLineString);
+    }
+
+    /// A copy of this geometry as a multi-polygon, e.g. to give polygons
+    /// and multi-polygons read from a shapefile the same type. See
+    /// `to_polygon`.
+    pub fn to_multi_polygon(&self) -> Geometry {
+        return self.force(ogr::OGR_G_ForceToMultiPolygon);
+    }
+
+    /// A copy of this geometry as a multi-point. See `to_polygon`.
+    pub fn to_multi_point(&self) -> Geometry {
+        return self.force(ogr::OGR_G_ForceToMultiPoint);
+    }
+
+    /// A copy of this geometry as a multi-line string. See `to_polygon`.
+    pub fn to_multi_line_string(&self) -> Geometry {
+        return self.force(ogr::OGR_G_ForceToMultiLineString);
+    }
+
+    /// Apply one of the `OGR_G_ForceTo*` functions, which take ownership
+    /// of their argument, to a copy of this geometry.
+    fn force(&self, force_to: unsafe extern "C" fn(*const c_void) -> *const c_void) -> Geometry {
+        let c_geom = unsafe { force_to(self.clone().into_c_geometry()) };
+        assert!(!c_geom.is_null());
+        return unsafe { Geometry::with_c_geometry(c_geom, true) };
+    }
+
     /// Compute the convex hull of this geometry.
     pub fn convex_hull(&self) -> Geometry {
         let c_geom = unsafe { ogr::OGR_G_ConvexHull(self.c_geometry()) };
         return unsafe { Geometry::with_c_geometry(c_geom, true) };
     }
 
-    pub unsafe fn _get_geometry(&self, n: usize) -> Geometry {
-        // get the n-th sub-geometry as a non-owned Geometry; don't keep this
-        // object for long.
+    /// The boundary of this geometry: the rings of polygons as line
+    /// strings, the end points of lines, or an empty geometry for points.
+    /// `None` if GDAL was built without GEOS.
+    pub fn boundary(&self) -> Option<Geometry> {
+        return Geometry::from_c_result(unsafe { ogr::OGR_G_Boundary(self.c_geometry()) });
+    }
+
+    /// The Delaunay triangulation of the vertices of this geometry, as a
+    /// geometry collection of triangular polygons, or of their edges as a
+    /// multi line string if `only_edges` is set. Vertices closer than
+    /// `tolerance` are snapped together, 0 keeps them all. Z values are
+    /// kept, e.g. to build a TIN from a multi point of elevations. `None`
+    /// if GDAL was built without GEOS 3.4 or later.
+    pub fn delaunay_triangulation(&self, tolerance: f64, only_edges: bool) -> Option<Geometry> {
+        let c_geom = unsafe {
+            ogr::OGR_G_DelaunayTriangulation(self.c_geometry(), tolerance as c_double, only_edges as c_int)
+        };
+        return Geometry::from_c_result(c_geom);
+    }
+
+    /// Assemble the lines of this geometry collection or multi line string
+    /// into polygons, as a geometry collection. Lines must be noded, i.e.
+    /// only touch at their end points; lines not closing any polygon are
+    /// left out. `None` if GDAL was built without GEOS or this isn't made
+    /// of lines.
+    pub fn polygonize(&self) -> Option<Geometry> {
+        return Geometry::from_c_result(unsafe { ogr::OGR_G_Polygonize(self.c_geometry()) });
+    }
+
+    /// The points shared by this geometry and `other`, or `None` if GDAL
+    /// was built without GEOS or the operation failed.
+    pub fn intersection(&self, other: &Geometry) -> Option<Geometry> {
+        let c_geom = unsafe { ogr::OGR_G_Intersection(self.c_geometry(), other.c_geometry()) };
+        return Geometry::from_c_result(c_geom);
+    }
+
+    /// The points of this geometry or `other`.
+    pub fn union(&self, other: &Geometry) -> Option<Geometry> {
+        let c_geom = unsafe { ogr::OGR_G_Union(self.c_geometry(), other.c_geometry()) };
+        return Geometry::from_c_result(c_geom);
+    }
+
+    /// Dissolve the polygons of this multi polygon into a union of non
+    /// overlapping polygons, in one pass rather than with a `union` per
+    /// polygon, which is much faster for many polygons. `None` if GDAL was
+    /// built without GEOS or this isn't a multi polygon.
+    pub fn union_cascaded(&self) -> Option<Geometry> {
+        return Geometry::from_c_result(unsafe { ogr::OGR_G_UnionCascaded(self.c_geometry()) });
+    }
+
+    /// The points of this geometry that are not in `other`.
+    pub fn difference(&self, other: &Geometry) -> Option<Geometry> {
+        let c_geom = unsafe { ogr::OGR_G_Difference(self.c_geometry(), other.c_geometry()) };
+        return Geometry::from_c_result(c_geom);
+    }
+
+    /// The points of either this geometry or `other`, but not both.
+    pub fn sym_difference(&self, other: &Geometry) -> Option<Geometry> {
+        let c_geom = unsafe { ogr::OGR_G_SymDifference(self.c_geometry(), other.c_geometry()) };
+        return Geometry::from_c_result(c_geom);
+    }
+
+    fn from_c_result(c_geom: *const c_void) -> Option<Geometry> {
+        return match c_geom.is_null() {
+            true => None,
+            false => Some(unsafe { Geometry::with_c_geometry(c_geom, true) }),
+        };
+    }
+
+    /// The polygon of all points within `distance` of this geometry, in the
+    /// units of its coordinates. Curves are approximated with
+    /// `n_quad_segs` segments per quarter circle; 30 is what GDAL uses by
+    /// default. A negative distance shrinks polygons. `None` if GDAL was
+    /// built without GEOS or the operation failed.
+    pub fn buffer(&self, distance: f64, n_quad_segs: u32) -> Option<Geometry> {
+        let c_geom = unsafe { ogr::OGR_G_Buffer(self.c_geometry(), distance as c_double, n_quad_segs as c_int) };
+        return Geometry::from_c_result(c_geom);
+    }
+
+    /// Simplify this geometry with the Douglas-Peucker algorithm, dropping
+    /// vertices closer than `tolerance` to the simplified line. Polygons
+    /// may become invalid or collapse; `None` if GDAL was built without
+    /// GEOS or the operation failed.
+    pub fn simplify(&self, tolerance: f64) -> Option<Geometry> {
+        let c_geom = unsafe { ogr::OGR_G_Simplify(self.c_geometry(), tolerance as c_double) };
+        return Geometry::from_c_result(c_geom);
+    }
+
+    /// Same as `simplify`, but the result stays valid and keeps its
+    /// topology, e.g. rings don't cross or collapse.
+    pub fn simplify_preserving_topology(&self, tolerance: f64) -> Option<Geometry> {
+        let c_geom = unsafe { ogr::OGR_G_SimplifyPreserveTopology(self.c_geometry(), tolerance as c_double) };
+        return Geometry::from_c_result(c_geom);
+    }
+
+    /// Whether this geometry has no points, e.g. `POLYGON EMPTY`.
+    pub fn is_empty(&self) -> bool {
+        let rv = unsafe { ogr::OGR_G_IsEmpty(self.c_geometry()) };
+        return rv != 0;
+    }
+
+    /// Whether this geometry is a closed and simple line string. Always
+    /// false without GEOS.
+    pub fn is_ring(&self) -> bool {
+        let rv = unsafe { ogr::OGR_G_IsRing(self.c_geometry()) };
+        return rv != 0;
+    }
+
+    /// Whether this geometry has no anomalous points, like a line string
+    /// crossing itself. Always false without GEOS.
+    pub fn is_simple(&self) -> bool {
+        let rv = unsafe { ogr::OGR_G_IsSimple(self.c_geometry()) };
+        return rv != 0;
+    }
+
+    /// Whether this geometry is valid in the OGC sense, e.g. polygons
+    /// without self intersecting rings. Always false without GEOS.
+    pub fn is_valid(&self) -> bool {
+        let rv = unsafe { ogr::OGR_G_IsValid(self.c_geometry()) };
+        return rv != 0;
+    }
+
+    /// Whether this geometry shares at least one point with `other`.
+    pub fn intersects(&self, other: &Geometry) -> bool {
+        let rv = unsafe { ogr::OGR_G_Intersects(self.c_geometry(), other.c_geometry()) };
+        return rv != 0;
+    }
+
+    /// Whether `other` lies in this geometry, with no point of `other` in its exterior.
+    pub fn contains(&self, other: &Geometry) -> bool {
+        let rv = unsafe { ogr::OGR_G_Contains(self.c_geometry(), other.c_geometry()) };
+        return rv != 0;
+    }
+
+    /// Whether this geometry lies in `other`, same as `other.contains(self)`.
+    pub fn within(&self, other: &Geometry) -> bool {
+        let rv = unsafe { ogr::OGR_G_Within(self.c_geometry(), other.c_geometry()) };
+        return rv != 0;
+    }
+
+    /// Whether the geometries share boundary points, but their interiors don't intersect.
+    pub fn touches(&self, other: &Geometry) -> bool {
+        let rv = unsafe { ogr::OGR_G_Touches(self.c_geometry(), other.c_geometry()) };
+        return rv != 0;
+    }
+
+    /// Whether the geometries share some but not all interior points, e.g. a line
+    /// running across a polygon.
+    pub fn crosses(&self, other: &Geometry) -> bool {
+        let rv = unsafe { ogr::OGR_G_Crosses(self.c_geometry(), other.c_geometry()) };
+        return rv != 0;
+    }
+
+    /// Whether the geometries have the same dimension and share some but not all
+    /// of their points.
+    pub fn overlaps(&self, other: &Geometry) -> bool {
+        let rv = unsafe { ogr::OGR_G_Overlaps(self.c_geometry(), other.c_geometry()) };
+        return rv != 0;
+    }
+
+    /// Whether the geometries share no point at all, the inverse of `intersects`.
+    pub fn disjoint(&self, other: &Geometry) -> bool {
+        let rv = unsafe { ogr::OGR_G_Disjoint(self.c_geometry(), other.c_geometry()) };
+        return rv != 0;
+    }
+
+    /// Same as `intersects`, but compares the envelopes of the geometries
+    /// first and skips the GEOS predicate when they don't overlap. In loops
+    /// where most pairs are far apart, like a naive point in polygon join,
+    /// this avoids most of the cost of `intersects`.
+    pub fn fast_intersects(&self, other: &Geometry) -> bool {
+        let (min_x, max_x, min_y, max_y) = self._envelope();
+        let (other_min_x, other_max_x, other_min_y, other_max_y) = other._envelope();
+        if min_x > other_max_x || other_min_x > max_x || min_y > other_max_y || other_min_y > max_y {
+            return false;
+        }
+        return self.intersects(other);
+    }
+
+    /// The center of mass of this geometry as a point. It may lie outside
+    /// of concave polygons; use `point_on_surface` for labels. `None` for
+    /// empty geometries or if the computation failed.
+    pub fn centroid(&self) -> Option<Geometry> {
+        let centroid = Geometry::empty(ogr::WKB_POINT);
+        let rv = unsafe { ogr::OGR_G_Centroid(self.c_geometry(), centroid.c_geometry()) };
+        return match rv {
+            ogr::OGRERR_NONE => Some(centroid),
+            _ => None,
+        };
+    }
+
+    /// A point guaranteed to lie in the interior of this polygon or
+    /// multipolygon, e.g. to place a label. `None` for other geometries or
+    /// if GDAL was built without GEOS.
+    pub fn point_on_surface(&self) -> Option<Geometry> {
+        let c_geom = unsafe { ogr::OGR_G_PointOnSurface(self.c_geometry()) };
+        return Geometry::from_c_result(c_geom);
+    }
+
+    pub fn _centroid_xy(&self) -> Option<(f64, f64)> {
+        return self.centroid().map(|centroid| {
+            let (x, y, _) = centroid.get_point(0);
+            (x, y)
+        });
+    }
+
+    /// The bounding box of this geometry.
+    pub fn envelope(&self) -> Envelope {
+        let (min_x, max_x, min_y, max_y) = self._envelope();
+        return Envelope::new(min_x, max_x, min_y, max_y);
+    }
+
+    /// The bounding box of this geometry including Z. Geometries without Z
+    /// report 0 for `min_z` and `max_z`.
+    pub fn envelope_3d(&self) -> Envelope3D {
+        let mut envelope = ogr::OGREnvelope3D::default();
+        unsafe { ogr::OGR_G_GetEnvelope3D(self.c_geometry(), &mut envelope) };
+        return Envelope3D{
+            min_x: envelope.min_x,
+            max_x: envelope.max_x,
+            min_y: envelope.min_y,
+            max_y: envelope.max_y,
+            min_z: envelope.min_z,
+            max_z: envelope.max_z,
+        };
+    }
+
+    pub fn _envelope(&self) -> (f64, f64, f64, f64) {
+        // (min_x, max_x, min_y, max_y), in OGREnvelope order
+        let mut envelope = ogr::OGREnvelope::default();
+        unsafe { ogr::OGR_G_GetEnvelope(self.c_geometry(), &mut envelope) };
+        return (envelope.min_x, envelope.max_x, envelope.min_y, envelope.max_y);
+    }
+
+    pub unsafe fn _get_geometry(&self, n: usize) -> GeometryRef<'_> {
+        // get the n-th sub-geometry, borrowed from this geometry
         let c_geom = ogr::OGR_G_GetGeometryRef(self.c_geometry(), n as c_int);
-        return Geometry::with_c_geometry(c_geom, false);
+        return GeometryRef::_with_c_geometry(c_geom);
+    }
+
+    /// Number of direct sub-geometries: the members of a collection, the
+    /// rings of a polygon, 0 for points and lines.
+    pub fn geometry_count(&self) -> usize {
+        return unsafe { ogr::OGR_G_GetGeometryCount(self.c_geometry()) } as usize;
+    }
+
+    /// The `n`-th direct sub-geometry, `None` if there are fewer.
+    pub fn sub_geometry(&self, n: usize) -> Option<GeometryRef<'_>> {
+        return match n < self.geometry_count() {
+            true  => Some(unsafe { self._get_geometry(n) }),
+            false => None,
+        };
+    }
+
+    /// Iterate over the direct sub-geometries, e.g. the polygons of a
+    /// multipolygon. See `flatten` to descend into nested collections.
+    pub fn sub_geometries(&self) -> SubGeometries<'_> {
+        return SubGeometries{geometry: self, next: 0, count: self.geometry_count()};
+    }
+
+    /// Iterate over the leaf geometries (points, lines, polygons, ...) of
+    /// this geometry, descending into nested multi geometries and
+    /// geometry collections depth first. A geometry that isn't a
+    /// collection yields itself.
+    pub fn flatten(&self) -> Flatten<'_> {
+        return Flatten{pending: vec!(self.c_geometry), _owner: PhantomData};
     }
 
     pub fn add_geometry(&mut self, mut sub: Geometry) {
@@ -149,11 +842,146 @@ impl Geometry {
     }
 }
 
+/// Iterator over the direct sub-geometries of a `Geometry`, see
+/// `sub_geometries`.
+pub struct SubGeometries<'a> {
+    geometry: &'a Geometry,
+    next: usize,
+    count: usize,
+}
+
+impl<'a> Iterator for SubGeometries<'a> {
+    type Item = GeometryRef<'a>;
+
+    fn next(&mut self) -> Option<GeometryRef<'a>> {
+        if self.next == self.count {
+            return None;
+        }
+        let sub = unsafe { self.geometry._get_geometry(self.next) };
+        self.next += 1;
+        return Some(sub);
+    }
+}
+
+/// Iterator over the leaf geometries of a `Geometry`, see `flatten`.
+pub struct Flatten<'a> {
+    // geometries still to visit, the next one last
+    pending: Vec<*const c_void>,
+    _owner: PhantomData<&'a Geometry>,
+}
+
+impl<'a> Iterator for Flatten<'a> {
+    type Item = GeometryRef<'a>;
+
+    fn next(&mut self) -> Option<GeometryRef<'a>> {
+        while let Some(c_geom) = self.pending.pop() {
+            if !is_collection(c_geom) {
+                return Some(unsafe { GeometryRef::_with_c_geometry(c_geom) });
+            }
+            let count = unsafe { ogr::OGR_G_GetGeometryCount(c_geom) };
+            for n in (0..count).rev() {
+                self.pending.push(unsafe { ogr::OGR_G_GetGeometryRef(c_geom, n) });
+            }
+        }
+        return None;
+    }
+}
+
+/// Distance in meters between two points given as longitude and latitude
+/// on the ellipsoid with `semi_major` axis and `flattening`, with Vincenty's
+/// inverse formula.
+fn geodesic_distance(semi_major: f64, flattening: f64, from: (f64, f64), to: (f64, f64)) -> f64 {
+    let semi_minor = semi_major * (1. - flattening);
+    let l = (to.0 - from.0).to_radians();
+    let u1 = ((1. - flattening) * from.1.to_radians().tan()).atan();
+    let u2 = ((1. - flattening) * to.1.to_radians().tan()).atan();
+    let (sin_u1, cos_u1, sin_u2, cos_u2) = (u1.sin(), u1.cos(), u2.sin(), u2.cos());
+
+    let mut lambda = l;
+    for _ in 0..200 {
+        let (sin_lambda, cos_lambda) = (lambda.sin(), lambda.cos());
+        let sin_sigma = ((cos_u2 * sin_lambda).powi(2)
+            + (cos_u1 * sin_u2 - sin_u1 * cos_u2 * cos_lambda).powi(2)).sqrt();
+        if sin_sigma == 0. {
+            return 0.;
+        }
+        let cos_sigma = sin_u1 * sin_u2 + cos_u1 * cos_u2 * cos_lambda;
+        let sigma = sin_sigma.atan2(cos_sigma);
+        let sin_alpha = cos_u1 * cos_u2 * sin_lambda / sin_sigma;
+        let cos2_alpha = 1. - sin_alpha * sin_alpha;
+        // 0 on the equator
+        let cos_2sigma_m = match cos2_alpha == 0. {
+            true  => 0.,
+            false => cos_sigma - 2. * sin_u1 * sin_u2 / cos2_alpha,
+        };
+        let c = flattening / 16. * cos2_alpha * (4. + flattening * (4. - 3. * cos2_alpha));
+        let previous = lambda;
+        lambda = l + (1. - c) * flattening * sin_alpha
+            * (sigma + c * sin_sigma * (cos_2sigma_m + c * cos_sigma * (-1. + 2. * cos_2sigma_m * cos_2sigma_m)));
+        if (lambda - previous).abs() < 1e-12 {
+            let u_sq = cos2_alpha * (semi_major * semi_major - semi_minor * semi_minor) / (semi_minor * semi_minor);
+            let a = 1. + u_sq / 16384. * (4096. + u_sq * (-768. + u_sq * (320. - 175. * u_sq)));
+            let b = u_sq / 1024. * (256. + u_sq * (-128. + u_sq * (74. - 47. * u_sq)));
+            let delta_sigma = b * sin_sigma * (cos_2sigma_m + b / 4. * (cos_sigma * (-1. + 2. * cos_2sigma_m * cos_2sigma_m)
+                - b / 6. * cos_2sigma_m * (-3. + 4. * sin_sigma * sin_sigma) * (-3. + 4. * cos_2sigma_m * cos_2sigma_m)));
+            return semi_minor * a * (sigma - delta_sigma);
+        }
+    }
+
+    // nearly antipodal points, take the great circle on the mean radius
+    let (lat1, lat2) = (from.1.to_radians(), to.1.to_radians());
+    let haversine = ((lat2 - lat1) / 2.).sin().powi(2) + lat1.cos() * lat2.cos() * (l / 2.).sin().powi(2);
+    return (2. * semi_major + semi_minor) / 3. * 2. * haversine.sqrt().min(1.).asin();
+}
+
+fn is_collection(c_geom: *const c_void) -> bool {
+    let wkb_type = unsafe { ogr::OGR_G_GetGeometryType(c_geom) };
+    return GeometryType::from_wkb(wkb_type).is_collection();
+}
+
 impl Drop for Geometry {
     fn drop(&mut self) {
         if self.owned {
-            let c_geometry = self.c_geometry_ref.borrow();
-            unsafe { ogr::OGR_G_DestroyGeometry(c_geometry.unwrap() as *mut c_void) };
+            unsafe { ogr::OGR_G_DestroyGeometry(self.c_geometry as *mut c_void) };
         }
     }
 }
+
+
+/// Cloning always gives an owned geometry, also for geometries borrowed
+/// from a feature.
+impl Clone for Geometry {
+    fn clone(&self) -> Geometry {
+        let c_geom = unsafe { ogr::OGR_G_Clone(self.c_geometry()) };
+        assert!(!c_geom.is_null());
+        return unsafe { Geometry::with_c_geometry(c_geom, true) };
+    }
+}
+
+
+/// Geometries are equal if they have the same type and the same points in
+/// the same order, like `OGR_G_Equals`. Rings starting at another vertex
+/// or going the other way aren't equal; use `sym_difference` to
+/// compare shapes.
+impl PartialEq for Geometry {
+    fn eq(&self, other: &Geometry) -> bool {
+        let rv = unsafe { ogr::OGR_G_Equals(self.c_geometry(), other.c_geometry()) };
+        return rv != 0;
+    }
+}
+
+
+/// Formats the geometry as WKT.
+impl fmt::Display for Geometry {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        return write!(f, "{}", self.wkt());
+    }
+}
+
+
+/// Formats the geometry as WKT, so failing `assert_eq!`s show it.
+impl fmt::Debug for Geometry {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        return write!(f, "Geometry({})", self.wkt());
+    }
+}
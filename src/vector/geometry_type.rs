@@ -0,0 +1,113 @@
+use libc::c_int;
+
+/// The kind of a geometry, decoded from an OGR WKB geometry type code
+/// without its Z and M dimensions.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GeometryType {
+    Unknown,
+    Point,
+    LineString,
+    Polygon,
+    MultiPoint,
+    MultiLineString,
+    MultiPolygon,
+    GeometryCollection,
+    CircularString,
+    CompoundCurve,
+    CurvePolygon,
+    MultiCurve,
+    MultiSurface,
+    Curve,
+    Surface,
+    PolyhedralSurface,
+    Tin,
+    Triangle,
+    /// The ring of a polygon, only found inside polygons.
+    LinearRing,
+    /// A code this crate doesn't know about.
+    Other(c_int),
+}
+
+impl GeometryType {
+    /// Remove the Z and M dimensions from `wkb_type`, both the 2.5D flag
+    /// and the ISO offsets (1000 for Z, 2000 for M, 3000 for ZM), like
+    /// `wkbFlatten`.
+    pub fn flatten(wkb_type: c_int) -> c_int {
+        return (wkb_type & 0x7fffffff) % 1000;
+    }
+
+    /// Whether `wkb_type` has a Z dimension, either with the 2.5D flag or
+    /// as an ISO Z or ZM code.
+    pub fn has_z(wkb_type: c_int) -> bool {
+        return wkb_type as u32 & 0x80000000 != 0 || matches!((wkb_type & 0x7fffffff) / 1000, 1 | 3);
+    }
+
+    /// Whether `wkb_type` has an M dimension, as an ISO M or ZM code.
+    pub fn has_m(wkb_type: c_int) -> bool {
+        return matches!((wkb_type & 0x7fffffff) / 1000, 2 | 3);
+    }
+
+    /// Decode `wkb_type`, one of the `WKB_*` constants, possibly with Z or
+    /// M.
+    pub fn from_wkb(wkb_type: c_int) -> GeometryType {
+        return match GeometryType::flatten(wkb_type) {
+            0 => GeometryType::Unknown,
+            1 => GeometryType::Point,
+            2 => GeometryType::LineString,
+            3 => GeometryType::Polygon,
+            4 => GeometryType::MultiPoint,
+            5 => GeometryType::MultiLineString,
+            6 => GeometryType::MultiPolygon,
+            7 => GeometryType::GeometryCollection,
+            8 => GeometryType::CircularString,
+            9 => GeometryType::CompoundCurve,
+            10 => GeometryType::CurvePolygon,
+            11 => GeometryType::MultiCurve,
+            12 => GeometryType::MultiSurface,
+            13 => GeometryType::Curve,
+            14 => GeometryType::Surface,
+            15 => GeometryType::PolyhedralSurface,
+            16 => GeometryType::Tin,
+            17 => GeometryType::Triangle,
+            101 => GeometryType::LinearRing,
+            other => GeometryType::Other(other),
+        };
+    }
+
+    /// The 2D WKB code, e.g. to create a layer or an empty geometry.
+    pub fn to_wkb(&self) -> c_int {
+        return match *self {
+            GeometryType::Unknown => 0,
+            GeometryType::Point => 1,
+            GeometryType::LineString => 2,
+            GeometryType::Polygon => 3,
+            GeometryType::MultiPoint => 4,
+            GeometryType::MultiLineString => 5,
+            GeometryType::MultiPolygon => 6,
+            GeometryType::GeometryCollection => 7,
+            GeometryType::CircularString => 8,
+            GeometryType::CompoundCurve => 9,
+            GeometryType::CurvePolygon => 10,
+            GeometryType::MultiCurve => 11,
+            GeometryType::MultiSurface => 12,
+            GeometryType::Curve => 13,
+            GeometryType::Surface => 14,
+            GeometryType::PolyhedralSurface => 15,
+            GeometryType::Tin => 16,
+            GeometryType::Triangle => 17,
+            GeometryType::LinearRing => 101,
+            GeometryType::Other(code) => code,
+        };
+    }
+
+    /// Whether geometries of this type are made of sub-geometries of their
+    /// own, e.g. a `MultiPolygon` of polygons. Polygons, made of rings,
+    /// don't count.
+    pub fn is_collection(&self) -> bool {
+        return matches!(
+            *self,
+            GeometryType::MultiPoint | GeometryType::MultiLineString | GeometryType::MultiPolygon |
+            GeometryType::GeometryCollection | GeometryType::MultiCurve | GeometryType::MultiSurface
+        );
+    }
+}
@@ -0,0 +1,160 @@
+//! GeoPackage creation options
+//!
+//! `GpkgOptions` holds the dataset creation options of the GPKG driver and
+//! `GpkgLayerOptions` the layer creation options.
+//!
+//! ```no_run
+//! use std::path::Path;
+//! use gdal::vector::gpkg::{GpkgLayerOptions, GpkgOptions, GpkgVersion, create_gpkg};
+//! use gdal::vector::WKB_POLYGON;
+//!
+//! let options = GpkgOptions::new().version(GpkgVersion::V1_2).add_gpkg_ogr_contents(false);
+//! let mut dataset = create_gpkg(Path::new("/tmp/parcels.gpkg"), &options).unwrap();
+//! let layer_options = GpkgLayerOptions::new().identifier("Parcels").spatial_index(false);
+//! let layer = dataset.create_layer_ext("parcels", None, WKB_POLYGON, &layer_options.options()).unwrap();
+//! ```
+
+use std::path::Path;
+use vector::{Dataset, Driver};
+
+/// Version of the GeoPackage specification a new file conforms to.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GpkgVersion {
+    /// Let the driver pick, the latest version it supports.
+    Auto,
+    V1_0,
+    V1_1,
+    /// Needs GDAL 2.2.
+    V1_2,
+    /// Needs GDAL 3.2.
+    V1_3,
+}
+
+impl GpkgVersion {
+    /// The value of the `VERSION` creation option.
+    pub fn name(&self) -> &'static str {
+        match *self {
+            GpkgVersion::Auto => "AUTO",
+            GpkgVersion::V1_0 => "1.0",
+            GpkgVersion::V1_1 => "1.1",
+            GpkgVersion::V1_2 => "1.2",
+            GpkgVersion::V1_3 => "1.3",
+        }
+    }
+}
+
+/// Builder for GeoPackage dataset creation options, rendered with
+/// `options()` to the string list expected by `Driver::create_with_options`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GpkgOptions {
+    version: Option<GpkgVersion>,
+    add_gpkg_ogr_contents: Option<bool>,
+}
+
+impl GpkgOptions {
+    pub fn new() -> GpkgOptions {
+        return GpkgOptions::default();
+    }
+
+    pub fn version(mut self, version: GpkgVersion) -> GpkgOptions {
+        self.version = Some(version);
+        return self;
+    }
+
+    /// Whether to keep feature counts in the `gpkg_ogr_contents` table,
+    /// which makes counting fast but adds triggers slowing down writes
+    /// (GDAL 2.2). Enabled by default.
+    pub fn add_gpkg_ogr_contents(mut self, add: bool) -> GpkgOptions {
+        self.add_gpkg_ogr_contents = Some(add);
+        return self;
+    }
+
+    pub fn options(&self) -> Vec<String> {
+        let mut options = Vec::new();
+        if let Some(version) = self.version {
+            options.push(format!("VERSION={}", version.name()));
+        }
+        if let Some(add) = self.add_gpkg_ogr_contents {
+            options.push(format!("ADD_GPKG_OGR_CONTENTS={}", if add { "YES" } else { "NO" }));
+        }
+        return options;
+    }
+}
+
+/// Builder for GeoPackage layer creation options, rendered with
+/// `options()` to the string list expected by `Dataset::create_layer_ext`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GpkgLayerOptions {
+    spatial_index: Option<bool>,
+    identifier: Option<String>,
+    description: Option<String>,
+    geometry_name: Option<String>,
+    fid: Option<String>,
+}
+
+impl GpkgLayerOptions {
+    pub fn new() -> GpkgLayerOptions {
+        return GpkgLayerOptions::default();
+    }
+
+    /// Whether to create an R-tree spatial index. Enabled by default; for
+    /// bulk loads it's faster to create it afterwards with
+    /// `SELECT CreateSpatialIndex(...)`.
+    pub fn spatial_index(mut self, create: bool) -> GpkgLayerOptions {
+        self.spatial_index = Some(create);
+        return self;
+    }
+
+    /// The human readable name of the layer in `gpkg_contents`, the layer
+    /// name by default.
+    pub fn identifier(mut self, identifier: &str) -> GpkgLayerOptions {
+        self.identifier = Some(identifier.to_string());
+        return self;
+    }
+
+    /// The description of the layer in `gpkg_contents`.
+    pub fn description(mut self, description: &str) -> GpkgLayerOptions {
+        self.description = Some(description.to_string());
+        return self;
+    }
+
+    /// Name of the geometry column, `geom` by default.
+    pub fn geometry_name(mut self, name: &str) -> GpkgLayerOptions {
+        self.geometry_name = Some(name.to_string());
+        return self;
+    }
+
+    /// Name of the feature id column, `fid` by default.
+    pub fn fid(mut self, name: &str) -> GpkgLayerOptions {
+        self.fid = Some(name.to_string());
+        return self;
+    }
+
+    pub fn options(&self) -> Vec<String> {
+        let mut options = Vec::new();
+        if let Some(create) = self.spatial_index {
+            options.push(format!("SPATIAL_INDEX={}", if create { "YES" } else { "NO" }));
+        }
+        if let Some(ref identifier) = self.identifier {
+            options.push(format!("IDENTIFIER={}", identifier));
+        }
+        if let Some(ref description) = self.description {
+            options.push(format!("DESCRIPTION={}", description));
+        }
+        if let Some(ref name) = self.geometry_name {
+            options.push(format!("GEOMETRY_NAME={}", name));
+        }
+        if let Some(ref name) = self.fid {
+            options.push(format!("FID={}", name));
+        }
+        return options;
+    }
+}
+
+/// Create the GeoPackage at `path`.
+pub fn create_gpkg(path: &Path, options: &GpkgOptions) -> Option<Dataset> {
+    return match Driver::get("GPKG") {
+        Some(driver) => driver.create_with_options(path, &options.options()),
+        None => None,
+    };
+}
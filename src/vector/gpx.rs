@@ -0,0 +1,129 @@
+//! GPX reading and writing
+//!
+//! The GPX driver exposes a GPX file as five layers, see `GpxLayer`.
+//!
+//! ```no_run
+//! use std::path::Path;
+//! use gdal::vector::gpx::{GpxLayer, GpxOptions, create_gpx, create_gpx_layer, open_gpx};
+//! use gdal::vector::Geometry;
+//!
+//! let mut output = create_gpx(Path::new("/tmp/out.gpx"), &GpxOptions::new()).unwrap();
+//! create_gpx_layer(&mut output, GpxLayer::Waypoints).unwrap()
+//!     .create_feature(Geometry::from_wkt("POINT (26.1 44.4)").unwrap()).unwrap();
+//!
+//! let mut input = open_gpx(Path::new("fixtures/track.gpx")).unwrap();
+//! let track_points = input.layer_by_name(GpxLayer::TrackPoints.name()).unwrap();
+//! for point in track_points.features() {
+//!     println!("{}", point.geometry().unwrap().wkt());
+//! }
+//! ```
+
+use std::path::Path;
+use libc::c_int;
+use vector::{Dataset, Driver, Layer};
+use vector::{WKB_POINT, WKB_LINESTRING, WKB_MULTILINESTRING};
+use GdalError;
+
+/// The layers of a GPX dataset.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GpxLayer {
+    /// Points of interest, as points.
+    Waypoints,
+    /// Routes, as linestrings.
+    Routes,
+    /// Tracks, as multilinestrings with one part per track segment.
+    Tracks,
+    /// The points of every route, with the id of their route.
+    RoutePoints,
+    /// The points of every track, with the id of their track and segment,
+    /// their time stamp and elevation.
+    TrackPoints,
+}
+
+impl GpxLayer {
+    /// The layer name used by the GPX driver.
+    pub fn name(&self) -> &'static str {
+        match *self {
+            GpxLayer::Waypoints => "waypoints",
+            GpxLayer::Routes => "routes",
+            GpxLayer::Tracks => "tracks",
+            GpxLayer::RoutePoints => "route_points",
+            GpxLayer::TrackPoints => "track_points",
+        }
+    }
+
+    fn geometry_type(&self) -> c_int {
+        match *self {
+            GpxLayer::Waypoints | GpxLayer::RoutePoints | GpxLayer::TrackPoints => WKB_POINT,
+            GpxLayer::Routes => WKB_LINESTRING,
+            GpxLayer::Tracks => WKB_MULTILINESTRING,
+        }
+    }
+}
+
+/// Builder for GPX dataset creation options, rendered with `options()` to
+/// the string list expected by `Driver::create_with_options`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GpxOptions {
+    extensions: Option<(String, String)>,
+    crlf: Option<bool>,
+}
+
+impl GpxOptions {
+    pub fn new() -> GpxOptions {
+        return GpxOptions::default();
+    }
+
+    /// Write fields that aren't part of the GPX schema as `<extensions>`
+    /// in the namespace `prefix`, declared with `url`. Without extensions
+    /// such fields are dropped.
+    pub fn extensions(mut self, prefix: &str, url: &str) -> GpxOptions {
+        self.extensions = Some((prefix.to_string(), url.to_string()));
+        return self;
+    }
+
+    /// End lines with CRLF instead of the platform default.
+    pub fn crlf(mut self, crlf: bool) -> GpxOptions {
+        self.crlf = Some(crlf);
+        return self;
+    }
+
+    pub fn options(&self) -> Vec<String> {
+        let mut options = Vec::new();
+        if let Some((ref prefix, ref url)) = self.extensions {
+            options.push("GPX_USE_EXTENSIONS=YES".to_string());
+            options.push(format!("GPX_EXTENSIONS_NS={}", prefix));
+            options.push(format!("GPX_EXTENSIONS_NS_URL={}", url));
+        }
+        if let Some(crlf) = self.crlf {
+            options.push(format!("LINEFORMAT={}", if crlf { "CRLF" } else { "LF" }));
+        }
+        return options;
+    }
+}
+
+/// Open the GPX file at `path`. With `GPX_ELE_AS_25D` unset, elevations
+/// are reported in the `ele` field.
+pub fn open_gpx(path: &Path) -> Option<Dataset> {
+    return Dataset::open_with_options(path, &["GPX".to_string()], &[]);
+}
+
+/// Create the GPX file at `path`. Add layers with `create_gpx_layer`.
+pub fn create_gpx(path: &Path, options: &GpxOptions) -> Option<Dataset> {
+    return match Driver::get("GPX") {
+        Some(driver) => driver.create_with_options(path, &options.options()),
+        None => None,
+    };
+}
+
+/// Create one of the GPX layers in `dataset`. The GPX driver only accepts
+/// the waypoints, routes and tracks layers, with the matching geometry
+/// type; fields with the names of the GPX elements (`name`, `ele`, `time`,
+/// ...) are written as those elements. Fails for the read-only route and
+/// track points layers.
+pub fn create_gpx_layer(dataset: &mut Dataset, layer: GpxLayer) -> Result<&mut Layer, GdalError> {
+    if layer == GpxLayer::RoutePoints || layer == GpxLayer::TrackPoints {
+        return Err(GdalError::CallFailed{desc: "read-only GPX layer"});
+    }
+    return dataset.create_layer_ext(layer.name(), None, layer.geometry_type(), &[]);
+}
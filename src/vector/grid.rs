@@ -0,0 +1,92 @@
+use std::f64::consts::PI;
+use vector::{Envelope, FieldValue, Geometry, Layer, OFT_INTEGER};
+use GdalError;
+
+/// A fishnet of `dx` by `dy` rectangles covering `envelope`, row by row
+/// from its top left corner. Cells on the right and bottom edges are cut to
+/// the envelope.
+pub fn fishnet(envelope: &Envelope, dx: f64, dy: f64) -> Result<Vec<Geometry>, GdalError> {
+    check_cell_size(dx, dy)?;
+    return Ok(fishnet_cells(envelope, dx, dy).into_iter().map(|(_, _, cell)| cell).collect());
+}
+
+/// Fill `layer` with the cells of `fishnet`. Each cell gets its `row` and
+/// `col` index as integer fields, which are added to the layer if it
+/// doesn't have them yet.
+pub fn grid(envelope: &Envelope, dx: f64, dy: f64, layer: &mut Layer) -> Result<(), GdalError> {
+    check_cell_size(dx, dy)?;
+    create_index_fields(layer)?;
+    for (row, col, cell) in fishnet_cells(envelope, dx, dy) {
+        layer.create_feature_fields(
+            cell,
+            &["row", "col"],
+            &[FieldValue::IntegerValue(row), FieldValue::IntegerValue(col)],
+        )?;
+    }
+    return Ok(());
+}
+
+fn check_cell_size(dx: f64, dy: f64) -> Result<(), GdalError> {
+    match dx.is_finite() && dy.is_finite() && dx > 0. && dy > 0. {
+        true => Ok(()),
+        false => Err(GdalError::CallFailed{desc: "grid cell size must be positive"}),
+    }
+}
+
+/// The `fishnet` cells with their row and column index.
+fn fishnet_cells(envelope: &Envelope, dx: f64, dy: f64) -> Vec<(i32, i32, Geometry)> {
+    let cols = (envelope.width() / dx).ceil().max(1.) as i32;
+    let rows = (envelope.height() / dy).ceil().max(1.) as i32;
+    let mut cells = Vec::with_capacity((rows * cols) as usize);
+    for row in 0..rows {
+        let n = envelope.max_y - row as f64 * dy;
+        let s = (n - dy).max(envelope.min_y);
+        for col in 0..cols {
+            let w = envelope.min_x + col as f64 * dx;
+            cells.push((row, col, Geometry::bbox(w, s, (w + dx).min(envelope.max_x), n)));
+        }
+    }
+    return cells;
+}
+
+/// Like `grid`, with flat topped hexagons of circumradius `size` (the
+/// distance from the center to a vertex). Odd columns are shifted down by
+/// half a cell; the first hexagon is centered on the top left corner of
+/// `envelope`. Unlike `fishnet` cells, hexagons aren't cut to the envelope.
+pub fn hex_grid(envelope: &Envelope, size: f64, layer: &mut Layer) -> Result<(), GdalError> {
+    if !size.is_finite() || size <= 0. {
+        return Err(GdalError::CallFailed{desc: "grid cell size must be positive"});
+    }
+    create_index_fields(layer)?;
+    let dx = 1.5 * size;
+    let dy = 3f64.sqrt() * size;
+    let cols = (envelope.width() / dx).ceil() as i32 + 1;
+    let rows = (envelope.height() / dy).ceil() as i32 + 1;
+    for row in 0..rows {
+        for col in 0..cols {
+            let cx = envelope.min_x + col as f64 * dx;
+            let cy = envelope.max_y - row as f64 * dy - (col % 2) as f64 * dy / 2.;
+            let mut ring: Vec<String> = (0..7).map(|i| {
+                let angle = (i % 6) as f64 * PI / 3.;
+                format!("{} {}", cx + size * angle.cos(), cy + size * angle.sin())
+            }).collect();
+            // close the ring on the exact first vertex
+            ring[6] = ring[0].clone();
+            layer.create_feature_fields(
+                Geometry::from_wkt(&format!("POLYGON (({}))", ring.join(", ")))?,
+                &["row", "col"],
+                &[FieldValue::IntegerValue(row), FieldValue::IntegerValue(col)],
+            )?;
+        }
+    }
+    return Ok(());
+}
+
+fn create_index_fields(layer: &mut Layer) -> Result<(), GdalError> {
+    for name in &["row", "col"] {
+        if !layer.defn().fields().any(|field| field.name() == *name) {
+            layer.create_defn_fields(&[(name, OFT_INTEGER)])?;
+        }
+    }
+    return Ok(());
+}
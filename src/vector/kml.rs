@@ -0,0 +1,202 @@
+//! KML and LIBKML writing
+//!
+//! The KML driver writes every layer as a `<Folder>`. The LIBKML driver
+//! supports more of the format, like documents per layer and styles; see
+//! `LibKmlLayerOptions` and `Style`.
+//!
+//! ```no_run
+//! use std::path::Path;
+//! use gdal::vector::kml::{KmlOptions, LibKmlLayerOptions, Style, create_kml};
+//! use gdal::vector::{Geometry, WKB_LINESTRING};
+//!
+//! let mut dataset = create_kml(Path::new("/tmp/roads.kml"), "LIBKML", &KmlOptions::new()).unwrap();
+//! let folder = LibKmlLayerOptions::new().folder(true).name("Roads");
+//! let layer = dataset.create_layer_ext("roads", None, WKB_LINESTRING, &folder.options()).unwrap();
+//! let style = Style::new().line_color("#ff0000ff").line_width(3.);
+//! layer.create_feature_styled(Geometry::from_wkt("LINESTRING (0 0, 1 1)").unwrap(), &style.to_ogr_style()).unwrap();
+//! ```
+
+use std::path::Path;
+use vector::{Dataset, Driver};
+
+/// How altitudes are interpreted by KML viewers.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AltitudeMode {
+    ClampToGround,
+    RelativeToGround,
+    Absolute,
+}
+
+impl AltitudeMode {
+    pub fn name(&self) -> &'static str {
+        match *self {
+            AltitudeMode::ClampToGround => "clampToGround",
+            AltitudeMode::RelativeToGround => "relativeToGround",
+            AltitudeMode::Absolute => "absolute",
+        }
+    }
+}
+
+/// Builder for KML dataset creation options, rendered with `options()` to
+/// the string list expected by `Driver::create_with_options`. All options
+/// apply to the KML driver; LIBKML only uses `document_id`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct KmlOptions {
+    name_field: Option<String>,
+    description_field: Option<String>,
+    altitude_mode: Option<AltitudeMode>,
+    document_id: Option<String>,
+}
+
+impl KmlOptions {
+    pub fn new() -> KmlOptions {
+        return KmlOptions::default();
+    }
+
+    /// The field written as the `<name>` of each placemark, `Name` by
+    /// default.
+    pub fn name_field(mut self, field: &str) -> KmlOptions {
+        self.name_field = Some(field.to_string());
+        return self;
+    }
+
+    /// The field written as the `<description>` of each placemark,
+    /// `Description` by default.
+    pub fn description_field(mut self, field: &str) -> KmlOptions {
+        self.description_field = Some(field.to_string());
+        return self;
+    }
+
+    pub fn altitude_mode(mut self, mode: AltitudeMode) -> KmlOptions {
+        self.altitude_mode = Some(mode);
+        return self;
+    }
+
+    /// The id of the root `<Document>`.
+    pub fn document_id(mut self, id: &str) -> KmlOptions {
+        self.document_id = Some(id.to_string());
+        return self;
+    }
+
+    pub fn options(&self) -> Vec<String> {
+        let mut options = Vec::new();
+        if let Some(ref field) = self.name_field {
+            options.push(format!("NameField={}", field));
+        }
+        if let Some(ref field) = self.description_field {
+            options.push(format!("DescriptionField={}", field));
+        }
+        if let Some(mode) = self.altitude_mode {
+            options.push(format!("AltitudeMode={}", mode.name()));
+        }
+        if let Some(ref id) = self.document_id {
+            options.push(format!("DOCUMENT_ID={}", id));
+        }
+        return options;
+    }
+}
+
+/// Builder for LIBKML layer creation options, rendered with `options()` to
+/// the string list expected by `Dataset::create_layer_ext`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct LibKmlLayerOptions {
+    folder: bool,
+    name: Option<String>,
+    description: Option<String>,
+}
+
+impl LibKmlLayerOptions {
+    pub fn new() -> LibKmlLayerOptions {
+        return LibKmlLayerOptions::default();
+    }
+
+    /// Write the layer as a `<Folder>` instead of a `<Document>`.
+    pub fn folder(mut self, folder: bool) -> LibKmlLayerOptions {
+        self.folder = folder;
+        return self;
+    }
+
+    /// The displayed name of the folder or document.
+    pub fn name(mut self, name: &str) -> LibKmlLayerOptions {
+        self.name = Some(name.to_string());
+        return self;
+    }
+
+    pub fn description(mut self, description: &str) -> LibKmlLayerOptions {
+        self.description = Some(description.to_string());
+        return self;
+    }
+
+    pub fn options(&self) -> Vec<String> {
+        let mut options = Vec::new();
+        if self.folder {
+            options.push("FOLDER=YES".to_string());
+        }
+        if let Some(ref name) = self.name {
+            options.push(format!("NAME={}", name));
+        }
+        if let Some(ref description) = self.description {
+            options.push(format!("DESCRIPTION={}", description));
+        }
+        return options;
+    }
+}
+
+/// A line and fill style, turned into an OGR style string with
+/// `to_ogr_style` for `Layer::create_feature_styled`. Colors are
+/// `#RRGGBB` or `#RRGGBBAA`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Style {
+    line_color: Option<String>,
+    line_width: Option<f64>,
+    fill_color: Option<String>,
+}
+
+impl Style {
+    pub fn new() -> Style {
+        return Style::default();
+    }
+
+    pub fn line_color(mut self, color: &str) -> Style {
+        self.line_color = Some(color.to_string());
+        return self;
+    }
+
+    /// Line width in pixels.
+    pub fn line_width(mut self, width: f64) -> Style {
+        self.line_width = Some(width);
+        return self;
+    }
+
+    pub fn fill_color(mut self, color: &str) -> Style {
+        self.fill_color = Some(color.to_string());
+        return self;
+    }
+
+    pub fn to_ogr_style(&self) -> String {
+        let mut tools = Vec::new();
+        let mut pen = Vec::new();
+        if let Some(ref color) = self.line_color {
+            pen.push(format!("c:{}", color));
+        }
+        if let Some(width) = self.line_width {
+            pen.push(format!("w:{}px", width));
+        }
+        if !pen.is_empty() {
+            tools.push(format!("PEN({})", pen.join(",")));
+        }
+        if let Some(ref color) = self.fill_color {
+            tools.push(format!("BRUSH(fc:{})", color));
+        }
+        return tools.join(";");
+    }
+}
+
+/// Create a KML file at `path` with `driver`, either `"KML"` or
+/// `"LIBKML"`.
+pub fn create_kml(path: &Path, driver: &str, options: &KmlOptions) -> Option<Dataset> {
+    return match Driver::get(driver) {
+        Some(driver) => driver.create_with_options(path, &options.options()),
+        None => None,
+    };
+}
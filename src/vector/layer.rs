@@ -1,7 +1,10 @@
 use std::ptr::null;
-use libc::{c_void};
-use vector::{ogr, Feature, Geometry};
+use libc::{c_float, c_int, c_void};
+use vector::{ogr, Envelope, Feature, FieldValue, Geometry, GeometryType};
 use vector::defn::Defn;
+use spatial_ref::SpatialRef;
+use utils::{_cstring, _string};
+use GdalError;
 
 /// Layer in a vector dataset
 ///
@@ -28,11 +31,28 @@ impl Layer {
         return Layer{c_layer: c_layer, defn: defn};
     }
 
+    pub unsafe fn _c_layer(&self) -> *const c_void {
+        return self.c_layer;
+    }
+
+    pub fn name(&self) -> String {
+        let rv = unsafe { ogr::OGR_L_GetName(self.c_layer) };
+        return _string(rv);
+    }
+
     /// Iterate over all features in this layer.
     pub fn features<'a>(&'a self) -> FeatureIterator<'a> {
         return FeatureIterator::_with_layer(&self);
     }
 
+    /// Iterate over the features of this layer with a valid geometry,
+    /// handling the others according to `policy`. Features without a
+    /// geometry are passed through. The iterator counts what it did, see
+    /// `ValidFeatureIterator::repaired` and `skipped`.
+    pub fn valid_features<'a>(&'a self, policy: ValidityPolicy) -> ValidFeatureIterator<'a> {
+        return ValidFeatureIterator{features: self.features(), policy: policy, repaired: 0, skipped: 0};
+    }
+
     pub fn set_spatial_filter(&self, geometry: &Geometry) {
         unsafe { ogr::OGR_L_SetSpatialFilter(self.c_layer, geometry.c_geometry()) };
     }
@@ -41,17 +61,401 @@ impl Layer {
         unsafe { ogr::OGR_L_SetSpatialFilter(self.c_layer, null()) };
     }
 
+    /// Only iterate over the features matching `query`, an SQL `WHERE`
+    /// clause like `highway = 'primary' AND lanes > 2`. Drivers like
+    /// GeoPackage and PostgreSQL evaluate it in the database, which is much
+    /// faster than filtering the features afterwards. Fails for invalid
+    /// queries, e.g. naming a missing field.
+    pub fn set_attribute_filter(&self, query: &str) -> Result<(), GdalError> {
+        let c_query = _cstring(query)?;
+        let rv = unsafe { ogr::OGR_L_SetAttributeFilter(self.c_layer, c_query.as_ptr()) };
+        return match rv {
+            ogr::OGRERR_NONE => Ok(()),
+            _ => Err(GdalError::CallFailed{desc: "OGR_L_SetAttributeFilter failed"}),
+        };
+    }
+
+    pub fn clear_attribute_filter(&self) {
+        unsafe { ogr::OGR_L_SetAttributeFilter(self.c_layer, null()) };
+    }
+
+    /// The spatial reference of the layer, if it has one.
+    pub fn spatial_ref(&self) -> Option<SpatialRef> {
+        let c_srs = unsafe { ogr::OGR_L_GetSpatialRef(self.c_layer) };
+        return match c_srs.is_null() {
+            true  => None,
+            // the layer keeps ownership of its spatial reference
+            false => Some(unsafe { SpatialRef::_clone_from_c_srs(c_srs) }),
+        };
+    }
+
+    /// The type of the geometries of this layer, as declared by its
+    /// definition. `GeometryType::Unknown` for layers of mixed geometries.
+    pub fn geometry_type(&self) -> GeometryType {
+        return GeometryType::from_wkb(self.wkb_type());
+    }
+
+    /// The raw OGR geometry type code of this layer, including the Z and M
+    /// dimensions, e.g. to create an output layer with the same schema.
+    pub fn wkb_type(&self) -> c_int {
+        return unsafe { ogr::OGR_L_GetGeomType(self.c_layer) };
+    }
+
+    /// Whether the geometries of this layer have Z coordinates.
+    pub fn has_z(&self) -> bool {
+        return GeometryType::has_z(self.wkb_type());
+    }
+
+    /// Whether the geometries of this layer have M coordinates.
+    pub fn has_m(&self) -> bool {
+        return GeometryType::has_m(self.wkb_type());
+    }
+
+    /// The bounding box of all features of the layer, ignoring the spatial
+    /// filter. Unless `force` is set, `None` if the driver can't tell it
+    /// without reading every feature. Also `None` for empty layers.
+    pub fn extent(&self, force: bool) -> Option<Envelope> {
+        let mut envelope = ogr::OGREnvelope::default();
+        let rv = unsafe { ogr::OGR_L_GetExtent(self.c_layer, &mut envelope, force as c_int) };
+        return match rv {
+            ogr::OGRERR_NONE => Some(Envelope::new(envelope.min_x, envelope.max_x, envelope.min_y, envelope.max_y)),
+            _ => None,
+        };
+    }
+
     pub fn defn(&self) -> &Defn {
         &self.defn
     }
 
-    pub fn create_feature(&mut self, geometry: Geometry) {
+    pub fn create_feature(&mut self, geometry: Geometry) -> Result<(), GdalError> {
+        return self.create_feature_fields(geometry, &[], &[]);
+    }
+
+    /// Add fields to the layer schema. Each field is a name plus one of the
+    /// `OFT_*` field types.
+    pub fn create_defn_fields(&mut self, fields_def: &[(&str, c_int)]) -> Result<(), GdalError> {
+        for &(name, field_type) in fields_def {
+            self.create_field(name, field_type, 0, 0)?;
+        }
+        return Ok(());
+    }
+
+    /// Add a field of `field_type` with a maximum `width` and `precision`,
+    /// e.g. the size of a shapefile column. Pass 0 for the driver default.
+    pub fn create_field(&mut self, name: &str, field_type: c_int, width: usize, precision: usize) -> Result<(), GdalError> {
+        let c_name = _cstring(name)?;
+        let c_field_defn = unsafe { ogr::OGR_Fld_Create(c_name.as_ptr(), field_type) };
+        unsafe { ogr::OGR_Fld_SetWidth(c_field_defn, width as c_int) };
+        unsafe { ogr::OGR_Fld_SetPrecision(c_field_defn, precision as c_int) };
+        let rv = unsafe { ogr::OGR_L_CreateField(self.c_layer, c_field_defn, 1) };
+        unsafe { ogr::OGR_Fld_Destroy(c_field_defn) };
+        return match rv {
+            ogr::OGRERR_NONE => Ok(()),
+            _ => Err(GdalError::CallFailed{desc: "OGR_L_CreateField failed"}),
+        };
+    }
+
+    /// Create a feature with a geometry and the given field values.
+    /// `field_names` and `values` are matched up by position. Fails if
+    /// their lengths differ or a field is missing from the layer.
+    pub fn create_feature_fields(
+        &mut self,
+        geometry: Geometry,
+        field_names: &[&str],
+        values: &[FieldValue],
+    ) -> Result<(), GdalError> {
+        if field_names.len() != values.len() {
+            return Err(GdalError::CallFailed{desc: "different numbers of field names and values"});
+        }
+        // convert everything up front, so nothing leaks on error
+        let mut c_names = Vec::with_capacity(field_names.len());
+        let mut c_values = Vec::with_capacity(values.len());
+        for (name, value) in field_names.iter().zip(values.iter()) {
+            c_names.push(_cstring(name)?);
+            c_values.push(match *value {
+                FieldValue::StringValue(ref value) => Some(_cstring(value)?),
+                _ => None,
+            });
+        }
+        let c_feature = unsafe { ogr::OGR_F_Create(self.defn.c_defn()) };
+        let mut field_ids = Vec::with_capacity(c_names.len());
+        for c_name in &c_names {
+            let field_id = unsafe { ogr::OGR_F_GetFieldIndex(c_feature, c_name.as_ptr()) };
+            if field_id == -1 {
+                unsafe { ogr::OGR_F_Destroy(c_feature) };
+                return Err(GdalError::CallFailed{desc: "no such field"});
+            }
+            field_ids.push(field_id);
+        }
+        let c_geometry = unsafe { geometry.into_c_geometry() };
+        let rv = unsafe { ogr::OGR_F_SetGeometryDirectly(c_feature, c_geometry) };
+        if rv != ogr::OGRERR_NONE {
+            unsafe { ogr::OGR_F_Destroy(c_feature) };
+            return Err(GdalError::CallFailed{desc: "OGR_F_SetGeometryDirectly failed"});
+        }
+        for (&field_id, (value, c_value)) in field_ids.iter().zip(values.iter().zip(c_values.iter())) {
+            match *value {
+                FieldValue::StringValue(_) => {
+                    let c_value = c_value.as_ref().unwrap();
+                    unsafe { ogr::OGR_F_SetFieldString(c_feature, field_id, c_value.as_ptr()) };
+                },
+                FieldValue::IntegerValue(value) => {
+                    unsafe { ogr::OGR_F_SetFieldInteger(c_feature, field_id, value) };
+                },
+                FieldValue::RealValue(value) => {
+                    unsafe { ogr::OGR_F_SetFieldDouble(c_feature, field_id, value) };
+                },
+                FieldValue::DateTimeValue(value) => {
+                    unsafe { ogr::OGR_F_SetFieldDateTimeEx(
+                        c_feature, field_id,
+                        value.year as c_int, value.month as c_int, value.day as c_int,
+                        value.hour as c_int, value.minute as c_int, value.second as c_float,
+                        value.time_zone.to_tz_flag(),
+                    ) };
+                },
+            }
+        }
+        return self._write_feature(c_feature);
+    }
+
+    /// Like `create_feature_fields`, checking every value against the
+    /// width and type of its field first. Strings longer than the field
+    /// width and non integral (or out of range) reals written to integer
+    /// fields are handled according to `policy`; the values that had to be
+    /// adjusted are returned. Widths are compared in bytes of UTF-8.
+    ///
+    /// Missing fields and, with `CoercionPolicy::Error`, values that don't
+    /// fit are reported before anything is changed. With
+    /// `CoercionPolicy::Widen` the fields stay widened if altering a later
+    /// field or writing the feature fails.
+    pub fn create_feature_coerced(
+        &mut self,
+        geometry: Geometry,
+        field_names: &[&str],
+        values: &[FieldValue],
+        policy: CoercionPolicy,
+    ) -> Result<Vec<Coercion>, GdalError> {
+        if field_names.len() != values.len() {
+            return Err(GdalError::CallFailed{desc: "different numbers of field names and values"});
+        }
+        let mut checked = Vec::with_capacity(values.len());
+        for (name, value) in field_names.iter().zip(values.iter()) {
+            let (field_id, field_type, width) = match self.defn.fields().enumerate().find(|(_, field)| field.name() == *name) {
+                Some((field_id, field)) => (field_id as c_int, field.field_type(), field.width()),
+                None => return Err(GdalError::CallFailed{desc: "no such field"}),
+            };
+            let fits = match *value {
+                FieldValue::StringValue(ref value) => field_type != ogr::OFT_STRING || width == 0 || value.len() <= width,
+                FieldValue::RealValue(value) => {
+                    field_type != ogr::OFT_INTEGER ||
+                        (value.fract() == 0. && value >= i32::MIN as f64 && value <= i32::MAX as f64)
+                },
+                FieldValue::IntegerValue(_) | FieldValue::DateTimeValue(_) => true,
+            };
+            if !fits && policy == CoercionPolicy::Error {
+                return Err(GdalError::FieldOverflow{field: name.to_string()});
+            }
+            checked.push((field_id, field_type, width, fits));
+        }
+
+        let mut coercions = vec!();
+        let mut coerced = Vec::with_capacity(values.len());
+        for (name, (value, &(field_id, field_type, width, fits))) in field_names.iter().zip(values.iter().zip(checked.iter())) {
+            if fits {
+                coerced.push(value.clone());
+                continue;
+            }
+            let field = name.to_string();
+            match (policy, value) {
+                (CoercionPolicy::Error, _) => unreachable!(),
+                (CoercionPolicy::Truncate, FieldValue::StringValue(value)) => {
+                    let mut end = width;
+                    while !value.is_char_boundary(end) {
+                        end -= 1;
+                    }
+                    coerced.push(FieldValue::StringValue(value[..end].to_string()));
+                    coercions.push(Coercion::Truncated{field: field, width: width});
+                },
+                (CoercionPolicy::Truncate, &FieldValue::RealValue(value)) => {
+                    let written = value as i32;
+                    coerced.push(FieldValue::IntegerValue(written));
+                    coercions.push(Coercion::RealToInteger{field: field, value: value, written: written});
+                },
+                (CoercionPolicy::Widen, FieldValue::StringValue(value)) => {
+                    self.alter_field(field_id, name, field_type, value.len(), 0, ogr::ALTER_WIDTH_PRECISION_FLAG)?;
+                    coerced.push(FieldValue::StringValue(value.clone()));
+                    coercions.push(Coercion::Widened{field: field, width: value.len()});
+                },
+                (CoercionPolicy::Widen, &FieldValue::RealValue(value)) => {
+                    // wide enough for any double, as written by the shapefile driver
+                    self.alter_field(field_id, name, ogr::OFT_REAL, 24, 15, ogr::ALTER_TYPE_FLAG | ogr::ALTER_WIDTH_PRECISION_FLAG)?;
+                    coerced.push(FieldValue::RealValue(value));
+                    coercions.push(Coercion::ToReal{field: field});
+                },
+                (_, &FieldValue::IntegerValue(_)) | (_, &FieldValue::DateTimeValue(_)) => unreachable!(),
+            }
+        }
+        self.create_feature_fields(geometry, field_names, &coerced)?;
+        return Ok(coercions);
+    }
+
+    fn alter_field(
+        &mut self,
+        field_id: c_int,
+        name: &str,
+        field_type: c_int,
+        width: usize,
+        precision: usize,
+        flags: c_int,
+    ) -> Result<(), GdalError> {
+        let c_name = _cstring(name)?;
+        let c_field_defn = unsafe { ogr::OGR_Fld_Create(c_name.as_ptr(), field_type) };
+        unsafe { ogr::OGR_Fld_SetWidth(c_field_defn, width as c_int) };
+        unsafe { ogr::OGR_Fld_SetPrecision(c_field_defn, precision as c_int) };
+        let rv = unsafe { ogr::OGR_L_AlterFieldDefn(self.c_layer, field_id, c_field_defn, flags) };
+        unsafe { ogr::OGR_Fld_Destroy(c_field_defn) };
+        return match rv {
+            ogr::OGRERR_NONE => Ok(()),
+            _ => Err(GdalError::CallFailed{desc: "OGR_L_AlterFieldDefn failed"}),
+        };
+    }
+
+    /// Create a feature with a geometry and an
+    /// [OGR style string](http://www.gdal.org/ogr_feature_style.html), which
+    /// drivers like KML and LIBKML turn into styles.
+    pub fn create_feature_styled(&mut self, geometry: Geometry, style: &str) -> Result<(), GdalError> {
+        let c_style = _cstring(style)?;
         let c_feature = unsafe { ogr::OGR_F_Create(self.defn.c_defn()) };
         let c_geometry = unsafe { geometry.into_c_geometry() };
         let rv = unsafe { ogr::OGR_F_SetGeometryDirectly(c_feature, c_geometry) };
-        assert_eq!(rv, ogr::OGRERR_NONE);
+        if rv != ogr::OGRERR_NONE {
+            unsafe { ogr::OGR_F_Destroy(c_feature) };
+            return Err(GdalError::CallFailed{desc: "OGR_F_SetGeometryDirectly failed"});
+        }
+        unsafe { ogr::OGR_F_SetStyleString(c_feature, c_style.as_ptr()) };
+        return self._write_feature(c_feature);
+    }
+
+    /// Write `c_feature` to the layer and destroy it.
+    fn _write_feature(&mut self, c_feature: *const c_void) -> Result<(), GdalError> {
         let rv = unsafe { ogr::OGR_L_CreateFeature(self.c_layer, c_feature) };
-        assert_eq!(rv, ogr::OGRERR_NONE);
+        unsafe { ogr::OGR_F_Destroy(c_feature) };
+        return match rv {
+            ogr::OGRERR_NONE => Ok(()),
+            _ => Err(GdalError::CallFailed{desc: "OGR_L_CreateFeature failed"}),
+        };
+    }
+
+    /// Write a copy of `feature`, which may come from another layer or
+    /// dataset, to this layer. Fields are matched by name, fields missing
+    /// from this layer's schema are dropped.
+    pub fn create_feature_from(&mut self, feature: &Feature) -> Result<(), GdalError> {
+        let c_feature = unsafe { ogr::OGR_F_Create(self.defn.c_defn()) };
+        let rv = unsafe { ogr::OGR_F_SetFrom(c_feature, feature._c_ptr(), 1) };
+        if rv != ogr::OGRERR_NONE {
+            unsafe { ogr::OGR_F_Destroy(c_feature) };
+            return Err(GdalError::CallFailed{desc: "OGR_F_SetFrom failed"});
+        }
+        return self._write_feature(c_feature);
+    }
+}
+
+/// What `Layer::create_feature_coerced` does with a value that doesn't fit
+/// its field.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CoercionPolicy {
+    /// Cut strings to the field width and cast reals to integers, like
+    /// most drivers silently do.
+    Truncate,
+    /// Fail with `GdalError::FieldOverflow`, without writing the feature.
+    Error,
+    /// Alter the field so the value fits: widen string fields, turn
+    /// integer fields into real ones. Only some drivers, like the
+    /// shapefile one, can alter fields.
+    Widen,
+}
+
+/// A value adjusted by `Layer::create_feature_coerced`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Coercion {
+    /// The string was cut to the `width` of `field`.
+    Truncated{field: String, width: usize},
+    /// The real `value` was written to integer field `field` as `written`.
+    RealToInteger{field: String, value: f64, written: i32},
+    /// `field` was widened to `width`.
+    Widened{field: String, width: usize},
+    /// The integer field `field` became a real field.
+    ToReal{field: String},
+}
+
+/// What `Layer::valid_features` does with a feature whose geometry is
+/// invalid.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ValidityPolicy {
+    /// Leave the feature out.
+    Skip,
+    /// Repair polygons and multi polygons with a zero width buffer, which
+    /// untangles self intersecting rings but drops the smaller lobe of a
+    /// bow tie (`OGR_G_MakeValid` needs GDAL 3.0). Features that are still
+    /// invalid or became empty, and invalid geometries of other types, are
+    /// left out.
+    Repair,
+}
+
+/// Iterator returned by `Layer::valid_features`.
+pub struct ValidFeatureIterator<'a> {
+    features: FeatureIterator<'a>,
+    policy: ValidityPolicy,
+    repaired: usize,
+    skipped: usize,
+}
+
+impl<'a> ValidFeatureIterator<'a> {
+    /// Number of features yielded with a repaired geometry so far.
+    pub fn repaired(&self) -> usize {
+        return self.repaired;
+    }
+
+    /// Number of features left out so far.
+    pub fn skipped(&self) -> usize {
+        return self.skipped;
+    }
+
+    fn repair(&self, geometry: &Geometry) -> Option<Geometry> {
+        if self.policy != ValidityPolicy::Repair {
+            return None;
+        }
+        match geometry.geometry_type() {
+            GeometryType::Polygon | GeometryType::MultiPolygon => (),
+            _ => return None,
+        }
+        let repaired = geometry.buffer(0., 0)?;
+        return match repaired.is_valid() && !repaired.is_empty() {
+            true => Some(repaired),
+            false => None,
+        };
+    }
+}
+
+impl<'a> Iterator for ValidFeatureIterator<'a> {
+    type Item = Feature<'a>;
+
+    fn next(&mut self) -> Option<Feature<'a>> {
+        while let Some(mut feature) = self.features.next() {
+            let repaired = match feature.geometry() {
+                None => return Some(feature),
+                Some(ref geometry) if geometry.is_valid() => return Some(feature),
+                Some(ref geometry) => self.repair(geometry),
+            };
+            if let Some(geometry) = repaired {
+                if feature.set_geometry(geometry).is_ok() {
+                    self.repaired += 1;
+                    return Some(feature);
+                }
+            }
+            self.skipped += 1;
+        }
+        return None;
     }
 }
 
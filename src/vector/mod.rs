@@ -9,19 +9,28 @@
 //! let mut dataset = Dataset::open(Path::new("fixtures/roads.geojson")).unwrap();
 //! let layer = dataset.layer(0).unwrap();
 //! for feature in layer.features() {
-//!     let highway_field = feature.field("highway").unwrap();
-//!     let geometry = feature.geometry();
+//!     let highway_field = feature.field("highway").unwrap().unwrap();
+//!     let geometry = feature.geometry().unwrap();
 //!     println!("{} {}", highway_field.as_string(), geometry.wkt());
 //! }
 //! ```
 
 
 pub use vector::driver::Driver;
-pub use vector::dataset::Dataset;
-pub use vector::layer::{Layer, FeatureIterator};
+pub use vector::dataset::{Dataset, SqlResultLayer};
+pub use vector::layer::{Layer, FeatureIterator, CoercionPolicy, Coercion, ValidityPolicy, ValidFeatureIterator};
 pub use vector::defn::{Defn, FieldIterator, Field};
-pub use vector::feature::{Feature, FieldValue};
-pub use vector::geometry::Geometry;
+pub use vector::feature::{Feature, FieldValue, FieldValueIterator, DateTime, TimeZone};
+pub use vector::envelope::{Envelope, Envelope3D, TileRange, TileIterator, MAX_TILE_ZOOM};
+pub use vector::grid::{fishnet, grid, hex_grid};
+pub use vector::convert::{convert_features, ConversionReport, FeatureError, OnError};
+pub use vector::shard::{ShardedWriter, Shard};
+pub use vector::geometry::{Geometry, GeometryRef, Flatten, SubGeometries, GmlFormat};
+pub use vector::geometry_type::GeometryType;
+pub use vector::sql::SqlDialect;
+pub use vector::ogr::{OFT_INTEGER, OFT_REAL, OFT_STRING, OFT_BINARY, OFT_DATE, OFT_TIME, OFT_DATETIME};
+pub use vector::ogr::{WKB_UNKNOWN, WKB_POINT, WKB_LINESTRING, WKB_POLYGON, WKB_MULTIPOINT,
+                      WKB_MULTILINESTRING, WKB_MULTIPOLYGON, WKB_GEOMETRYCOLLECTION, WKB_NONE};
 
 /// Convert object to a GDAL geometry.
 pub trait ToGdal {
@@ -35,8 +44,25 @@ mod layer;
 mod defn;
 mod feature;
 mod geometry;
+mod geometry_type;
+mod envelope;
+mod grid;
+mod convert;
+mod shard;
 mod gdal_to_geo;
 mod geo_to_gdal;
+pub mod csv;
+pub mod geojson;
+pub mod gpkg;
+pub mod gpx;
+pub mod kml;
+pub mod osm;
+pub mod sql;
+pub mod xlsx;
+#[cfg(feature = "testing")]
+pub mod testing;
+#[cfg(feature = "arrow")]
+pub mod arrow;
 
 #[cfg(test)]
 mod tests;
@@ -1,28 +1,95 @@
-use libc::{c_int, c_char, c_double, c_void};
+use libc::{c_int, c_uint, c_char, c_double, c_float, c_void};
+
+#[repr(C)]
+#[derive(Default)]
+pub struct OGREnvelope {
+    pub min_x: c_double,
+    pub max_x: c_double,
+    pub min_y: c_double,
+    pub max_y: c_double,
+}
+
+#[repr(C)]
+#[derive(Default)]
+pub struct OGREnvelope3D {
+    pub min_x: c_double,
+    pub max_x: c_double,
+    pub min_y: c_double,
+    pub max_y: c_double,
+    pub min_z: c_double,
+    pub max_z: c_double,
+}
 
 #[link(name="gdal")]
 extern {
     pub fn OGRRegisterAll();
     pub fn OGRGetDriverByName(pszName: *const c_char) -> *const c_void;
-    pub fn OGR_Dr_CreateDataSource(hDriver: *const c_void, pszName: *const c_char, papszOptions: *const c_void) -> *const c_void;
+    pub fn OGR_Dr_CreateDataSource(hDriver: *const c_void, pszName: *const c_char, papszOptions: *const *const c_char) -> *const c_void;
     pub fn OGROpen(pszName: *const c_char, bUpdate: c_int, pahDriverList: *const c_void) -> *const c_void;
+    pub fn GDALOpenEx(
+        pszFilename: *const c_char,
+        nOpenFlags: c_uint,
+        papszAllowedDrivers: *const *const c_char,
+        papszOpenOptions: *const *const c_char,
+        papszSiblingFiles: *const *const c_char
+    ) -> *const c_void;
+    pub fn OGR_DS_GetDriver(hDS: *const c_void) -> *const c_void;
+    pub fn OGR_Dr_GetName(hDriver: *const c_void) -> *const c_char;
+    pub fn GDALGetDriverLongName(hDriver: *const c_void) -> *const c_char;
+    pub fn OGR_DS_ExecuteSQL(hDS: *const c_void, pszSQLCommand: *const c_char, hSpatialFilter: *const c_void, pszDialect: *const c_char) -> *const c_void;
+    pub fn OGR_DS_ReleaseResultSet(hDS: *const c_void, hLayer: *const c_void);
+    pub fn CPLErrorReset();
+    pub fn CPLGetLastErrorType() -> c_int;
+    pub fn CPLGetLastErrorMsg() -> *const c_char;
     pub fn OGR_DS_GetLayerCount(hDS: *const c_void) -> c_int;
+    pub fn GDALGetAccess(hDS: *const c_void) -> c_int;
     pub fn OGR_DS_Destroy(hDataSource: *const c_void);
     pub fn OGR_DS_GetLayer(hDS: *const c_void, iLayer: c_int) -> *const c_void;
-    pub fn OGR_DS_CreateLayer(hDS: *const c_void, pszName: *const c_char, hSpatialRef: *const c_char, eType: c_int, papszOptions: *const c_void) -> *const c_void;
+    pub fn OGR_DS_CreateLayer(hDS: *const c_void, pszName: *const c_char, hSpatialRef: *const c_void, eType: c_int, papszOptions: *const *const c_char) -> *const c_void;
+    pub fn OGR_DS_GetLayerByName(hDS: *const c_void, pszName: *const c_char) -> *const c_void;
+    pub fn OGR_L_GetName(hLayer: *const c_void) -> *const c_char;
+    pub fn OGR_L_GetSpatialRef(hLayer: *const c_void) -> *const c_void;
     pub fn OGR_L_GetLayerDefn(hLayer: *const c_void) -> *const c_void;
+    pub fn OGR_L_GetGeomType(hLayer: *const c_void) -> c_int;
+    pub fn OGR_L_GetExtent(hLayer: *const c_void, psExtent: *mut OGREnvelope, bForce: c_int) -> c_int;
     pub fn OGR_L_GetNextFeature(hLayer: *const c_void) -> *const c_void;
     pub fn OGR_L_SetSpatialFilter(hLayer: *const c_void, hGeom: *const c_void);
+    pub fn OGR_L_SetAttributeFilter(hLayer: *const c_void, pszQuery: *const c_char) -> c_int;
     pub fn OGR_L_CreateFeature(hLayer: *const c_void, hFeat: *const c_void) -> c_int;
+    pub fn OGR_L_CreateField(hLayer: *const c_void, hField: *const c_void, bApproxOK: c_int) -> c_int;
+    pub fn OGR_L_AlterFieldDefn(hLayer: *const c_void, iField: c_int, hNewFieldDefn: *const c_void, nFlags: c_int) -> c_int;
     pub fn OGR_FD_GetFieldCount(hDefn: *const c_void) -> c_int;
     pub fn OGR_FD_GetFieldDefn(hDefn: *const c_void, iField: c_int) -> *const c_void;
     pub fn OGR_F_Create(hDefn: *const c_void) -> *const c_void;
     pub fn OGR_F_GetFieldIndex(hFeat: *const c_void, pszName: *const c_char) -> c_int;
     pub fn OGR_F_GetFieldDefnRef(hFeat: *const c_void, i: c_int) -> *const c_void;
+    pub fn OGR_F_GetFieldCount(hFeat: *const c_void) -> c_int;
+    pub fn OGR_F_IsFieldSet(hFeat: *const c_void, iField: c_int) -> c_int;
+    pub fn OGR_F_GetFieldAsInteger(hFeat: *const c_void, iField: c_int) -> c_int;
     pub fn OGR_F_GetFieldAsString(hFeat: *const c_void, iField: c_int) -> *const c_char;
     pub fn OGR_F_GetFieldAsDouble(hFeat: *const c_void, iField: c_int) -> c_double;
+    pub fn OGR_F_SetFieldInteger(hFeat: *const c_void, iField: c_int, nValue: c_int);
+    pub fn OGR_F_SetFieldDouble(hFeat: *const c_void, iField: c_int, dfValue: c_double);
+    pub fn OGR_F_SetFieldString(hFeat: *const c_void, iField: c_int, pszValue: *const c_char);
+    pub fn OGR_F_GetFieldAsBinary(hFeat: *const c_void, iField: c_int, pnBytes: &mut c_int) -> *const u8;
+    pub fn OGR_F_SetFieldBinary(hFeat: *const c_void, iField: c_int, nBytes: c_int, pabyData: *const u8);
+    pub fn OGR_F_GetFieldAsDateTimeEx(
+        hFeat: *const c_void, iField: c_int,
+        pnYear: &mut c_int, pnMonth: &mut c_int, pnDay: &mut c_int,
+        pnHour: &mut c_int, pnMinute: &mut c_int, pfSecond: &mut c_float, pnTZFlag: &mut c_int,
+    ) -> c_int;
+    pub fn OGR_F_SetFieldDateTimeEx(
+        hFeat: *const c_void, iField: c_int,
+        nYear: c_int, nMonth: c_int, nDay: c_int,
+        nHour: c_int, nMinute: c_int, fSecond: c_float, nTZFlag: c_int,
+    );
     pub fn OGR_F_GetGeometryRef(hFeat: *const c_void) -> *const c_void;
     pub fn OGR_F_SetGeometryDirectly(hFeat: *const c_void, hGeom: *const c_void) -> c_int;
+    pub fn OGR_F_SetStyleString(hFeat: *const c_void, pszStyle: *const c_char);
+    pub fn OGR_F_GetStyleString(hFeat: *const c_void) -> *const c_char;
+    pub fn OGR_F_GetFID(hFeat: *const c_void) -> i64;
+    pub fn OGR_F_Clone(hFeat: *const c_void) -> *const c_void;
+    pub fn OGR_F_SetFrom(hFeat: *const c_void, hOtherFeat: *const c_void, bForgiving: c_int) -> c_int;
     pub fn OGR_F_Destroy(hFeat: *const c_void);
     pub fn OGR_G_CreateGeometry(eGeometryType: c_int) -> *const c_void;
     pub fn OGR_G_CreateFromWkt(ppszData: &mut *const c_char, hSRS: *const c_void, phGeometry: &mut *const c_void) -> c_int;
@@ -30,23 +97,122 @@ extern {
     pub fn OGR_G_GetPoint(hGeom: *const c_void, i: c_int, pdfX: &mut c_double, pdfY: &mut c_double, pdfZ: &mut c_double);
     pub fn OGR_G_GetPointCount(hGeom: *const c_void) -> c_int;
     pub fn OGR_G_SetPoint_2D(hGeom: *const c_void, i: c_int, dfX: c_double, dfY: c_double);
+    pub fn OGR_G_SetPoint(hGeom: *const c_void, i: c_int, dfX: c_double, dfY: c_double, dfZ: c_double);
+    pub fn OGR_G_AddPoint_2D(hGeom: *const c_void, dfX: c_double, dfY: c_double);
+    pub fn OGR_G_AddPoint(hGeom: *const c_void, dfX: c_double, dfY: c_double, dfZ: c_double);
+    pub fn OGR_G_GetPointZM(hGeom: *const c_void, i: c_int, pdfX: &mut c_double, pdfY: &mut c_double, pdfZ: &mut c_double, pdfM: &mut c_double);
+    pub fn OGR_G_SetPointM(hGeom: *const c_void, i: c_int, dfX: c_double, dfY: c_double, dfM: c_double);
+    pub fn OGR_G_SetPointZM(hGeom: *const c_void, i: c_int, dfX: c_double, dfY: c_double, dfZ: c_double, dfM: c_double);
+    pub fn OGR_G_AddPointM(hGeom: *const c_void, dfX: c_double, dfY: c_double, dfM: c_double);
+    pub fn OGR_G_AddPointZM(hGeom: *const c_void, dfX: c_double, dfY: c_double, dfZ: c_double, dfM: c_double);
+    pub fn OGR_G_IsMeasured(hGeom: *const c_void) -> c_int;
+    pub fn OGR_G_SetMeasured(hGeom: *const c_void, bIsMeasured: c_int);
+    pub fn OGR_G_Is3D(hGeom: *const c_void) -> c_int;
+    pub fn OGR_G_Set3D(hGeom: *const c_void, bIs3D: c_int);
+    pub fn OGR_G_CoordinateDimension(hGeom: *const c_void) -> c_int;
+    pub fn OGR_G_SwapXY(hGeom: *const c_void);
+    pub fn OGR_G_HasCurveGeometry(hGeom: *const c_void, bLookForNonLinear: c_int) -> c_int;
+    pub fn OGR_G_GetLinearGeometry(hGeom: *const c_void, dfMaxAngleStepSizeDegrees: c_double, papszOptions: *const *const c_char) -> *const c_void;
     pub fn OGR_G_ExportToWkt(hGeom: *const c_void, ppszSrcText: &mut *const c_char) -> c_int;
     pub fn OGR_G_ExportToJson(hGeometry: *const c_void) -> *const c_char;
+    pub fn OGR_G_ExportToGMLEx(hGeometry: *const c_void, papszOptions: *const *const c_char) -> *const c_char;
+    pub fn OGR_G_ExportToKML(hGeometry: *const c_void, pszAltitudeMode: *const c_char) -> *const c_char;
+    pub fn OGR_G_CreateFromGML(pszGML: *const c_char) -> *const c_void;
+    pub fn OGR_G_CreateGeometryFromJson(pszJson: *const c_char) -> *const c_void;
+    pub fn OGR_G_ExportToJsonEx(hGeometry: *const c_void, papszOptions: *const *const c_char) -> *const c_char;
+    pub fn OGR_G_Intersects(hGeom: *const c_void, hOtherGeom: *const c_void) -> c_int;
+    pub fn OGR_G_Equals(hGeom: *const c_void, hOther: *const c_void) -> c_int;
+    pub fn OGR_G_IsEmpty(hGeom: *const c_void) -> c_int;
+    pub fn OGR_G_IsRing(hGeom: *const c_void) -> c_int;
+    pub fn OGR_G_IsSimple(hGeom: *const c_void) -> c_int;
+    pub fn OGR_G_IsValid(hGeom: *const c_void) -> c_int;
+    pub fn OGR_G_Contains(hThis: *const c_void, hOther: *const c_void) -> c_int;
+    pub fn OGR_G_Within(hThis: *const c_void, hOther: *const c_void) -> c_int;
+    pub fn OGR_G_Touches(hThis: *const c_void, hOther: *const c_void) -> c_int;
+    pub fn OGR_G_Crosses(hThis: *const c_void, hOther: *const c_void) -> c_int;
+    pub fn OGR_G_Overlaps(hThis: *const c_void, hOther: *const c_void) -> c_int;
+    pub fn OGR_G_Disjoint(hThis: *const c_void, hOther: *const c_void) -> c_int;
+    pub fn OGR_G_Clone(hGeom: *const c_void) -> *const c_void;
     pub fn OGR_G_ConvexHull(hTarget: *const c_void) -> *const c_void;
+    pub fn OGR_G_Boundary(hTarget: *const c_void) -> *const c_void;
+    pub fn OGR_G_Polygonize(hTarget: *const c_void) -> *const c_void;
+    pub fn OGR_G_DelaunayTriangulation(hThis: *const c_void, dfTolerance: c_double, bOnlyEdges: c_int) -> *const c_void;
+    pub fn OGR_G_Transform(hGeom: *const c_void, hTransform: *const c_void) -> c_int;
+    pub fn OGR_G_TransformTo(hGeom: *const c_void, hSRS: *const c_void) -> c_int;
+    pub fn OGR_G_AssignSpatialReference(hGeom: *const c_void, hSRS: *const c_void);
+    pub fn OGR_G_GetSpatialReference(hGeom: *const c_void) -> *const c_void;
+    pub fn OGR_G_Area(hGeom: *const c_void) -> c_double;
+    pub fn OGR_G_Length(hGeom: *const c_void) -> c_double;
+    pub fn OGR_G_Distance(hFirst: *const c_void, hOther: *const c_void) -> c_double;
+    pub fn OGR_G_Distance3D(hFirst: *const c_void, hOther: *const c_void) -> c_double;
+    pub fn OGR_G_Simplify(hThis: *const c_void, tolerance: c_double) -> *const c_void;
+    pub fn OGR_G_Segmentize(hGeom: *const c_void, dfMaxLength: c_double);
+    pub fn OGR_G_CloseRings(hGeom: *const c_void);
+    pub fn OGR_G_FlattenTo2D(hGeom: *const c_void);
+    pub fn OGR_G_ForceToPolygon(hGeom: *const c_void) -> *const c_void;
+    pub fn OGR_G_ForceToLineString(hGeom: *const c_void) -> *const c_void;
+    pub fn OGR_G_ForceToMultiPolygon(hGeom: *const c_void) -> *const c_void;
+    pub fn OGR_G_ForceToMultiPoint(hGeom: *const c_void) -> *const c_void;
+    pub fn OGR_G_ForceToMultiLineString(hGeom: *const c_void) -> *const c_void;
+    pub fn OGR_G_SimplifyPreserveTopology(hThis: *const c_void, tolerance: c_double) -> *const c_void;
+    pub fn OGR_G_Buffer(hTarget: *const c_void, dfDist: c_double, nQuadSegs: c_int) -> *const c_void;
+    pub fn OGR_G_Intersection(hThis: *const c_void, hOther: *const c_void) -> *const c_void;
+    pub fn OGR_G_Union(hThis: *const c_void, hOther: *const c_void) -> *const c_void;
+    pub fn OGR_G_UnionCascaded(hThis: *const c_void) -> *const c_void;
+    pub fn OGR_G_Difference(hThis: *const c_void, hOther: *const c_void) -> *const c_void;
+    pub fn OGR_G_SymDifference(hThis: *const c_void, hOther: *const c_void) -> *const c_void;
+    pub fn OGR_G_Centroid(hGeom: *const c_void, hCentroidPoint: *const c_void) -> c_int;
+    pub fn OGR_G_PointOnSurface(hGeom: *const c_void) -> *const c_void;
+    pub fn OGR_G_GetEnvelope(hGeom: *const c_void, psEnvelope: *mut OGREnvelope);
+    pub fn OGR_G_GetEnvelope3D(hGeom: *const c_void, psEnvelope: *mut OGREnvelope3D);
     pub fn OGR_G_GetGeometryCount(hGeom: *const c_void) -> c_int;
     pub fn OGR_G_GetGeometryRef(hGeom: *const c_void, iSubGeom: c_int) -> *const c_void;
     pub fn OGR_G_AddGeometryDirectly(hGeom: *const c_void, hNewSubGeom: *const c_void) -> c_int;
     pub fn OGR_G_DestroyGeometry(hGeom: *mut c_void);
+    pub fn OGR_Fld_Create(pszName: *const c_char, eType: c_int) -> *const c_void;
+    pub fn OGR_Fld_Destroy(hDefn: *const c_void);
     pub fn OGR_Fld_GetNameRef(hDefn: *const c_void) -> *const c_char;
     pub fn OGR_Fld_GetType(hDefn: *const c_void) -> c_int;
+    pub fn OGR_Fld_GetWidth(hDefn: *const c_void) -> c_int;
+    pub fn OGR_Fld_SetWidth(hDefn: *const c_void, nNewWidth: c_int);
+    pub fn OGR_Fld_GetPrecision(hDefn: *const c_void) -> c_int;
+    pub fn OGR_Fld_SetPrecision(hDefn: *const c_void, nPrecision: c_int);
     pub fn OGRFree(ptr: *mut c_void);
     pub fn VSIFree(ptr: *mut c_void);
 }
 
+#[cfg(feature = "arrow")]
+extern {
+    pub fn OGR_L_CreateFieldFromArrowSchema(
+        hLayer: *const c_void,
+        schema: *const ::vector::arrow::ArrowSchema,
+        papszOptions: *const *const c_char
+    ) -> bool;
+    pub fn OGR_L_WriteArrowBatch(
+        hLayer: *const c_void,
+        schema: *const ::vector::arrow::ArrowSchema,
+        array: *mut ::vector::arrow::ArrowArray,
+        papszOptions: *const *const c_char
+    ) -> bool;
+}
+
+pub const GA_UPDATE:              c_int = 1;
+
+pub const CE_FAILURE:             c_int = 3;
+
 pub const OGRERR_NONE:            c_int = 0;
+pub const OGR_NULL_FID:           i64 = -1;
+
+pub const ALTER_TYPE_FLAG:            c_int = 0x2;
+pub const ALTER_WIDTH_PRECISION_FLAG: c_int = 0x4;
 
+pub const OFT_INTEGER:            c_int = 0;
 pub const OFT_REAL:               c_int = 2;
 pub const OFT_STRING:             c_int = 4;
+pub const OFT_BINARY:             c_int = 8;
+pub const OFT_DATE:               c_int = 9;
+pub const OFT_TIME:               c_int = 10;
+pub const OFT_DATETIME:           c_int = 11;
 
 pub const WKB_UNKNOWN:            c_int = 0;
 pub const WKB_POINT:              c_int = 1;
@@ -56,4 +222,5 @@ pub const WKB_MULTIPOINT:         c_int = 4;
 pub const WKB_MULTILINESTRING:    c_int = 5;
 pub const WKB_MULTIPOLYGON:       c_int = 6;
 pub const WKB_GEOMETRYCOLLECTION: c_int = 7;
+pub const WKB_NONE:               c_int = 100;
 pub const WKB_LINEARRING:         c_int = 101;
@@ -0,0 +1,128 @@
+//! OpenStreetMap reading
+//!
+//! The OSM driver reads `.osm` and `.osm.pbf` files in a single pass and
+//! sorts the features into the layers listed in `OsmLayer`. Reading the
+//! layers one after the other only works for small files: for large ones
+//! the driver needs interleaved reading, where every layer is drained in
+//! turn until all of them are exhausted. `read_interleaved` implements
+//! that pattern.
+//!
+//! ```no_run
+//! use std::path::Path;
+//! use gdal::vector::osm::{OsmOpenOptions, read_interleaved};
+//!
+//! let mut dataset = OsmOpenOptions::new()
+//!     .interleaved_reading(true)
+//!     .open(Path::new("city.osm.pbf"))
+//!     .unwrap();
+//! read_interleaved(&mut dataset, |layer, feature| {
+//!     println!("{}: {}", layer.name(), feature.geometry().unwrap().wkt());
+//! });
+//! ```
+
+use std::path::Path;
+use vector::{Dataset, Feature, Layer};
+
+/// The layers of an OSM dataset.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum OsmLayer {
+    /// Nodes with significant tags.
+    Points,
+    /// Ways that aren't areas.
+    Lines,
+    /// Route relations.
+    MultiLineStrings,
+    /// Closed ways that are areas, and multipolygon relations.
+    MultiPolygons,
+    /// Other relations.
+    OtherRelations,
+}
+
+impl OsmLayer {
+    /// The layer name used by the OSM driver.
+    pub fn name(&self) -> &'static str {
+        match *self {
+            OsmLayer::Points => "points",
+            OsmLayer::Lines => "lines",
+            OsmLayer::MultiLineStrings => "multilinestrings",
+            OsmLayer::MultiPolygons => "multipolygons",
+            OsmLayer::OtherRelations => "other_relations",
+        }
+    }
+}
+
+/// Builder for OSM open options, rendered with `options()` to the string
+/// list expected by `Dataset::open_with_options`, or used directly with
+/// `open`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct OsmOpenOptions {
+    interleaved_reading: bool,
+    config_file: Option<String>,
+    max_tmpfile_size: Option<usize>,
+}
+
+impl OsmOpenOptions {
+    pub fn new() -> OsmOpenOptions {
+        return OsmOpenOptions::default();
+    }
+
+    /// Read all layers in a single pass over the file, see
+    /// `read_interleaved`. Required for files with more features than fit
+    /// in the driver's buffers.
+    pub fn interleaved_reading(mut self, interleaved: bool) -> OsmOpenOptions {
+        self.interleaved_reading = interleaved;
+        return self;
+    }
+
+    /// An `osmconf.ini` file choosing which tags become fields.
+    pub fn config_file(mut self, path: &str) -> OsmOpenOptions {
+        self.config_file = Some(path.to_string());
+        return self;
+    }
+
+    /// Size in MB of the in-memory node index before it's moved to a
+    /// temporary file.
+    pub fn max_tmpfile_size(mut self, megabytes: usize) -> OsmOpenOptions {
+        self.max_tmpfile_size = Some(megabytes);
+        return self;
+    }
+
+    pub fn options(&self) -> Vec<String> {
+        let mut options = Vec::new();
+        if self.interleaved_reading {
+            options.push("INTERLEAVED_READING=YES".to_string());
+        }
+        if let Some(ref path) = self.config_file {
+            options.push(format!("CONFIG_FILE={}", path));
+        }
+        if let Some(size) = self.max_tmpfile_size {
+            options.push(format!("MAX_TMPFILE_SIZE={}", size));
+        }
+        return options;
+    }
+
+    /// Open the OSM file at `path` with these options.
+    pub fn open(&self, path: &Path) -> Option<Dataset> {
+        return Dataset::open_with_options(path, &["OSM".to_string()], &self.options());
+    }
+}
+
+/// Read every feature of an OSM `dataset` opened with interleaved reading,
+/// calling `f` with each feature and the layer it belongs to. Layers are
+/// drained in turn, over and over, until a whole round yields nothing;
+/// features of a layer therefore don't arrive in one block.
+pub fn read_interleaved<F>(dataset: &mut Dataset, mut f: F) where F: FnMut(&Layer, Feature) {
+    let layers = dataset.layers();
+    loop {
+        let mut has_features = false;
+        for layer in layers {
+            for feature in layer.features() {
+                has_features = true;
+                f(layer, feature);
+            }
+        }
+        if !has_features {
+            break;
+        }
+    }
+}
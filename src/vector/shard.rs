@@ -0,0 +1,193 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use libc::c_int;
+use spatial_ref::SpatialRef;
+use vector::{Dataset, Driver, Feature, Layer, WKB_UNKNOWN};
+use GdalError;
+
+/// A file written by `ShardedWriter`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Shard {
+    pub path: PathBuf,
+    /// Number of features written to the file.
+    pub features: usize,
+}
+
+/// Writes features to a series of files of bounded feature count and/or
+/// size, e.g. GeoJSON parts for a consumer that only accepts small
+/// uploads. Every shard gets its own dataset and a layer with the same
+/// schema, named `<name>_<index>.<extension>` in `directory`.
+///
+/// ```no_run
+/// use std::path::Path;
+/// use gdal::vector::{Dataset, ShardedWriter};
+///
+/// let mut dataset = Dataset::open(Path::new("roads.gpkg")).unwrap();
+/// let layer = dataset.layer(0).unwrap();
+/// let mut writer = ShardedWriter::new("GeoJSON", Path::new("/tmp/parts"), "roads", "geojson").unwrap()
+///     .max_features(10000)
+///     .schema_from(layer);
+/// writer.write_layer(layer).unwrap();
+/// for shard in writer.finish() {
+///     println!("{}: {} features", shard.path.display(), shard.features);
+/// }
+/// ```
+pub struct ShardedWriter {
+    driver: Driver,
+    directory: PathBuf,
+    name: String,
+    extension: String,
+    max_features: Option<usize>,
+    max_bytes: Option<u64>,
+    dataset_options: Vec<String>,
+    layer_options: Vec<String>,
+    srs: Option<SpatialRef>,
+    geometry_type: c_int,
+    fields: Vec<(String, c_int, usize, usize)>,
+    /// The open shard, the layer being a handle into the dataset.
+    current: Option<(Layer, Dataset)>,
+    shards: Vec<Shard>,
+}
+
+impl ShardedWriter {
+    /// Prepare to write shards with the driver called `driver_name`. No
+    /// file is created before the first feature is written.
+    pub fn new(driver_name: &str, directory: &Path, name: &str, extension: &str) -> Result<ShardedWriter, GdalError> {
+        let driver = match Driver::get(driver_name) {
+            Some(driver) => driver,
+            None => return Err(GdalError::CallFailed{desc: "driver not available"}),
+        };
+        return Ok(ShardedWriter{
+            driver: driver,
+            directory: directory.to_path_buf(),
+            name: name.to_string(),
+            extension: extension.to_string(),
+            max_features: None,
+            max_bytes: None,
+            dataset_options: vec!(),
+            layer_options: vec!(),
+            srs: None,
+            geometry_type: WKB_UNKNOWN,
+            fields: vec!(),
+            current: None,
+            shards: vec!(),
+        });
+    }
+
+    /// Start a new shard once the current one holds `max_features`.
+    pub fn max_features(mut self, max_features: usize) -> ShardedWriter {
+        assert!(max_features > 0);
+        self.max_features = Some(max_features);
+        return self;
+    }
+
+    /// Start a new shard once the current file reaches `max_bytes`. The
+    /// size is checked on disk before each feature, so a shard can exceed
+    /// the limit by the last feature plus what the driver hasn't flushed
+    /// yet; pick a limit with some headroom.
+    pub fn max_bytes(mut self, max_bytes: u64) -> ShardedWriter {
+        self.max_bytes = Some(max_bytes);
+        return self;
+    }
+
+    /// Driver specific dataset creation options, as `NAME=VALUE` strings.
+    pub fn dataset_options(mut self, options: &[String]) -> ShardedWriter {
+        self.dataset_options = options.to_vec();
+        return self;
+    }
+
+    /// Driver specific layer creation options, as `NAME=VALUE` strings,
+    /// e.g. from `GeoJsonLayerOptions::options`.
+    pub fn layer_options(mut self, options: &[String]) -> ShardedWriter {
+        self.layer_options = options.to_vec();
+        return self;
+    }
+
+    /// Give the shards the fields, spatial reference and geometry type of
+    /// `layer`.
+    pub fn schema_from(mut self, layer: &Layer) -> ShardedWriter {
+        self.fields = layer.defn().fields()
+            .map(|field| (field.name(), field.field_type(), field.width(), field.precision()))
+            .collect();
+        self.srs = layer.spatial_ref();
+        self.geometry_type = layer.wkb_type();
+        return self;
+    }
+
+    /// Write a copy of `feature` to the current shard, starting a new one
+    /// first if the current one is full. Fields are matched by name, like
+    /// `Layer::create_feature_from`.
+    pub fn write(&mut self, feature: &Feature) -> Result<(), GdalError> {
+        if self.is_full() {
+            self.current = None;
+        }
+        if self.current.is_none() {
+            self.open_shard()?;
+        }
+        if let Some((ref mut layer, _)) = self.current {
+            layer.create_feature_from(feature)?;
+        }
+        self.shards.last_mut().unwrap().features += 1;
+        return Ok(());
+    }
+
+    /// Write the features of `layer` from its current position, returning
+    /// how many were written.
+    pub fn write_layer(&mut self, layer: &Layer) -> Result<usize, GdalError> {
+        let mut count = 0;
+        for feature in layer.features() {
+            self.write(&feature)?;
+            count += 1;
+        }
+        return Ok(count);
+    }
+
+    /// The shards written so far, the last one possibly still open.
+    pub fn shards(&self) -> &[Shard] {
+        return &self.shards;
+    }
+
+    /// Close the last shard and return all of them.
+    pub fn finish(mut self) -> Vec<Shard> {
+        self.current = None;
+        return self.shards;
+    }
+
+    fn is_full(&self) -> bool {
+        let shard = match (&self.current, self.shards.last()) {
+            (&Some(_), Some(shard)) => shard,
+            _ => return false,
+        };
+        if let Some(max_features) = self.max_features {
+            if shard.features >= max_features {
+                return true;
+            }
+        }
+        if let Some(max_bytes) = self.max_bytes {
+            if fs::metadata(&shard.path).map(|m| m.len()).unwrap_or(0) >= max_bytes {
+                return true;
+            }
+        }
+        return false;
+    }
+
+    fn open_shard(&mut self) -> Result<(), GdalError> {
+        let file_name = format!("{}_{}.{}", self.name, self.shards.len(), self.extension);
+        let path = self.directory.join(file_name);
+        let mut dataset = match self.driver.create_with_options(&path, &self.dataset_options) {
+            Some(dataset) => dataset,
+            None => return Err(GdalError::CallFailed{desc: "failed to create shard"}),
+        };
+        let c_layer = {
+            let layer = dataset.create_layer_ext(&self.name, self.srs.as_ref(), self.geometry_type, &self.layer_options)?;
+            for &(ref name, field_type, width, precision) in &self.fields {
+                layer.create_field(name, field_type, width, precision)?;
+            }
+            unsafe { layer._c_layer() }
+        };
+        let layer = unsafe { Layer::_with_c_layer(c_layer) };
+        self.current = Some((layer, dataset));
+        self.shards.push(Shard{path: path, features: 0});
+        return Ok(());
+    }
+}
@@ -0,0 +1,93 @@
+//! Helpers to build SQL statements and attribute filters from untrusted
+//! values, so names containing quotes can't break out of the statement.
+//!
+//! ```
+//! use gdal::vector::FieldValue;
+//! use gdal::vector::sql::{bind, quote_identifier};
+//!
+//! let sql = bind(
+//!     &format!("SELECT * FROM roads WHERE {} = ?", quote_identifier("name")),
+//!     &[FieldValue::StringValue("O'Connell Street".to_string())],
+//! ).unwrap();
+//! assert_eq!(sql, "SELECT * FROM roads WHERE \"name\" = 'O''Connell Street'");
+//! ```
+
+use vector::FieldValue;
+use GdalError;
+
+/// The SQL dialect used to run a statement.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SqlDialect {
+    /// The native SQL of the driver if it has one (SQLite, GeoPackage,
+    /// PostgreSQL, ...), OGR SQL otherwise.
+    Default,
+    /// [OGR SQL](http://www.gdal.org/ogr_sql.html), available for every
+    /// driver.
+    Ogr,
+    /// [SQLite SQL](http://www.gdal.org/ogr_sql_sqlite.html), available for
+    /// every driver when GDAL is built with SQLite.
+    Sqlite,
+}
+
+impl SqlDialect {
+    /// The dialect name passed to GDAL, `None` for the default.
+    pub fn name(&self) -> Option<&'static str> {
+        match *self {
+            SqlDialect::Default => None,
+            SqlDialect::Ogr => Some("OGRSQL"),
+            SqlDialect::Sqlite => Some("SQLITE"),
+        }
+    }
+}
+
+/// Quote `identifier` as an SQL identifier, e.g. a table or field name.
+pub fn quote_identifier(identifier: &str) -> String {
+    return format!("\"{}\"", identifier.replace('"', "\"\""));
+}
+
+/// Quote `text` as an SQL string literal.
+pub fn quote_literal(text: &str) -> String {
+    return format!("'{}'", text.replace('\'', "''"));
+}
+
+/// Render `value` as an SQL literal. Fails for NaN and infinite reals,
+/// which have no literal.
+pub fn to_literal(value: &FieldValue) -> Result<String, GdalError> {
+    return match *value {
+        FieldValue::StringValue(ref text) => Ok(quote_literal(text)),
+        FieldValue::IntegerValue(value) => Ok(value.to_string()),
+        FieldValue::RealValue(value) if value.is_finite() => Ok(format!("{:?}", value)),
+        FieldValue::RealValue(_) => Err(GdalError::CallFailed{desc: "non-finite SQL parameter"}),
+        FieldValue::DateTimeValue(ref value) => Ok(quote_literal(&value.to_string())),
+    };
+}
+
+/// Replace each `?` placeholder of `sql` with the matching value of
+/// `params`, rendered with `to_literal`. Question marks inside quoted
+/// strings and identifiers are left alone. The number of placeholders
+/// must match the number of values.
+pub fn bind(sql: &str, params: &[FieldValue]) -> Result<String, GdalError> {
+    let mut bound = String::with_capacity(sql.len());
+    let mut params = params.iter();
+    let mut quote = None;
+    for c in sql.chars() {
+        match (quote, c) {
+            // doubled quotes just leave and re-enter the quoted section
+            (None, '\'') | (None, '"') => quote = Some(c),
+            (Some(q), _) if q == c => quote = None,
+            (None, '?') => {
+                match params.next() {
+                    Some(value) => bound.push_str(&to_literal(value)?),
+                    None => return Err(GdalError::CallFailed{desc: "not enough SQL parameters"}),
+                }
+                continue;
+            },
+            _ => (),
+        }
+        bound.push(c);
+    }
+    if params.next().is_some() {
+        return Err(GdalError::CallFailed{desc: "too many SQL parameters"});
+    }
+    return Ok(bound);
+}
@@ -0,0 +1,92 @@
+//! Generators of random and gridded geometries for tests and benchmarks.
+//! Only available with the `testing` feature.
+//!
+//! ```
+//! use gdal::vector::Envelope;
+//! use gdal::vector::testing::{Rng, random_points, random_polygons};
+//!
+//! let envelope = Envelope::new(0., 10., 0., 10.);
+//! let mut rng = Rng::new(42);
+//! let points = random_points(&envelope, 100, &mut rng);
+//! let polygons = random_polygons(&envelope, 10, 1., &mut rng);
+//! ```
+
+use std::f64::consts::PI;
+use vector::{self, Envelope, Geometry};
+
+/// A small, seedable pseudo random number generator (xorshift64*). Not
+/// suitable for anything but generating test data: the same seed always
+/// gives the same sequence, on every platform.
+#[derive(Clone, Debug)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Rng {
+        // xorshift gets stuck at zero, and similar seeds should still give
+        // unrelated sequences, so scramble the seed first (splitmix64)
+        let mut z = seed.wrapping_add(0x9e3779b97f4a7c15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^= z >> 31;
+        return Rng{state: if z == 0 { 1 } else { z }};
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state ^= self.state >> 12;
+        self.state ^= self.state << 25;
+        self.state ^= self.state >> 27;
+        return self.state.wrapping_mul(0x2545f4914f6cdd1d);
+    }
+
+    /// A uniformly distributed number in `[0, 1)`.
+    pub fn next_f64(&mut self) -> f64 {
+        return (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64;
+    }
+
+    /// A uniformly distributed number in `[low, high)`.
+    pub fn range(&mut self, low: f64, high: f64) -> f64 {
+        return low + self.next_f64() * (high - low);
+    }
+}
+
+/// `count` points uniformly distributed within `envelope`.
+pub fn random_points(envelope: &Envelope, count: usize, rng: &mut Rng) -> Vec<Geometry> {
+    return (0..count).map(|_| {
+        let x = rng.range(envelope.min_x, envelope.max_x);
+        let y = rng.range(envelope.min_y, envelope.max_y);
+        // finite coordinates always make valid WKT
+        Geometry::from_wkt(&format!("POINT ({} {})", x, y)).unwrap()
+    }).collect();
+}
+
+/// `count` valid polygons within `envelope`, each a star shaped ring of 3
+/// to 12 vertices around a random center, no further than `max_radius`
+/// from it. Polygons may overlap each other.
+pub fn random_polygons(envelope: &Envelope, count: usize, max_radius: f64, rng: &mut Rng) -> Vec<Geometry> {
+    return (0..count).map(|_| {
+        let cx = rng.range(envelope.min_x, envelope.max_x);
+        let cy = rng.range(envelope.min_y, envelope.max_y);
+        // keep the polygon inside the envelope
+        let radius = max_radius
+            .min(cx - envelope.min_x).min(envelope.max_x - cx)
+            .min(cy - envelope.min_y).min(envelope.max_y - cy);
+        let vertex_count = 3 + (rng.next_u64() % 10) as usize;
+        // vertices sorted by angle around the center never cross
+        let mut angles: Vec<f64> = (0..vertex_count).map(|_| rng.range(0., 2. * PI)).collect();
+        angles.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        let mut ring: Vec<String> = angles.iter().map(|angle| {
+            let r = radius * rng.range(0.5, 1.);
+            format!("{} {}", cx + r * angle.cos(), cy + r * angle.sin())
+        }).collect();
+        let first = ring[0].clone();
+        ring.push(first);
+        Geometry::from_wkt(&format!("POLYGON (({}))", ring.join(", "))).unwrap()
+    }).collect();
+}
+
+/// `vector::fishnet`, panicking on cell sizes that aren't positive.
+pub fn fishnet(envelope: &Envelope, dx: f64, dy: f64) -> Vec<Geometry> {
+    return vector::fishnet(envelope, dx, dy).unwrap();
+}
@@ -8,7 +8,7 @@ fn test_import_export_point() {
     let coord = geo::Coordinate{x: 1., y: 2.};
     let geo = geo::Geometry::Point(geo::Point(coord));
 
-    assert_eq!(Geometry::from_wkt(wkt).to_geo(), geo);
+    assert_eq!(Geometry::from_wkt(wkt).unwrap().to_geo(), geo);
     assert_eq!(geo.to_gdal().wkt(), wkt);
 }
 
@@ -22,7 +22,7 @@ fn test_import_export_multipoint() {
     );
     let geo = geo::Geometry::MultiPoint(geo::MultiPoint(coord));
 
-    assert_eq!(Geometry::from_wkt(wkt).to_geo(), geo);
+    assert_eq!(Geometry::from_wkt(wkt).unwrap().to_geo(), geo);
     assert_eq!(geo.to_gdal().wkt(), wkt);
 }
 
@@ -36,7 +36,7 @@ fn test_import_export_linestring() {
     );
     let geo = geo::Geometry::LineString(geo::LineString(coord));
 
-    assert_eq!(Geometry::from_wkt(wkt).to_geo(), geo);
+    assert_eq!(Geometry::from_wkt(wkt).unwrap().to_geo(), geo);
     assert_eq!(geo.to_gdal().wkt(), wkt);
 }
 
@@ -57,7 +57,7 @@ fn test_import_export_multilinestring() {
     );
     let geo = geo::Geometry::MultiLineString(geo::MultiLineString(strings));
 
-    assert_eq!(Geometry::from_wkt(wkt).to_geo(), geo);
+    assert_eq!(Geometry::from_wkt(wkt).unwrap().to_geo(), geo);
     assert_eq!(geo.to_gdal().wkt(), wkt);
 }
 
@@ -80,7 +80,7 @@ fn test_import_export_polygon() {
     let holes = vec!(square(1, 1, 2, 2), square(3, 3, 4, 4));
     let geo = geo::Geometry::Polygon(geo::Polygon(outer, holes));
 
-    assert_eq!(Geometry::from_wkt(wkt).to_geo(), geo);
+    assert_eq!(Geometry::from_wkt(wkt).unwrap().to_geo(), geo);
     assert_eq!(geo.to_gdal().wkt(), wkt);
 }
 
@@ -106,7 +106,7 @@ fn test_import_export_multipolygon() {
     ));
     let geo = geo::Geometry::MultiPolygon(multipolygon);
 
-    assert_eq!(Geometry::from_wkt(wkt).to_geo(), geo);
+    assert_eq!(Geometry::from_wkt(wkt).unwrap().to_geo(), geo);
     assert_eq!(geo.to_gdal().wkt(), wkt);
 }
 
@@ -124,6 +124,35 @@ fn test_import_export_geometrycollection() {
     let collection = geo::GeometryCollection(vec!(point, linestring));
     let geo = geo::Geometry::GeometryCollection(collection);
 
-    assert_eq!(Geometry::from_wkt(wkt).to_geo(), geo);
+    assert_eq!(Geometry::from_wkt(wkt).unwrap().to_geo(), geo);
     assert_eq!(geo.to_gdal().wkt(), wkt);
 }
+
+#[test]
+fn test_import_export_nested_geometrycollection() {
+    let wkt = concat!(
+        "GEOMETRYCOLLECTION (MULTIPOINT (0 0,1 1),",
+        "GEOMETRYCOLLECTION (POINT (1 2),POLYGON ((0 0,1 0,1 1,0 0))))"
+    );
+    let point = |x, y| geo::Point(geo::Coordinate{x: x, y: y});
+    let multipoint = geo::Geometry::MultiPoint(geo::MultiPoint(vec!(point(0., 0.), point(1., 1.))));
+    let ring = geo::LineString(vec!(point(0., 0.), point(1., 0.), point(1., 1.), point(0., 0.)));
+    let polygon = geo::Geometry::Polygon(geo::Polygon(ring, vec!()));
+    let inner = geo::GeometryCollection(vec!(geo::Geometry::Point(point(1., 2.)), polygon));
+    let collection = geo::GeometryCollection(vec!(multipoint, geo::Geometry::GeometryCollection(inner)));
+    let geo = geo::Geometry::GeometryCollection(collection);
+
+    assert_eq!(Geometry::from_wkt(wkt).unwrap().to_geo(), geo);
+    assert_eq!(geo.to_gdal().wkt(), wkt);
+}
+
+#[test]
+fn test_import_3d_geometrycollection() {
+    let wkt = "GEOMETRYCOLLECTION (POINT (1 2 3),LINESTRING (0 0 1,0 1 2))";
+    let point = |x, y| geo::Point(geo::Coordinate{x: x, y: y});
+    let collection = geo::GeometryCollection(vec!(
+        geo::Geometry::Point(point(1., 2.)),
+        geo::Geometry::LineString(geo::LineString(vec!(point(0., 0.), point(0., 1.)))),
+    ));
+    assert_eq!(Geometry::from_wkt(wkt).unwrap().to_geo(), geo::Geometry::GeometryCollection(collection));
+}
@@ -1,5 +1,5 @@
 use std::path::Path;
-use super::{Driver, Dataset, Feature, FeatureIterator, Geometry};
+use super::{Driver, Dataset, Feature, FeatureIterator, FieldValue, Geometry, OFT_INTEGER, OFT_REAL, OFT_STRING};
 
 mod convert_geo;
 
@@ -56,13 +56,13 @@ fn test_string_field() {
     with_features("roads.geojson", |mut features| {
         let feature = features.next().unwrap();
         assert_eq!(feature.field("highway")
-                          .unwrap()
+                          .unwrap().unwrap()
                           .as_string(),
                    "footway".to_string());
         assert_eq!(
             features.filter(|field| {
                 let highway = field.field("highway")
-                                   .unwrap()
+                                   .unwrap().unwrap()
                                    .as_string();
                 highway == "residential".to_string() })
                 .count(),
@@ -76,7 +76,7 @@ fn test_float_field() {
     with_first_feature("roads.geojson", |feature| {
         assert_almost_eq(
             feature.field("sort_key")
-                   .unwrap()
+                   .unwrap().unwrap()
                    .as_real(),
             -9.0
         );
@@ -95,7 +95,7 @@ fn test_missing_field() {
 #[test]
 fn test_wkt() {
     with_first_feature("roads.geojson", |feature| {
-        let wkt = feature.geometry().wkt();
+        let wkt = feature.geometry().unwrap().wkt();
         let wkt_ok = format!("{}{}",
             "LINESTRING (26.1019276 44.4302748,",
             "26.1019382 44.4303191,26.1020002 44.4304202)"
@@ -105,10 +105,58 @@ fn test_wkt() {
 }
 
 
+#[test]
+fn test_geometry_to_owned() {
+    let mut ds = Dataset::open(fixture!("roads.geojson")).unwrap();
+    let layer = ds.layer(0).unwrap();
+    let geometry = {
+        let feature = layer.features().next().unwrap();
+        let geometry = feature.geometry().unwrap().to_owned();
+        geometry
+    };
+    assert_eq!(geometry.get_point(0), (26.1019276, 44.4302748, 0.));
+}
+
+
+#[test]
+fn test_feature_without_geometry() {
+    use std::fs;
+
+    fs::write(
+        fixture!("no_geometry.geojson"),
+        r#"{"type": "FeatureCollection", "features": [{"type": "Feature", "properties": {}, "geometry": null}]}"#,
+    ).unwrap();
+    let mut ds = Dataset::open(fixture!("no_geometry.geojson")).unwrap();
+    fs::remove_file(fixture!("no_geometry.geojson")).unwrap();
+    let layer = ds.layer(0).unwrap();
+    let feature = layer.features().next().unwrap();
+    assert!(!feature.has_geometry());
+    assert!(feature.geometry().is_none());
+}
+
+
+#[test]
+fn test_geometry_clone() {
+    let mut ds = Dataset::open(fixture!("roads.geojson")).unwrap();
+    let layer = ds.layer(0).unwrap();
+    let geometry = {
+        let feature = layer.features().next().unwrap();
+        let geometry: Geometry = (*feature.geometry().unwrap()).clone();
+        geometry
+    };
+    assert_eq!(geometry.get_point(0), (26.1019276, 44.4302748, 0.));
+
+    let mut copy = geometry.clone();
+    copy.set_point_2d(0, (0., 0.));
+    assert_eq!(copy.get_point(0), (0., 0., 0.));
+    assert_eq!(geometry.get_point(0), (26.1019276, 44.4302748, 0.));
+}
+
+
 #[test]
 fn test_json() {
     with_first_feature("roads.geojson", |feature| {
-        let json = feature.geometry().json();
+        let json = feature.geometry().unwrap().json();
         let json_ok = format!("{}{}{}{}",
             "{ \"type\": \"LineString\", \"coordinates\": [ ",
             "[ 26.1019276, 44.4302748 ], ",
@@ -161,13 +209,169 @@ fn test_spatial_filter() {
     assert_eq!(again_all_features.len(), 21);
 }
 
+#[test]
+fn test_attribute_filter() {
+    let mut ds = Dataset::open(fixture!("roads.geojson")).unwrap();
+    let layer = ds.layer(0).unwrap();
+
+    layer.set_attribute_filter("highway = 'pedestrian'").unwrap();
+    assert_eq!(layer.features().count(), 10);
+
+    layer.set_attribute_filter("highway IN ('pedestrian', 'footway')").unwrap();
+    assert_eq!(layer.features().count(), 18);
+
+    assert!(layer.set_attribute_filter("no_such_field = 1").is_err());
+
+    layer.clear_attribute_filter();
+    assert_eq!(layer.features().count(), 21);
+}
+
 #[test]
 fn test_convex_hull() {
     let star = "POLYGON ((0 1,3 1,1 3,1.5 0.0,2 3,0 1))";
     let hull = "POLYGON ((1.5 0.0,0 1,1 3,2 3,3 1,1.5 0.0))";
-    assert_eq!(Geometry::from_wkt(star).convex_hull().wkt(), hull);
+    assert_eq!(Geometry::from_wkt(star).unwrap().convex_hull().wkt(), hull);
+}
+
+#[test]
+fn test_segmentize() {
+    let mut line = Geometry::from_wkt("LINESTRING (0 0,10 0,10 1)").unwrap();
+    line.segmentize(2.5);
+    assert_eq!(line.wkt(), "LINESTRING (0 0,2.5 0,5 0,7.5 0,10 0,10 1)");
+    let mut square = Geometry::bbox(0., 0., 2., 2.);
+    square.segmentize(1.);
+    assert_eq!(square.boundary().unwrap().get_point_vec().len(), 9);
+}
+
+#[test]
+fn test_close_rings_flatten_force() {
+    let mut polygon = Geometry::from_wkt("POLYGON ((0 0 1,1 0 1,1 1 1))").unwrap();
+    polygon.close_rings();
+    polygon.flatten_to_2d();
+    assert_eq!(polygon.wkt(), "POLYGON ((0 0,1 0,1 1,0 0))");
+
+    let multi = polygon.to_multi_polygon();
+    assert_eq!(multi.wkt(), "MULTIPOLYGON (((0 0,1 0,1 1,0 0)))");
+    assert_eq!(multi.to_multi_polygon().wkt(), multi.wkt());
+    assert_eq!(multi.to_polygon().wkt(), polygon.wkt());
+    // the original is left alone
+    assert_eq!(polygon.wkt(), "POLYGON ((0 0,1 0,1 1,0 0))");
+
+    let lines = Geometry::from_wkt("MULTILINESTRING ((0 0,1 0),(1 0,1 1))").unwrap();
+    assert_eq!(lines.to_line_string().wkt(), "LINESTRING (0 0,1 0,1 1)");
+    let line = Geometry::from_wkt("LINESTRING (0 0,1 0)").unwrap();
+    assert_eq!(line.to_multi_line_string().wkt(), "MULTILINESTRING ((0 0,1 0))");
+    assert_eq!(Geometry::from_wkt("POINT (1 2)").unwrap().to_multi_point().wkt(), "MULTIPOINT (1 2)");
+}
+
+#[test]
+fn test_from_geojson() {
+    let point = Geometry::from_geojson("{\"type\": \"Point\", \"coordinates\": [1, 2]}").unwrap();
+    assert_eq!(point.wkt(), "POINT (1 2)");
+    let line = Geometry::from_wkt("LINESTRING (0 0,1 0.5)").unwrap();
+    assert_eq!(Geometry::from_geojson(&line.json()).unwrap().wkt(), line.wkt());
+    assert!(Geometry::from_geojson("{\"type\": \"Nothing\"}").is_none());
+    assert!(Geometry::from_geojson("not json").is_none());
+}
+
+#[test]
+fn test_boundary() {
+    let square = Geometry::from_wkt("POLYGON ((0 0,1 0,1 1,0 1,0 0))").unwrap();
+    assert_eq!(square.boundary().unwrap().wkt(), "LINESTRING (0 0,1 0,1 1,0 1,0 0)");
+    let line = Geometry::from_wkt("LINESTRING (0 0,1 0,1 1)").unwrap();
+    assert_eq!(line.boundary().unwrap().wkt(), "MULTIPOINT (0 0,1 1)");
+}
+
+#[test]
+fn test_delaunay_triangulation() {
+    use super::GeometryType;
+    let points = Geometry::from_wkt("MULTIPOINT (0 0 1,1 0 2,1 1 3,0 1 4)").unwrap();
+    let triangles = points.delaunay_triangulation(0., false).unwrap();
+    assert_eq!(triangles.geometry_count(), 2);
+    assert!((triangles.area() - 1.).abs() < 1e-10);
+    for triangle in triangles.sub_geometries() {
+        assert_eq!(triangle.geometry_type(), GeometryType::Polygon);
+        assert_eq!(triangle.sub_geometry(0).unwrap().get_point_vec().len(), 4);
+    }
+
+    let edges = points.delaunay_triangulation(0., true).unwrap();
+    assert_eq!(edges.geometry_type(), GeometryType::MultiLineString);
+    // the four sides and one diagonal
+    assert_eq!(edges.geometry_count(), 5);
+}
+
+#[test]
+fn test_polygonize() {
+    use super::GeometryType;
+    // two squares sharing an edge, and a dangling line
+    let lines = Geometry::from_wkt(
+        "MULTILINESTRING ((0 0,1 0),(1 0,1 1),(1 1,0 1),(0 1,0 0),(1 0,2 0,2 1,1 1),(2 1,3 3))"
+    ).unwrap();
+    let polygons = lines.polygonize().unwrap();
+    assert_eq!(polygons.geometry_count(), 2);
+    for polygon in polygons.sub_geometries() {
+        assert_eq!(polygon.geometry_type(), GeometryType::Polygon);
+        assert!((polygon.area() - 1.).abs() < 1e-10);
+    }
+}
+
+#[test]
+fn test_union_cascaded() {
+    // two overlapping squares and a separate one
+    let polygons = Geometry::from_wkt(
+        "MULTIPOLYGON (((0 0,2 0,2 2,0 2,0 0)),((1 1,3 1,3 3,1 3,1 1)),((5 5,6 5,6 6,5 6,5 5)))"
+    ).unwrap();
+    let dissolved = polygons.union_cascaded().unwrap();
+    assert_eq!(dissolved.geometry_count(), 2);
+    assert!((dissolved.area() - 8.).abs() < 1e-10);
+    assert_eq!(dissolved.envelope(), polygons.envelope());
+}
+
+#[test]
+fn test_valid_features() {
+    use super::ValidityPolicy;
+
+    fn write_features(ds: &mut Dataset) -> &mut super::Layer {
+        let layer = ds.create_layer().unwrap();
+        layer.create_feature(Geometry::from_wkt("POLYGON ((0 0,1 0,1 1,0 1,0 0))").unwrap()).unwrap();
+        // bow tie
+        layer.create_feature(Geometry::from_wkt("POLYGON ((0 0,2 2,2 0,0 2,0 0))").unwrap()).unwrap();
+        layer.create_feature(Geometry::from_wkt("POINT (1 2)").unwrap()).unwrap();
+        return layer;
+    }
+    let driver = Driver::get("Memory").unwrap();
+
+    let mut ds = driver.create(Path::new("")).unwrap();
+    let layer = write_features(&mut ds);
+    let mut features = layer.valid_features(ValidityPolicy::Skip);
+    assert_eq!(features.by_ref().count(), 2);
+    assert_eq!((features.repaired(), features.skipped()), (0, 1));
+
+    let mut ds = driver.create(Path::new("")).unwrap();
+    let layer = write_features(&mut ds);
+    let mut features = layer.valid_features(ValidityPolicy::Repair);
+    let geometries: Vec<Geometry> = features.by_ref().map(|feature| feature.geometry().unwrap().to_owned()).collect();
+    assert_eq!(geometries.len(), 3);
+    assert!(geometries.iter().all(|geometry| geometry.is_valid()));
+    assert_eq!((features.repaired(), features.skipped()), (1, 0));
+}
+
+#[test]
+fn test_fast_intersects() {
+    let square = Geometry::bbox(0., 0., 10., 10.);
+    let inside = Geometry::from_wkt("POINT (5 5)").unwrap();
+    let far = Geometry::from_wkt("POINT (50 50)").unwrap();
+    // inside the envelope of the triangle, but not the triangle itself
+    let triangle = Geometry::from_wkt("POLYGON ((0 0, 10 0, 0 10, 0 0))").unwrap();
+    let corner = Geometry::from_wkt("POINT (9 9)").unwrap();
+
+    assert!(square.fast_intersects(&inside));
+    assert!(!square.fast_intersects(&far));
+    assert!(!triangle.fast_intersects(&corner));
+    assert_eq!(triangle.fast_intersects(&corner), triangle.intersects(&corner));
 }
 
+
 #[test]
 fn test_write_features() {
     use std::fs;
@@ -175,8 +379,8 @@ fn test_write_features() {
     {
         let driver = Driver::get("GeoJSON").unwrap();
         let mut ds = driver.create(fixture!("output.geojson")).unwrap();
-        let mut layer = ds.create_layer();
-        layer.create_feature(Geometry::from_wkt("POINT (1 2)"));
+        let mut layer = ds.create_layer().unwrap();
+        layer.create_feature(Geometry::from_wkt("POINT (1 2)").unwrap()).unwrap();
         // dataset is closed here
     }
 
@@ -184,7 +388,1388 @@ fn test_write_features() {
     fs::remove_file(fixture!("output.geojson")).unwrap();
     let layer = ds.layer(0).unwrap();
     let wkt_list = layer.features()
-        .map(|f| f.geometry().wkt())
+        .map(|f| f.geometry().unwrap().wkt())
         .collect::<Vec<String>>();
     assert_eq!(wkt_list, vec!("POINT (1 2)"));
 }
+
+#[test]
+fn test_write_features_with_fields() {
+    use std::fs;
+
+    {
+        let driver = Driver::get("GeoJSON").unwrap();
+        let mut ds = driver.create(fixture!("output_fields.geojson")).unwrap();
+        let layer = ds.create_layer().unwrap();
+        layer.create_defn_fields(&[("name", OFT_STRING), ("value", OFT_REAL)]).unwrap();
+        layer.create_feature_fields(
+            Geometry::from_wkt("POINT (1 2)").unwrap(),
+            &["name", "value"],
+            &[FieldValue::StringValue("a point".to_string()), FieldValue::RealValue(4.5)],
+        ).unwrap();
+    }
+
+    let mut ds = Dataset::open(fixture!("output_fields.geojson")).unwrap();
+    fs::remove_file(fixture!("output_fields.geojson")).unwrap();
+    let layer = ds.layer(0).unwrap();
+    let feature = layer.features().next().unwrap();
+    assert_eq!(feature.field("name").unwrap().unwrap().as_string(), "a point");
+    assert_eq!(feature.field("value").unwrap().unwrap().as_real(), 4.5);
+}
+
+#[test]
+fn test_clone_feature() {
+    let mut ds = Dataset::open(fixture!("roads.geojson")).unwrap();
+    let layer = ds.layer(0).unwrap();
+    let mut features = layer.features();
+    let first = features.next().unwrap().clone();
+    let _second = features.next().unwrap();
+    assert_eq!(first.field("highway").unwrap().unwrap().as_string(), "footway");
+    assert_eq!(first.geometry().unwrap().get_point(0), (26.1019276, 44.4302748, 0.));
+
+    let driver = Driver::get("Memory").unwrap();
+    let mut copy_ds = driver.create(Path::new("")).unwrap();
+    let copy_layer = copy_ds.create_layer().unwrap();
+    copy_layer.create_defn_fields(&[("highway", OFT_STRING)]).unwrap();
+    copy_layer.create_feature_from(&first).unwrap();
+    let copy = copy_layer.features().next().unwrap();
+    assert_eq!(copy.field("highway").unwrap().unwrap().as_string(), "footway");
+    assert!(copy.field("kind").is_none());
+    assert_eq!(copy.geometry().unwrap().wkt(), first.geometry().unwrap().wkt());
+}
+
+#[test]
+fn test_feature_fields() {
+    with_first_feature("roads.geojson", |feature| {
+        let names: Vec<String> = feature.fields().map(|(name, _)| name).collect();
+        assert_eq!(names, ["kind", "sort_key", "is_link", "is_tunnel", "is_bridge", "railway", "highway"]);
+        let (_, kind) = feature.fields().next().unwrap();
+        assert_eq!(kind.unwrap().unwrap().as_string(), "path");
+        let (_, sort_key) = feature.fields().nth(1).unwrap();
+        assert_eq!(sort_key.unwrap().unwrap().as_real(), -9.);
+    });
+
+    let driver = Driver::get("Memory").unwrap();
+    let mut ds = driver.create(Path::new("")).unwrap();
+    let layer = ds.create_layer().unwrap();
+    layer.create_defn_fields(&[("lanes", OFT_INTEGER), ("name", OFT_STRING)]).unwrap();
+    layer.create_feature_fields(Geometry::from_wkt("POINT (1 2)").unwrap(), &["lanes"], &[FieldValue::IntegerValue(3)]).unwrap();
+    let feature = layer.features().next().unwrap();
+    assert_eq!(feature.field("lanes").unwrap().unwrap().as_int(), 3);
+    let fields: Vec<_> = feature.fields().collect();
+    assert_eq!(fields.len(), 2);
+    assert_eq!(fields[1].0, "name");
+    assert!(fields[1].1.is_none());
+}
+
+#[test]
+fn test_create_layer_with_srs() {
+    use std::fs;
+    use spatial_ref::SpatialRef;
+    use super::WKB_POINT;
+
+    let mercator = SpatialRef::from_epsg(3857).unwrap();
+    {
+        let driver = Driver::get("ESRI Shapefile").unwrap();
+        let mut ds = driver.create(fixture!("output_srs.shp")).unwrap();
+        let layer = ds.create_layer_ext("output_srs", Some(&mercator), WKB_POINT, &["ENCODING=UTF-8".to_string()]).unwrap();
+        layer.create_feature(Geometry::from_wkt("POINT (1 2)").unwrap()).unwrap();
+    }
+    assert!(fixture!("output_srs.prj").exists());
+
+    {
+        let mut ds = Dataset::open(fixture!("output_srs.shp")).unwrap();
+        let layer = ds.layer(0).unwrap();
+        assert!(layer.spatial_ref().unwrap().is_same(&mercator));
+    }
+    for extension in &["shp", "shx", "dbf", "prj", "cpg"] {
+        let _ = fs::remove_file(fixture!(format!("output_srs.{}", extension)));
+    }
+}
+
+#[test]
+fn test_csv_options() {
+    use super::csv::{CsvOpenOptions, CsvLayerOptions, GeometryEncoding, Separator};
+
+    let open_options = CsvOpenOptions::new()
+        .x_y_columns("lon", "lat")
+        .keep_geometry_columns(false)
+        .autodetect_type(true)
+        .separator(Separator::Semicolon);
+    assert_eq!(open_options.options(), [
+        "X_POSSIBLE_NAMES=lon", "Y_POSSIBLE_NAMES=lat", "KEEP_GEOM_COLUMNS=NO",
+        "AUTODETECT_TYPE=YES", "SEPARATOR=SEMICOLON",
+    ]);
+
+    let layer_options = CsvLayerOptions::new()
+        .geometry(GeometryEncoding::AsWkt)
+        .create_csvt(true);
+    assert_eq!(layer_options.options(), ["GEOMETRY=AS_WKT", "CREATE_CSVT=YES"]);
+}
+
+#[test]
+fn test_write_and_read_csv() {
+    use std::fs;
+    use super::csv::{CsvOpenOptions, CsvLayerOptions, GeometryEncoding, write_csv};
+
+    {
+        let driver = Driver::get("Memory").unwrap();
+        let mut ds = driver.create(Path::new("")).unwrap();
+        let layer = ds.create_layer().unwrap();
+        layer.create_defn_fields(&[("name", OFT_STRING), ("value", OFT_REAL)]).unwrap();
+        layer.create_feature_fields(
+            Geometry::from_wkt("POINT (1 2)").unwrap(),
+            &["name", "value"],
+            &[FieldValue::StringValue("a point".to_string()), FieldValue::RealValue(4.5)],
+        ).unwrap();
+        let options = CsvLayerOptions::new().geometry(GeometryEncoding::AsXy);
+        write_csv(layer, fixture!("output.csv"), &options).unwrap();
+    }
+
+    let mut ds = CsvOpenOptions::new()
+        .x_y_columns("X", "Y")
+        .keep_geometry_columns(false)
+        .autodetect_type(true)
+        .open(fixture!("output.csv"))
+        .unwrap();
+    fs::remove_file(fixture!("output.csv")).unwrap();
+    let layer = ds.layer(0).unwrap();
+    let feature = layer.features().next().unwrap();
+    assert_eq!(feature.field("name").unwrap().unwrap().as_string(), "a point");
+    assert_eq!(feature.field("value").unwrap().unwrap().as_real(), 4.5);
+    assert!(feature.field("X").is_none());
+    assert_eq!(feature.geometry().unwrap().wkt(), "POINT (1 2)");
+}
+
+#[test]
+fn test_xlsx_options() {
+    use super::xlsx::{Headers, XlsxOpenOptions};
+
+    assert!(XlsxOpenOptions::new().options().is_empty());
+    let options = XlsxOpenOptions::new().headers(Headers::Force).detect_types(false);
+    assert_eq!(options.options(), ["HEADERS=FORCE", "FIELD_TYPES=STRING"]);
+}
+
+#[test]
+fn test_write_and_read_xlsx() {
+    use std::fs;
+    use super::xlsx::{Headers, XlsxOpenOptions, write_xlsx};
+
+    {
+        let driver = Driver::get("Memory").unwrap();
+        let mut ds = driver.create(Path::new("")).unwrap();
+        let layer = ds.create_layer().unwrap();
+        layer.create_defn_fields(&[("name", OFT_STRING), ("value", OFT_REAL)]).unwrap();
+        layer.create_feature_fields(
+            Geometry::from_wkt("POINT (1 2)").unwrap(),
+            &["name", "value"],
+            &[FieldValue::StringValue("a point".to_string()), FieldValue::RealValue(4.5)],
+        ).unwrap();
+        assert!(write_xlsx(layer, fixture!("output.xlsx"), Some("name")).is_err());
+        write_xlsx(layer, fixture!("output.xlsx"), Some("WKT")).unwrap();
+    }
+
+    let mut ds = XlsxOpenOptions::new()
+        .headers(Headers::Force)
+        .open(fixture!("output.xlsx"))
+        .unwrap();
+    fs::remove_file(fixture!("output.xlsx")).unwrap();
+    let layer = ds.layer(0).unwrap();
+    let feature = layer.features().next().unwrap();
+    assert_eq!(feature.field("name").unwrap().unwrap().as_string(), "a point");
+    assert_eq!(feature.field("value").unwrap().unwrap().as_real(), 4.5);
+    assert_eq!(feature.field("WKT").unwrap().unwrap().as_string(), "POINT (1 2)");
+}
+
+#[test]
+fn test_layer_by_name() {
+    let mut ds = Dataset::open(fixture!("roads.geojson")).unwrap();
+    let name = ds.layer(0).unwrap().name();
+    assert_eq!(ds.layer_by_name(&name).unwrap().name(), name);
+    assert!(ds.layer_by_name("no such layer").is_none());
+}
+
+#[test]
+fn test_write_and_read_gpx() {
+    use std::fs;
+    use super::gpx::{GpxLayer, GpxOptions, create_gpx, create_gpx_layer, open_gpx};
+
+    let options = GpxOptions::new().extensions("ogr", "http://osgeo.org/gdal");
+    assert_eq!(options.options(), [
+        "GPX_USE_EXTENSIONS=YES", "GPX_EXTENSIONS_NS=ogr", "GPX_EXTENSIONS_NS_URL=http://osgeo.org/gdal",
+    ]);
+    {
+        let mut ds = create_gpx(fixture!("output.gpx"), &options).unwrap();
+        assert!(create_gpx_layer(&mut ds, GpxLayer::TrackPoints).is_err());
+        let layer = create_gpx_layer(&mut ds, GpxLayer::Waypoints).unwrap();
+        layer.create_defn_fields(&[("name", OFT_STRING), ("color", OFT_STRING)]).unwrap();
+        layer.create_feature_fields(
+            Geometry::from_wkt("POINT (26.1 44.4)").unwrap(),
+            &["name", "color"],
+            &[FieldValue::StringValue("home".to_string()), FieldValue::StringValue("red".to_string())],
+        ).unwrap();
+    }
+
+    let mut ds = open_gpx(fixture!("output.gpx")).unwrap();
+    fs::remove_file(fixture!("output.gpx")).unwrap();
+    let layer = ds.layer_by_name(GpxLayer::Waypoints.name()).unwrap();
+    let waypoint = layer.features().next().unwrap();
+    assert_eq!(waypoint.field("name").unwrap().unwrap().as_string(), "home");
+    assert_eq!(waypoint.field("ogr_color").unwrap().unwrap().as_string(), "red");
+    assert_eq!(waypoint.geometry().unwrap().wkt(), "POINT (26.1 44.4)");
+}
+
+#[test]
+fn test_kml_options() {
+    use super::kml::{AltitudeMode, KmlOptions, LibKmlLayerOptions, Style};
+
+    let options = KmlOptions::new().name_field("title").altitude_mode(AltitudeMode::Absolute);
+    assert_eq!(options.options(), ["NameField=title", "AltitudeMode=absolute"]);
+    let layer_options = LibKmlLayerOptions::new().folder(true).name("Roads");
+    assert_eq!(layer_options.options(), ["FOLDER=YES", "NAME=Roads"]);
+    let style = Style::new().line_color("#ff0000").line_width(2.).fill_color("#00ff0080");
+    assert_eq!(style.to_ogr_style(), "PEN(c:#ff0000,w:2px);BRUSH(fc:#00ff0080)");
+}
+
+#[test]
+fn test_gpkg_options() {
+    use std::fs;
+    use super::SqlDialect;
+    use super::gpkg::{GpkgLayerOptions, GpkgOptions, GpkgVersion, create_gpkg};
+
+    let options = GpkgOptions::new().version(GpkgVersion::V1_2).add_gpkg_ogr_contents(false);
+    assert_eq!(options.options(), ["VERSION=1.2", "ADD_GPKG_OGR_CONTENTS=NO"]);
+    let layer_options = GpkgLayerOptions::new().spatial_index(false).identifier("Roads").description("All roads");
+    assert_eq!(layer_options.options(), ["SPATIAL_INDEX=NO", "IDENTIFIER=Roads", "DESCRIPTION=All roads"]);
+
+    let _ = fs::remove_file(fixture!("output.gpkg"));
+    {
+        let mut ds = create_gpkg(fixture!("output.gpkg"), &options).unwrap();
+        let layer = ds.create_layer_ext("roads", None, super::WKB_LINESTRING, &layer_options.options()).unwrap();
+        layer.create_feature(Geometry::from_wkt("LINESTRING (0 0, 1 1)").unwrap()).unwrap();
+    }
+    {
+        let ds = Dataset::open(fixture!("output.gpkg")).unwrap();
+        let sql = "SELECT identifier, description FROM gpkg_contents WHERE table_name = 'roads'";
+        let result = ds.execute_sql(sql, SqlDialect::Default).unwrap().unwrap();
+        let contents = result.features().next().unwrap();
+        assert_eq!(contents.field("identifier").unwrap().unwrap().as_string(), "Roads");
+        assert_eq!(contents.field("description").unwrap().unwrap().as_string(), "All roads");
+    }
+    {
+        let ds = Dataset::open(fixture!("output.gpkg")).unwrap();
+        let sql = "SELECT name FROM sqlite_master WHERE name IN ('gpkg_ogr_contents', 'rtree_roads_geom')";
+        let result = ds.execute_sql(sql, SqlDialect::Default).unwrap().unwrap();
+        assert_eq!(result.features().count(), 0);
+    }
+    fs::remove_file(fixture!("output.gpkg")).unwrap();
+}
+
+#[test]
+fn test_write_kml_with_style() {
+    use std::fs;
+    use super::kml::{KmlOptions, Style, create_kml};
+    use super::WKB_LINESTRING;
+
+    let style = Style::new().line_color("#ff0000").line_width(2.).to_ogr_style();
+    {
+        let mut ds = create_kml(fixture!("output.kml"), "KML", &KmlOptions::new()).unwrap();
+        let layer = ds.create_layer_ext("roads", None, WKB_LINESTRING, &[]).unwrap();
+        layer.create_feature_styled(Geometry::from_wkt("LINESTRING (0 0, 1 1)").unwrap(), &style).unwrap();
+    }
+    let kml = fs::read_to_string(fixture!("output.kml")).unwrap();
+    fs::remove_file(fixture!("output.kml")).unwrap();
+    assert!(kml.contains("<name>roads</name>"));
+    // KML colors are written as aabbggrr
+    assert!(kml.contains("<LineStyle>"));
+    assert!(kml.contains("ff0000ff"));
+}
+
+#[test]
+fn test_layers() {
+    let mut ds = Dataset::open(fixture!("roads.geojson")).unwrap();
+    assert_eq!(ds.layers().len(), 1);
+}
+
+#[test]
+fn test_osm_options() {
+    use super::osm::{OsmLayer, OsmOpenOptions};
+
+    let options = OsmOpenOptions::new().interleaved_reading(true).max_tmpfile_size(200);
+    assert_eq!(options.options(), ["INTERLEAVED_READING=YES", "MAX_TMPFILE_SIZE=200"]);
+    assert_eq!(OsmLayer::MultiPolygons.name(), "multipolygons");
+}
+
+#[test]
+fn test_read_interleaved() {
+    use super::osm::read_interleaved;
+
+    // any dataset can be read this way, it's just slower than needed
+    let mut ds = Dataset::open(fixture!("roads.geojson")).unwrap();
+    let mut count = 0;
+    read_interleaved(&mut ds, |_, feature| {
+        assert!(feature.field("highway").is_some());
+        count += 1;
+    });
+    assert_eq!(count, 21);
+}
+
+#[test]
+fn test_create_view() {
+    use std::fs;
+    use super::SqlDialect;
+
+    for &(driver_name, file_name) in &[("SQLite", "output_view.sqlite"), ("GPKG", "output_view.gpkg")] {
+        {
+            let driver = Driver::get(driver_name).unwrap();
+            let mut ds = driver.create(fixture!(file_name)).unwrap();
+            assert_eq!(ds.driver_name(), driver_name);
+            {
+                let layer = ds.create_layer_ext("roads", None, super::WKB_POINT, &[]).unwrap();
+                layer.create_defn_fields(&[("kind", OFT_STRING)]).unwrap();
+                for kind in &["path", "road", "path"] {
+                    layer.create_feature_fields(
+                        Geometry::from_wkt("POINT (1 2)").unwrap(),
+                        &["kind"],
+                        &[FieldValue::StringValue(kind.to_string())],
+                    ).unwrap();
+                }
+            }
+            ds.create_view("paths", "SELECT kind FROM roads WHERE kind = 'path'", SqlDialect::Default).unwrap();
+            assert!(ds.create_view("broken", "SELECT * FROM no_such_table", SqlDialect::Default).is_err());
+        }
+
+        let mut ds = Dataset::open(fixture!(file_name)).unwrap();
+        let count = ds.layer_by_name("paths").unwrap().features().count();
+        assert_eq!(count, 2);
+        drop(ds);
+        fs::remove_file(fixture!(file_name)).unwrap();
+    }
+
+    let mut geojson = Dataset::open(fixture!("roads.geojson")).unwrap();
+    assert!(geojson.create_view("paths", "SELECT * FROM roads", SqlDialect::Ogr).is_err());
+}
+
+#[test]
+fn test_field_encoding() {
+    use std::fs;
+
+    {
+        let driver = Driver::get("ESRI Shapefile").unwrap();
+        let mut ds = driver.create(fixture!("output_latin1.shp")).unwrap();
+        let layer = ds.create_layer_ext("output_latin1", None, super::WKB_POINT, &["ENCODING=ISO-8859-1".to_string()]).unwrap();
+        layer.create_defn_fields(&[("name", OFT_STRING)]).unwrap();
+        layer.create_feature_fields(
+            Geometry::from_wkt("POINT (1 2)").unwrap(),
+            &["name"],
+            &[FieldValue::StringValue("café".to_string())],
+        ).unwrap();
+    }
+
+    {
+        // recoded from the encoding declared by the shapefile
+        let mut ds = Dataset::open(fixture!("output_latin1.shp")).unwrap();
+        let feature = ds.layer(0).unwrap().features().next().unwrap();
+        assert_eq!(feature.field("name").unwrap().unwrap().as_string(), "café");
+    }
+
+    {
+        // raw Latin-1 bytes
+        let mut ds = Dataset::open_with_encoding(fixture!("output_latin1.shp"), "").unwrap();
+        let feature = ds.layer(0).unwrap().features().next().unwrap();
+        assert!(feature.field("name").unwrap().is_err());
+        let (_, value) = feature.fields().next().unwrap();
+        assert!(value.unwrap().is_err());
+        assert_eq!(feature.field_lossy("name").unwrap().as_string(), "caf\u{FFFD}");
+        assert!(feature.field("no such field").is_none());
+    }
+
+    for extension in &["shp", "shx", "dbf", "cpg"] {
+        let _ = fs::remove_file(fixture!(format!("output_latin1.{}", extension)));
+    }
+}
+
+#[test]
+fn test_nul_in_strings() {
+    use std::fs;
+    use GdalError;
+
+    {
+        let mut ds = Dataset::open(fixture!("roads.geojson")).unwrap();
+        let layer = ds.layer(0).unwrap();
+        let feature = layer.features().next().unwrap();
+        assert!(feature.field("high\0way").is_none());
+    }
+
+    let driver = Driver::get("GeoJSON").unwrap();
+    let mut ds = driver.create(fixture!("output_nul.geojson")).unwrap();
+    let layer = ds.create_layer().unwrap();
+    assert!(layer.create_defn_fields(&[("na\0me", OFT_STRING)]).is_err());
+    layer.create_defn_fields(&[("name", OFT_STRING)]).unwrap();
+    match layer.create_feature_fields(
+        Geometry::from_wkt("POINT (1 2)").unwrap(),
+        &["name"],
+        &[FieldValue::StringValue("a\0b".to_string())],
+    ) {
+        Err(GdalError::FfiNulError(_)) => (),
+        _ => panic!("expected a NUL error"),
+    }
+    assert!(layer.create_feature_fields(Geometry::from_wkt("POINT (1 2)").unwrap(), &["missing"], &[FieldValue::IntegerValue(1)]).is_err());
+    assert!(layer.create_feature_fields(Geometry::from_wkt("POINT (1 2)").unwrap(), &["name"], &[]).is_err());
+    assert!(Dataset::open(Path::new("roads\0.geojson")).is_none());
+    assert!(ds.create_layer_ext("ro\0ads", None, super::WKB_POINT, &[]).is_err());
+    match Geometry::from_wkt("POINT (1\0 2)") {
+        Err(GdalError::FfiNulError(_)) => (),
+        _ => panic!("expected a NUL error"),
+    }
+    assert!(Geometry::from_wkt("POINT (1 2").is_err());
+    fs::remove_file(fixture!("output_nul.geojson")).unwrap();
+}
+
+#[test]
+fn test_sql_bind() {
+    use vector::sql::{bind, quote_identifier, to_literal};
+
+    assert_eq!(quote_identifier("my \"table\""), "\"my \"\"table\"\"\"");
+    assert_eq!(to_literal(&FieldValue::RealValue(1.5)).unwrap(), "1.5");
+    assert!(to_literal(&FieldValue::RealValue(f64::NAN)).is_err());
+
+    let sql = bind(
+        "SELECT * FROM \"what?\" WHERE name = ? AND note <> '?' AND lanes > ?",
+        &[FieldValue::StringValue("x' OR '1'='1".to_string()), FieldValue::IntegerValue(2)],
+    ).unwrap();
+    assert_eq!(sql, "SELECT * FROM \"what?\" WHERE name = 'x'' OR ''1''=''1' AND note <> '?' AND lanes > 2");
+
+    assert!(bind("name = ?", &[]).is_err());
+    assert!(bind("name = 'a'", &[FieldValue::IntegerValue(1)]).is_err());
+}
+
+#[test]
+fn test_flatten() {
+    let geom = Geometry::from_wkt(
+        "GEOMETRYCOLLECTION (POINT (1 2), MULTILINESTRING ((0 0, 1 1), (2 2, 3 3)), \
+         GEOMETRYCOLLECTION (POLYGON ((0 0, 1 0, 1 1, 0 0)), GEOMETRYCOLLECTION EMPTY))"
+    ).unwrap();
+    let parts: Vec<String> = geom.flatten().map(|part| part.wkt()).collect();
+    assert_eq!(parts, vec!(
+        "POINT (1 2)",
+        "LINESTRING (0 0,1 1)",
+        "LINESTRING (2 2,3 3)",
+        "POLYGON ((0 0,1 0,1 1,0 0))",
+    ));
+
+    let point = Geometry::from_wkt("POINT (1 2)").unwrap();
+    assert_eq!(point.flatten().count(), 1);
+}
+
+#[test]
+fn test_envelope_of() {
+    use vector::Envelope;
+
+    let envelope = Envelope::of(&Geometry::from_wkt("LINESTRING (1 5, 3 2)").unwrap());
+    assert_eq!(envelope, Envelope::new(1., 3., 2., 5.));
+    assert_eq!((envelope.width(), envelope.height()), (2., 3.));
+    assert!(envelope.contains(3., 2.));
+    assert!(!envelope.contains(0., 2.));
+}
+
+#[test]
+fn test_envelope_set_operations() {
+    use vector::Envelope;
+
+    let a = Envelope::new(0., 10., 0., 10.);
+    let b = Envelope::new(5., 15., -5., 5.);
+    let far = Envelope::new(20., 30., 20., 30.);
+    assert!(a.intersects(&b));
+    assert!(!a.intersects(&far));
+    assert!(a.intersects(&Envelope::new(10., 12., 0., 1.)));
+    assert_eq!(a.intersection(&b), Some(Envelope::new(5., 10., 0., 5.)));
+    assert_eq!(a.intersection(&far), None);
+    assert_eq!(a.union(&b), Envelope::new(0., 15., -5., 10.));
+    assert_eq!(a.buffer(1.), Envelope::new(-1., 11., -1., 11.));
+    assert!(a.contains_envelope(&a.buffer(-1.)));
+    assert!(!a.contains_envelope(&b));
+    assert_eq!(Envelope::of(&a.to_geometry()), a);
+}
+
+#[test]
+fn test_layer_extent() {
+    let mut ds = Dataset::open(fixture!("roads.geojson")).unwrap();
+    let layer = ds.layer(0).unwrap();
+    let mut expected = None;
+    for feature in layer.features() {
+        let envelope = feature.geometry().unwrap().envelope();
+        expected = Some(match expected {
+            None => envelope,
+            Some(extent) => envelope.union(&extent),
+        });
+    }
+    assert_eq!(layer.extent(true), expected);
+}
+
+#[cfg(feature = "testing")]
+#[test]
+fn test_testing_generators() {
+    use vector::Envelope;
+    use vector::testing::{Rng, fishnet, random_points, random_polygons};
+
+    let envelope = Envelope::new(0., 10., 0., 5.);
+    assert_eq!(Rng::new(7).next_u64(), Rng::new(7).next_u64());
+
+    let mut rng = Rng::new(1);
+    for point in random_points(&envelope, 50, &mut rng) {
+        let (x, y, _) = point.get_point(0);
+        assert!(envelope.contains(x, y));
+    }
+    let outer = Geometry::bbox(0., 0., 10., 5.);
+    for polygon in random_polygons(&envelope, 20, 2., &mut rng) {
+        assert!(polygon.intersects(&outer));
+        let bounds = Envelope::of(&polygon);
+        assert!(envelope.contains(bounds.min_x, bounds.min_y));
+        assert!(envelope.contains(bounds.max_x, bounds.max_y));
+    }
+
+    let cells = fishnet(&envelope, 4., 2.);
+    assert_eq!(cells.len(), 9);
+    assert_eq!(Envelope::of(&cells[0]), Envelope::new(0., 4., 3., 5.));
+    assert_eq!(Envelope::of(&cells[8]), Envelope::new(8., 10., 0., 1.));
+}
+
+#[test]
+fn test_grid() {
+    use vector::{fishnet, grid, hex_grid, Envelope};
+
+    let envelope = Envelope::new(0., 10., 0., 5.);
+    let driver = Driver::get("Memory").unwrap();
+    let mut ds = driver.create(Path::new("")).unwrap();
+
+    {
+        let layer = ds.create_layer_ext("grid", None, super::WKB_POLYGON, &[]).unwrap();
+        grid(&envelope, 4., 2., layer).unwrap();
+        assert!(grid(&envelope, 0., 2., layer).is_err());
+        let cells: Vec<Feature> = layer.features().collect();
+        assert_eq!(cells.len(), 9);
+        let last = &cells[8];
+        assert_eq!(last.field("row").unwrap().unwrap().as_int(), 2);
+        assert_eq!(last.field("col").unwrap().unwrap().as_int(), 2);
+        assert_eq!(Envelope::of(&last.geometry().unwrap()), Envelope::new(8., 10., 0., 1.));
+    }
+    assert_eq!(fishnet(&envelope, 4., 2.).unwrap().len(), 9);
+    assert!(fishnet(&envelope, 4., -1.).is_err());
+
+    let layer = ds.create_layer_ext("hex", None, super::WKB_POLYGON, &[]).unwrap();
+    hex_grid(&envelope, 1., layer).unwrap();
+    let hexagons: Vec<Feature> = layer.features().collect();
+    // 8 columns of 4 rows
+    assert_eq!(hexagons.len(), 32);
+    let first = Envelope::of(&hexagons[0].geometry().unwrap());
+    assert!((first.width() - 2.).abs() < 1e-9);
+    assert!((first.height() - 3f64.sqrt()).abs() < 1e-9);
+}
+
+#[test]
+fn test_envelope_tiles() {
+    use vector::{Envelope, TileRange, MAX_TILE_ZOOM};
+
+    let world = Envelope::from_tile(0, 0, 0).unwrap();
+    assert_eq!(world.tile_range(0), Some(TileRange{zoom: 0, min_x: 0, max_x: 0, min_y: 0, max_y: 0}));
+    assert_eq!(world.tile_range(2).unwrap().tile_count(), 16);
+
+    // the north east quarter, edges exactly on tile boundaries
+    let quarter = Envelope::new(0., world.max_x, 0., world.max_y);
+    let range = quarter.tile_range(1).unwrap();
+    assert_eq!(range, TileRange{zoom: 1, min_x: 1, max_x: 1, min_y: 0, max_y: 0});
+    assert_eq!(quarter.tile_range(2).unwrap().tiles().collect::<Vec<_>>(), vec!((2, 0), (3, 0), (2, 1), (3, 1)));
+    assert_eq!(Envelope::from_tile(1, 0, 1), Some(quarter));
+
+    // zoom levels whose tile indices don't fit, and tiles that don't exist
+    let deepest = world.tile_range(MAX_TILE_ZOOM).unwrap();
+    assert_eq!(deepest.max_x, (1 << MAX_TILE_ZOOM) - 1);
+    assert_eq!(deepest.tile_count(), 1 << (2 * MAX_TILE_ZOOM));
+    assert_eq!(world.tile_range(MAX_TILE_ZOOM + 1), None);
+    assert_eq!(world.tile_range(64), None);
+    assert_eq!(Envelope::from_tile(0, 0, 64), None);
+    assert_eq!(Envelope::from_tile(2, 0, 1), None);
+    assert!(Envelope::from_tile(u32::MAX >> 1, 0, MAX_TILE_ZOOM).is_some());
+
+    let polygon = quarter.to_geometry();
+    assert_eq!(Envelope::of(&polygon), quarter);
+
+    let cell = Envelope::from_geohash("u4pruydqqvj").unwrap();
+    assert!(cell.contains(10.40744, 57.64911));
+    assert!(cell.width() < 1e-5);
+    assert!(Envelope::from_geohash("u4a").is_none());
+}
+
+#[test]
+fn test_convert_features() {
+    use std::fs;
+    use vector::{convert_features, OnError};
+
+    {
+        let mut src = Dataset::open(fixture!("roads.geojson")).unwrap();
+        let roads = src.layer(0).unwrap();
+        let driver = Driver::get("Memory").unwrap();
+        let mut ds = driver.create(Path::new("")).unwrap();
+        let layer = ds.create_layer().unwrap();
+        let report = convert_features(roads, layer, OnError::Skip).unwrap();
+        assert_eq!(report.written, 21);
+        assert!(report.errors.is_empty());
+    }
+
+    {
+        // point shapefiles refuse the line strings of every road
+        let driver = Driver::get("ESRI Shapefile").unwrap();
+        let mut ds = driver.create(fixture!("output_points.shp")).unwrap();
+        let layer = ds.create_layer_ext("output_points", None, super::WKB_POINT, &[]).unwrap();
+        let mut src = Dataset::open(fixture!("roads.geojson")).unwrap();
+        let report = convert_features(src.layer(0).unwrap(), layer, OnError::Skip).unwrap();
+        assert_eq!(report.written, 0);
+        assert_eq!(report.errors.len(), 21);
+        assert!(!report.errors[0].message.is_empty());
+
+        let mut src = Dataset::open(fixture!("roads.geojson")).unwrap();
+        assert!(convert_features(src.layer(0).unwrap(), layer, OnError::Abort).is_err());
+    }
+
+    for extension in &["shp", "shx", "dbf"] {
+        let _ = fs::remove_file(fixture!(format!("output_points.{}", extension)));
+    }
+}
+
+#[test]
+fn test_create_feature_coerced() {
+    use std::fs;
+    use GdalError;
+    use vector::{Coercion, CoercionPolicy};
+
+    {
+        let driver = Driver::get("ESRI Shapefile").unwrap();
+        let mut ds = driver.create(fixture!("output_coerced.shp")).unwrap();
+        let layer = ds.create_layer_ext("output_coerced", None, super::WKB_POINT, &[]).unwrap();
+        layer.create_field("name", OFT_STRING, 5, 0).unwrap();
+        layer.create_field("lanes", OFT_INTEGER, 9, 0).unwrap();
+        assert_eq!(layer.defn().fields().next().unwrap().width(), 5);
+
+        let point = || Geometry::from_wkt("POINT (1 2)").unwrap();
+        let values = [FieldValue::StringValue("Strada".to_string()), FieldValue::RealValue(2.5)];
+        match layer.create_feature_coerced(point(), &["name", "lanes"], &values, CoercionPolicy::Error) {
+            Err(GdalError::FieldOverflow{field}) => assert_eq!(field, "name"),
+            _ => panic!("expected an overflow"),
+        }
+        // a missing field fails before the name field is widened
+        let with_missing = [values[0].clone(), FieldValue::IntegerValue(1)];
+        assert!(layer.create_feature_coerced(point(), &["name", "missing"], &with_missing, CoercionPolicy::Widen).is_err());
+        assert_eq!(layer.defn().fields().next().unwrap().width(), 5);
+
+        let coercions = layer.create_feature_coerced(point(), &["name", "lanes"], &values, CoercionPolicy::Truncate).unwrap();
+        assert_eq!(coercions, vec!(
+            Coercion::Truncated{field: "name".to_string(), width: 5},
+            Coercion::RealToInteger{field: "lanes".to_string(), value: 2.5, written: 2},
+        ));
+
+        let coercions = layer.create_feature_coerced(point(), &["name", "lanes"], &values, CoercionPolicy::Widen).unwrap();
+        assert_eq!(coercions, vec!(
+            Coercion::Widened{field: "name".to_string(), width: 6},
+            Coercion::ToReal{field: "lanes".to_string()},
+        ));
+        let short = [FieldValue::StringValue("Cale".to_string()), FieldValue::IntegerValue(1)];
+        assert!(layer.create_feature_coerced(point(), &["name", "lanes"], &short, CoercionPolicy::Error).unwrap().is_empty());
+    }
+
+    {
+        let mut ds = Dataset::open(fixture!("output_coerced.shp")).unwrap();
+        let features: Vec<Feature> = ds.layer(0).unwrap().features().collect();
+        assert_eq!(features.len(), 3);
+        assert_eq!(features[0].field("name").unwrap().unwrap().as_string(), "Strad");
+        assert_eq!(features[1].field("name").unwrap().unwrap().as_string(), "Strada");
+        assert_eq!(features[1].field("lanes").unwrap().unwrap().as_real(), 2.5);
+    }
+
+    for extension in &["shp", "shx", "dbf"] {
+        let _ = fs::remove_file(fixture!(format!("output_coerced.{}", extension)));
+    }
+}
+
+#[test]
+fn test_geojson_precision() {
+    use std::fs;
+    use std::io::Read;
+    use vector::geojson::GeoJsonLayerOptions;
+
+    let geometry = Geometry::from_wkt("POINT (26.123456789 44.987654321)").unwrap();
+    assert_eq!(geometry.json_with_precision(3), "{ \"type\": \"Point\", \"coordinates\": [ 26.123, 44.988 ] }");
+
+    let options = GeoJsonLayerOptions::new().coordinate_precision(2).write_bbox(true);
+    assert_eq!(options.options(), vec!("COORDINATE_PRECISION=2", "WRITE_BBOX=YES"));
+    {
+        let driver = Driver::get("GeoJSON").unwrap();
+        let mut ds = driver.create(fixture!("output_precision.geojson")).unwrap();
+        let layer = ds.create_layer_ext("points", None, super::WKB_POINT, &options.options()).unwrap();
+        layer.create_feature(geometry).unwrap();
+    }
+    let mut json = String::new();
+    fs::File::open(fixture!("output_precision.geojson")).unwrap().read_to_string(&mut json).unwrap();
+    assert!(json.contains("26.12, 44.99"));
+    fs::remove_file(fixture!("output_precision.geojson")).unwrap();
+}
+
+#[test]
+fn test_boolean_operations() {
+    let a = Geometry::bbox(0., 0., 2., 2.);
+    let b = Geometry::bbox(1., 1., 3., 3.);
+    assert_eq!(a.intersection(&b).unwrap()._envelope(), (1., 2., 1., 2.));
+    assert_eq!(a.union(&b).unwrap()._envelope(), (0., 3., 0., 3.));
+    assert_eq!(a.difference(&b).unwrap()._envelope(), (0., 2., 0., 2.));
+    assert_eq!(b.difference(&a).unwrap()._envelope(), (1., 3., 1., 3.));
+    let sym = a.sym_difference(&b).unwrap();
+    assert_eq!(sym._envelope(), (0., 3., 0., 3.));
+    assert!(!sym.intersects(&Geometry::from_wkt("POINT (1.5 1.5)").unwrap()));
+}
+
+#[test]
+fn test_execute_sql() {
+    use GdalError;
+    use super::SqlDialect;
+    use vector::sql::quote_identifier;
+
+    let mut ds = Dataset::open(fixture!("roads.geojson")).unwrap();
+    let name = ds.layer(0).unwrap().name();
+    {
+        let sql = format!("SELECT kind, highway FROM {} WHERE kind = 'path'", quote_identifier(&name));
+        let result = ds.execute_sql(&sql, SqlDialect::Ogr).unwrap().unwrap();
+        let fields: Vec<String> = result.defn().fields().map(|field| field.name()).collect();
+        assert_eq!(fields, vec!("kind", "highway"));
+        assert!(result.features().count() > 0);
+        for feature in result.features() {
+            assert_eq!(feature.field("kind").unwrap().unwrap().as_string(), "path");
+        }
+    }
+    match ds.execute_sql("SELECT FROM nowhere", SqlDialect::Ogr) {
+        Err(GdalError::SqlFailed{message}) => assert!(!message.is_empty()),
+        _ => panic!("expected an SQL error"),
+    };
+}
+
+#[test]
+fn test_execute_sql_filtered() {
+    use super::SqlDialect;
+
+    let bbox = Geometry::bbox(26.1017, 44.4297, 26.1025, 44.4303);
+    let ds = Dataset::open(fixture!("roads.geojson")).unwrap();
+    let result = ds.execute_sql_filtered("SELECT * FROM roads", &bbox, SqlDialect::Ogr).unwrap().unwrap();
+    assert_eq!(result.features().count(), 7);
+
+    // filters on the result layer itself work too
+    result.set_spatial_filter(&Geometry::bbox(0., 0., 1., 1.));
+    assert_eq!(result.features().count(), 0);
+}
+
+#[test]
+fn test_predicates() {
+    let square = Geometry::bbox(0., 0., 2., 2.);
+    let inner = Geometry::bbox(0.5, 0.5, 1.5, 1.5);
+    let neighbour = Geometry::bbox(2., 0., 4., 2.);
+    let shifted = Geometry::bbox(1., 1., 3., 3.);
+    let line = Geometry::from_wkt("LINESTRING (-1 1, 3 1)").unwrap();
+    let far = Geometry::from_wkt("POINT (10 10)").unwrap();
+
+    assert!(square.contains(&inner) && !inner.contains(&square));
+    assert!(inner.within(&square) && !square.within(&inner));
+    assert!(square.touches(&neighbour) && !square.touches(&inner));
+    assert!(line.crosses(&square) && !inner.crosses(&square));
+    assert!(square.overlaps(&shifted) && !square.overlaps(&inner));
+    assert!(square.disjoint(&far) && !square.disjoint(&neighbour));
+    assert!(square.intersects(&neighbour) && !square.intersects(&far));
+}
+
+#[test]
+fn test_is_empty_ring_simple() {
+    let empty = Geometry::from_wkt("POLYGON EMPTY").unwrap();
+    let ring = Geometry::from_wkt("LINESTRING (0 0,1 0,1 1,0 0)").unwrap();
+    let open = Geometry::from_wkt("LINESTRING (0 0,1 0,1 1)").unwrap();
+    let bowtie = Geometry::from_wkt("LINESTRING (0 0,1 1,1 0,0 1,0 0)").unwrap();
+
+    assert!(empty.is_empty() && !ring.is_empty());
+    assert!(ring.is_ring() && !open.is_ring() && !bowtie.is_ring());
+    assert!(ring.is_simple() && open.is_simple() && !bowtie.is_simple());
+}
+
+#[test]
+fn test_buffer() {
+    let point = Geometry::from_wkt("POINT (0 0)").unwrap();
+    let circle = point.buffer(1., 8).unwrap();
+    let (min_x, max_x, min_y, max_y) = circle._envelope();
+    assert!((min_x + 1.).abs() < 1e-9 && (max_x - 1.).abs() < 1e-9);
+    assert!((min_y + 1.).abs() < 1e-9 && (max_y - 1.).abs() < 1e-9);
+    let ring = unsafe { circle._get_geometry(0) };
+    assert_eq!(ring.get_point_vec().len(), 4 * 8 + 1);
+
+    let square = Geometry::bbox(0., 0., 4., 4.);
+    assert_eq!(square.buffer(-1., 8).unwrap()._envelope(), (1., 3., 1., 3.));
+    assert!(circle.contains(&Geometry::from_wkt("POINT (0.5 0.5)").unwrap()));
+}
+
+#[test]
+fn test_simplify() {
+    let line = Geometry::from_wkt("LINESTRING (0 0, 1 0.05, 2 -0.05, 3 0, 3 3)").unwrap();
+    assert_eq!(line.simplify(0.1).unwrap().wkt(), "LINESTRING (0 0,3 0,3 3)");
+    assert_eq!(line.simplify(0.01).unwrap().get_point_vec().len(), 5);
+
+    // a thin triangle collapses without topology preservation
+    let triangle = Geometry::from_wkt("POLYGON ((0 0, 10 0, 5 0.1, 0 0))").unwrap();
+    assert!(triangle.simplify(1.).unwrap().wkt().contains("EMPTY"));
+    let preserved = triangle.simplify_preserving_topology(1.).unwrap();
+    assert_eq!(unsafe { preserved._get_geometry(0) }.get_point_vec().len(), 4);
+}
+
+#[test]
+fn test_area_length() {
+    let square = Geometry::bbox(0., 0., 2., 3.);
+    assert_eq!(square.area(), 6.);
+    let holed = Geometry::from_wkt("POLYGON ((0 0, 4 0, 4 4, 0 4, 0 0), (1 1, 2 1, 2 2, 1 2, 1 1))").unwrap();
+    assert_eq!(holed.area(), 15.);
+
+    let line = Geometry::from_wkt("MULTILINESTRING ((0 0, 3 4), (0 0, 0 1))").unwrap();
+    assert_eq!(line.length(), 6.);
+    assert_eq!(line.area(), 0.);
+    assert_eq!(Geometry::from_wkt("POINT (1 1)").unwrap().area(), 0.);
+}
+
+#[test]
+fn test_centroid_point_on_surface() {
+    let square = Geometry::bbox(0., 0., 2., 2.);
+    assert_eq!(square.centroid().unwrap().wkt(), "POINT (1 1)");
+
+    // the centroid of a U shape lies in its gap
+    let u = Geometry::from_wkt("POLYGON ((0 0, 3 0, 3 3, 2 3, 2 1, 1 1, 1 3, 0 3, 0 0))").unwrap();
+    let centroid = u.centroid().unwrap();
+    assert!(!u.contains(&centroid));
+    let point = u.point_on_surface().unwrap();
+    assert!(u.contains(&point));
+    assert_eq!(point.get_point_vec().len(), 1);
+}
+
+#[test]
+fn test_gml_kml() {
+    use super::GmlFormat;
+
+    let point = Geometry::from_wkt("POINT (1 2)").unwrap();
+    assert_eq!(point.gml(GmlFormat::Gml2), "<gml:Point><gml:coordinates>1,2</gml:coordinates></gml:Point>");
+    assert_eq!(point.gml(GmlFormat::Gml3), "<gml:Point><gml:pos>1 2</gml:pos></gml:Point>");
+    assert_eq!(point.kml(), "<Point><coordinates>1,2</coordinates></Point>");
+
+    let polygon = Geometry::bbox(0., 0., 1., 1.);
+    for format in &[GmlFormat::Gml2, GmlFormat::Gml3, GmlFormat::Gml32] {
+        let parsed = Geometry::from_gml(&polygon.gml(*format)).unwrap();
+        assert_eq!(parsed.wkt(), polygon.wkt());
+    }
+    assert!(Geometry::from_gml("<gml:Point>").is_none());
+}
+
+#[test]
+fn test_geometry_envelope() {
+    use super::{Envelope, Envelope3D};
+
+    let line = Geometry::from_wkt("LINESTRING (3 -1, 0 4, 2 2)").unwrap();
+    assert_eq!(line.envelope(), Envelope::new(0., 3., -1., 4.));
+    assert_eq!(line.envelope_3d(), Envelope3D{min_x: 0., max_x: 3., min_y: -1., max_y: 4., min_z: 0., max_z: 0.});
+
+    let line_z = Geometry::from_wkt("LINESTRING Z (3 -1 10, 0 4 -5)").unwrap();
+    let envelope = line_z.envelope_3d();
+    assert_eq!((envelope.min_z, envelope.max_z), (-5., 10.));
+    assert_eq!(envelope.to_2d(), line_z.envelope());
+}
+
+#[test]
+fn test_geometry_type() {
+    use super::{GeometryType, WKB_MULTIPOLYGON};
+
+    let point = Geometry::from_wkt("POINT (1 2)").unwrap();
+    assert_eq!(point.geometry_type(), GeometryType::Point);
+    assert_eq!(point.wkb_type(), GeometryType::Point.to_wkb());
+
+    let point_z = Geometry::from_wkt("POINT Z (1 2 3)").unwrap();
+    assert_eq!(point_z.geometry_type(), GeometryType::Point);
+    assert!(point_z.wkb_type() != GeometryType::Point.to_wkb());
+    assert_eq!(GeometryType::flatten(point_z.wkb_type()), 1);
+    // ISO codes for MultiPolygon ZM and LineString M
+    assert_eq!(GeometryType::from_wkb(3006), GeometryType::MultiPolygon);
+    assert_eq!(GeometryType::from_wkb(2002), GeometryType::LineString);
+    assert_eq!(GeometryType::from_wkb(9999), GeometryType::Other(999));
+
+    let multi = Geometry::from_wkt("MULTIPOLYGON (((0 0, 1 0, 1 1, 0 0)))").unwrap();
+    assert_eq!(multi.geometry_type().to_wkb(), WKB_MULTIPOLYGON);
+    assert!(multi.geometry_type().is_collection());
+    assert!(!Geometry::bbox(0., 0., 1., 1.).geometry_type().is_collection());
+    assert_eq!(Geometry::from_wkt("CIRCULARSTRING (0 0, 1 1, 2 0)").unwrap().geometry_type(), GeometryType::CircularString);
+}
+
+#[test]
+fn test_sub_geometries() {
+    let multi = Geometry::from_wkt("MULTIPOLYGON (((0 0, 1 0, 1 1, 0 0)), ((5 5, 6 5, 6 6, 5 5), (5.1 5.1, 5.2 5.1, 5.2 5.2, 5.1 5.1)))").unwrap();
+    assert_eq!(multi.geometry_count(), 2);
+    let rings: Vec<usize> = multi.sub_geometries().map(|polygon| polygon.geometry_count()).collect();
+    assert_eq!(rings, vec!(1, 2));
+    assert_eq!(multi.sub_geometry(1).unwrap().envelope().min_x, 5.);
+    assert!(multi.sub_geometry(2).is_none());
+
+    let point = Geometry::from_wkt("POINT (1 2)").unwrap();
+    assert_eq!(point.geometry_count(), 0);
+    assert_eq!(point.sub_geometries().count(), 0);
+}
+
+#[test]
+fn test_dataset_driver() {
+    let ds = Dataset::open(fixture!("roads.geojson")).unwrap();
+    let driver = ds.driver();
+    assert_eq!(driver.short_name(), "GeoJSON");
+    assert_eq!(driver.long_name(), "GeoJSON");
+    assert_eq!(ds.driver_name(), "GeoJSON");
+    assert_eq!(Driver::get("Memory").unwrap().long_name(), "Memory");
+}
+
+#[test]
+fn test_add_point() {
+    use super::{GeometryType, WKB_LINESTRING, WKB_POLYGON};
+    let mut line = Geometry::empty(WKB_LINESTRING);
+    line.add_point_2d((0.0, 0.0));
+    line.add_point_2d((1.0, 0.0));
+    assert_eq!(line.wkt(), "LINESTRING (0 0,1 0)");
+    line.add_point((1.0, 1.0, 5.0));
+    assert_eq!(line.get_point_vec(), vec![(0.0, 0.0, 0.0), (1.0, 0.0, 0.0), (1.0, 1.0, 5.0)]);
+    line.set_point(0, (0.0, 0.0, 2.0));
+    assert_eq!(line.get_point(0), (0.0, 0.0, 2.0));
+
+    let mut ring = Geometry::empty(GeometryType::LinearRing.to_wkb());
+    for &p in &[(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 0.0)] {
+        ring.add_point_2d(p);
+    }
+    let mut polygon = Geometry::empty(WKB_POLYGON);
+    polygon.add_geometry(ring);
+    assert_eq!(polygon.area(), 0.5);
+}
+
+#[test]
+fn test_distance() {
+    let a = Geometry::from_wkt("POINT (0 0)").unwrap();
+    let b = Geometry::from_wkt("POINT (3 4)").unwrap();
+    assert_eq!(a.distance(&b), 5.0);
+    let line = Geometry::from_wkt("LINESTRING (-1 2,1 2)").unwrap();
+    assert_eq!(a.distance(&line), 2.0);
+    let polygon = Geometry::from_wkt("POLYGON ((-1 -1,1 -1,1 1,-1 1,-1 -1))").unwrap();
+    assert_eq!(a.distance(&polygon), 0.0);
+
+    let c = Geometry::from_wkt("POINT (0 0 0)").unwrap();
+    let d = Geometry::from_wkt("POINT (2 3 6)").unwrap();
+    assert_eq!(c.distance_3d(&d), 7.0);
+}
+
+#[test]
+fn test_open_flags() {
+    use OpenFlags;
+    let ds = Dataset::open(fixture!("roads.geojson")).unwrap();
+    assert_eq!(ds.open_flags(), OpenFlags::VECTOR);
+    assert!(ds.is_vector());
+    assert!(!ds.is_raster());
+    assert!(!ds.is_update());
+
+    let ds = Driver::get("Memory").unwrap().create(Path::new("")).unwrap();
+    assert!(ds.is_update());
+    assert!(ds.open_flags().contains(OpenFlags::VECTOR | OpenFlags::UPDATE));
+}
+
+#[test]
+fn test_layer_geometry_type() {
+    use super::{GeometryType, WKB_POINT};
+    let mut ds = Dataset::open(fixture!("roads.geojson")).unwrap();
+    let layer = ds.layer(0).unwrap();
+    assert_eq!(layer.geometry_type(), GeometryType::LineString);
+    assert!(!layer.has_z());
+    assert!(!layer.has_m());
+
+    let mut ds = Driver::get("Memory").unwrap().create(Path::new("")).unwrap();
+    let point_25d = (WKB_POINT as u32 | 0x80000000) as i32;
+    let layer = ds.create_layer_ext("points_z", None, point_25d, &[]).unwrap();
+    assert_eq!(layer.geometry_type(), GeometryType::Point);
+    assert_eq!(layer.wkb_type(), point_25d);
+    assert!(layer.has_z());
+    assert!(!layer.has_m());
+    let layer = ds.create_layer_ext("points_zm", None, 3001, &[]).unwrap();
+    assert_eq!(layer.geometry_type(), GeometryType::Point);
+    assert!(layer.has_z());
+    assert!(layer.has_m());
+}
+
+#[test]
+fn test_geometry_transform() {
+    use spatial_ref::{SpatialRef, CoordTransform};
+    let wgs84 = SpatialRef::from_epsg(4326).unwrap();
+    let mercator = SpatialRef::from_epsg(3857).unwrap();
+    let transform = CoordTransform::new(&wgs84, &mercator).unwrap();
+    let mut line = Geometry::from_wkt("LINESTRING (0 0,180 0)").unwrap();
+    line.transform(&transform).unwrap();
+    let (x, y, _) = line.get_point(1);
+    assert!((x - 20037508.34).abs() < 0.01 && y.abs() < 1e-6);
+
+    // without a spatial reference, there's nothing to transform from
+    let mut point = Geometry::from_wkt("POINT (0 0)").unwrap();
+    assert!(point.transform_to(&mercator).is_err());
+
+    let mut ds = Dataset::open(fixture!("roads.geojson")).unwrap();
+    let layer = ds.layer(0).unwrap();
+    let feature = layer.features().next().unwrap();
+    let mut geometry = feature.geometry().unwrap().to_owned();
+    geometry.transform_to(&mercator).unwrap();
+    let (x, y, _) = geometry.get_point(0);
+    assert!((x - 2905653.29).abs() < 0.01 && (y - 5532271.45).abs() < 0.01);
+}
+
+#[test]
+fn test_geometry_spatial_ref() {
+    use spatial_ref::SpatialRef;
+    let wgs84 = SpatialRef::from_epsg(4326).unwrap();
+    let mercator = SpatialRef::from_epsg(3857).unwrap();
+
+    let mut point = Geometry::from_wkt("POINT (10 20)").unwrap();
+    assert!(point.spatial_ref().is_none());
+    {
+        let srs = SpatialRef::from_epsg(4326).unwrap();
+        point.set_spatial_ref(&srs);
+    }
+    assert!(point.spatial_ref().unwrap().is_same(&wgs84));
+    assert!(point.clone().spatial_ref().unwrap().is_same(&wgs84));
+    point.transform_to(&mercator).unwrap();
+    assert!(point.spatial_ref().unwrap().is_same(&mercator));
+
+    let mut ds = Dataset::open(fixture!("roads.geojson")).unwrap();
+    let layer = ds.layer(0).unwrap();
+    let feature = layer.features().next().unwrap();
+    assert!(feature.geometry().unwrap().spatial_ref().unwrap().is_same(&wgs84));
+}
+
+#[test]
+fn test_measured_points() {
+    use super::{GeometryType, WKB_LINESTRING};
+    let mut route = Geometry::empty(WKB_LINESTRING);
+    assert!(!route.is_measured());
+    route.add_point_m((0.0, 0.0, 0.0));
+    route.add_point_m((3.0, 4.0, 5.0));
+    assert!(route.is_measured());
+    assert_eq!(route.get_point_vec_zm(), vec![(0.0, 0.0, 0.0, 0.0), (3.0, 4.0, 0.0, 5.0)]);
+    route.set_point_m(1, (3.0, 4.0, 6.0));
+    assert_eq!(route.get_point_zm(1), (3.0, 4.0, 0.0, 6.0));
+    route.add_point_zm((3.0, 5.0, 10.0, 7.0));
+    assert_eq!(route.get_point_zm(2), (3.0, 5.0, 10.0, 7.0));
+    assert!(GeometryType::has_m(route.wkb_type()) && GeometryType::has_z(route.wkb_type()));
+    route.set_point_zm(0, (0.0, 0.0, 1.0, -1.0));
+    assert_eq!(route.get_point_zm(0), (0.0, 0.0, 1.0, -1.0));
+
+    route.set_measured(false);
+    assert!(!route.is_measured());
+    assert_eq!(route.get_point_zm(2), (3.0, 5.0, 10.0, 0.0));
+
+    let parsed = Geometry::from_wkt("LINESTRING M (0 0 1,1 1 2)").unwrap();
+    assert!(parsed.is_measured());
+    assert_eq!(parsed.get_point_zm(1), (1.0, 1.0, 0.0, 2.0));
+}
+
+#[test]
+fn test_geometry_eq_and_format() {
+    let point = Geometry::from_wkt("POINT (1 2)").unwrap();
+    assert_eq!(point, Geometry::from_wkt("POINT (1.0 2.00)").unwrap());
+    assert!(point != Geometry::from_wkt("POINT (1 3)").unwrap());
+    assert!(point != Geometry::from_wkt("MULTIPOINT (1 2)").unwrap());
+    let square = Geometry::from_wkt("POLYGON ((0 0,1 0,1 1,0 1,0 0))").unwrap();
+    assert_eq!(square, Geometry::from_wkt("POLYGON ((0 0, 1 0, 1 1, 0 1, 0 0))").unwrap());
+    assert_eq!(square, square.clone());
+    // same shape, other start vertex
+    let rotated = Geometry::from_wkt("POLYGON ((1 0,1 1,0 1,0 0,1 0))").unwrap();
+    assert!(square != rotated);
+    assert!(square.sym_difference(&rotated).unwrap().is_empty());
+
+    assert_eq!(format!("{}", point), "POINT (1 2)");
+    assert_eq!(format!("{:?}", point), "Geometry(POINT (1 2))");
+
+    let mut ds = Dataset::open(fixture!("roads.geojson")).unwrap();
+    let layer = ds.layer(0).unwrap();
+    let feature = layer.features().next().unwrap();
+    let geometry = feature.geometry().unwrap();
+    assert_eq!(*geometry, geometry.to_owned());
+}
+
+#[test]
+fn test_swap_xy_and_dimension() {
+    let mut line = Geometry::from_wkt("LINESTRING (44.4 26.1,44.5 26.2)").unwrap();
+    line.swap_xy();
+    assert_eq!(line, Geometry::from_wkt("LINESTRING (26.1 44.4,26.2 44.5)").unwrap());
+    assert!(!line.is_3d());
+    assert_eq!(line.coordinate_dimension(), 2);
+
+    line.set_3d(true);
+    assert!(line.is_3d());
+    assert_eq!(line.coordinate_dimension(), 3);
+    assert_eq!(line.get_point(1), (26.2, 44.5, 0.0));
+    line.set_measured(true);
+    assert_eq!(line.coordinate_dimension(), 4);
+
+    let mut measured = Geometry::from_wkt("POINT M (1 2 3)").unwrap();
+    assert!(!measured.is_3d());
+    assert_eq!(measured.coordinate_dimension(), 3);
+    measured.swap_xy();
+    assert_eq!(measured.get_point_zm(0), (2.0, 1.0, 0.0, 3.0));
+}
+
+#[test]
+fn test_linear_geometry() {
+    use super::GeometryType;
+
+    // half circle of radius 1 around the origin
+    let arc = Geometry::from_wkt("CIRCULARSTRING (-1 0,0 1,1 0)").unwrap();
+    assert!(arc.has_curve_geometry());
+    let line = arc.get_linear_geometry(10.);
+    assert!(!line.has_curve_geometry());
+    assert_eq!(line.geometry_type(), GeometryType::LineString);
+    let points = line.get_point_vec();
+    assert!(points.len() >= 19);
+    for &(x, y, _) in &points {
+        assert!((x.hypot(y) - 1.).abs() < 1e-9);
+    }
+    assert!((line.length() - ::std::f64::consts::PI).abs() < 0.01);
+
+    let polygon = Geometry::from_wkt("CURVEPOLYGON (COMPOUNDCURVE (CIRCULARSTRING (0 0,1 1,2 0),(2 0,0 0)))").unwrap();
+    assert!(polygon.has_curve_geometry());
+    let linear = polygon.get_linear_geometry(0.);
+    assert_eq!(linear.geometry_type(), GeometryType::Polygon);
+    assert!((linear.area() - ::std::f64::consts::PI / 2.).abs() < 0.01);
+
+    let plain = Geometry::from_wkt("LINESTRING (0 0,1 1)").unwrap();
+    assert!(!plain.has_curve_geometry());
+    assert_eq!(plain.get_linear_geometry(0.), plain);
+}
+
+#[test]
+fn test_datetime_fields() {
+    use std::fs;
+    use super::{DateTime, TimeZone, OFT_DATETIME};
+
+    let summer = DateTime::new(2017, 6, 21, 12, 30, 15.5, TimeZone::Offset(120));
+    assert_eq!(summer.to_string(), "2017-06-21T12:30:15.500+02:00");
+    assert_eq!(DateTime::new(2017, 1, 2, 3, 4, 5., TimeZone::Utc).to_string(), "2017-01-02T03:04:05Z");
+    assert_eq!(DateTime::new(2017, 1, 2, 3, 4, 5., TimeZone::Offset(-570)).to_string(), "2017-01-02T03:04:05-09:30");
+    assert_eq!(DateTime::new(2017, 1, 2, 3, 4, 5., TimeZone::Local).to_string(), "2017-01-02T03:04:05");
+    assert_eq!(TimeZone::from_tz_flag(108), TimeZone::Offset(120));
+    assert_eq!(TimeZone::Offset(-570).to_tz_flag(), 62);
+    assert_eq!(TimeZone::Local.utc_offset(), None);
+
+    let driver = Driver::get("Memory").unwrap();
+    let mut ds = driver.create(Path::new("")).unwrap();
+    let layer = ds.create_layer().unwrap();
+    layer.create_defn_fields(&[("seen", OFT_DATETIME)]).unwrap();
+    layer.create_feature_fields(Geometry::from_wkt("POINT (1 2)").unwrap(), &["seen"], &[FieldValue::DateTimeValue(summer)]).unwrap();
+    let feature = layer.features().next().unwrap();
+    assert_eq!(feature.field("seen").unwrap().unwrap().as_datetime(), summer);
+
+    let _ = fs::remove_file(fixture!("output_datetime.gpkg"));
+    {
+        let driver = Driver::get("GPKG").unwrap();
+        let mut ds = driver.create(fixture!("output_datetime.gpkg")).unwrap();
+        let layer = ds.create_layer().unwrap();
+        layer.create_defn_fields(&[("seen", OFT_DATETIME)]).unwrap();
+        layer.create_feature_fields(Geometry::from_wkt("POINT (1 2)").unwrap(), &["seen"], &[FieldValue::DateTimeValue(summer)]).unwrap();
+    }
+    {
+        let mut ds = Dataset::open(fixture!("output_datetime.gpkg")).unwrap();
+        let layer = ds.layer(0).unwrap();
+        let feature = layer.features().next().unwrap();
+        let seen = feature.field("seen").unwrap().unwrap().as_datetime();
+        // GeoPackage may store the instant in UTC, but it must stay the same
+        let offset = seen.time_zone.utc_offset().unwrap();
+        assert_eq!((seen.year, seen.month, seen.day), (2017, 6, 21));
+        assert_eq!(seen.hour as i32 * 60 + seen.minute as i32 - offset, 10 * 60 + 30);
+        assert!((seen.second - 15.5).abs() < 0.01);
+    }
+    fs::remove_file(fixture!("output_datetime.gpkg")).unwrap();
+}
+
+#[test]
+fn test_unset_datetime_fields() {
+    use super::OFT_DATETIME;
+
+    let driver = Driver::get("Memory").unwrap();
+    let mut ds = driver.create(Path::new("")).unwrap();
+    let layer = ds.create_layer().unwrap();
+    layer.create_defn_fields(&[("seen", OFT_DATETIME), ("name", OFT_STRING)]).unwrap();
+    layer.create_feature_fields(Geometry::from_wkt("POINT (1 2)").unwrap(), &["name"], &[FieldValue::StringValue("a".to_string())]).unwrap();
+    let feature = layer.features().next().unwrap();
+    assert_eq!(feature.field("seen").unwrap().unwrap().as_string(), "");
+    let fields: Vec<_> = feature.fields().collect();
+    assert!(fields[0].1.is_none());
+}
+
+#[cfg(feature = "chrono")]
+#[test]
+fn test_chrono_datetime() {
+    use std::convert::TryFrom;
+    use chrono;
+    use super::{DateTime, TimeZone};
+
+    let summer = DateTime::new(2017, 6, 21, 12, 30, 15.5, TimeZone::Offset(120));
+    let converted = chrono::DateTime::<chrono::FixedOffset>::try_from(summer).unwrap();
+    assert_eq!(converted, chrono::DateTime::parse_from_rfc3339("2017-06-21T12:30:15.500+02:00").unwrap());
+    assert_eq!(DateTime::from(converted), summer);
+    let utc = chrono::DateTime::parse_from_rfc3339("2017-01-02T03:04:05Z").unwrap();
+    assert_eq!(DateTime::from(utc), DateTime::new(2017, 1, 2, 3, 4, 5., TimeZone::Utc));
+
+    let local = DateTime::new(2017, 1, 2, 3, 4, 5., TimeZone::Local);
+    assert!(chrono::DateTime::<chrono::FixedOffset>::try_from(local).is_err());
+    assert_eq!(chrono::NaiveDateTime::try_from(local).unwrap(), chrono::NaiveDate::from_ymd_opt(2017, 1, 2).unwrap().and_hms_opt(3, 4, 5).unwrap());
+    assert!(chrono::NaiveDateTime::try_from(DateTime::new(2017, 2, 30, 0, 0, 0., TimeZone::Utc)).is_err());
+}
+
+#[test]
+fn test_binary_fields() {
+    use super::OFT_BINARY;
+
+    let driver = Driver::get("Memory").unwrap();
+    let mut ds = driver.create(Path::new("")).unwrap();
+    let layer = ds.create_layer().unwrap();
+    layer.create_defn_fields(&[("thumbnail", OFT_BINARY), ("name", OFT_STRING)]).unwrap();
+    layer.create_feature(Geometry::from_wkt("POINT (1 2)").unwrap()).unwrap();
+
+    let mut feature = layer.features().next().unwrap();
+    assert_eq!(feature.field_as_bytes("thumbnail"), Some(&[][..]));
+    assert_eq!(feature.field_as_bytes("missing"), None);
+    let thumbnail = [0x89, b'P', b'N', b'G', 0, 255];
+    feature.set_field_bytes("thumbnail", &thumbnail).unwrap();
+    assert!(feature.set_field_bytes("missing", &thumbnail).is_err());
+    assert_eq!(feature.field_as_bytes("thumbnail"), Some(&thumbnail[..]));
+    assert_eq!(feature.field_as_bytes("name"), Some(&[][..]));
+
+    let mut copy_ds = driver.create(Path::new("")).unwrap();
+    let copy_layer = copy_ds.create_layer().unwrap();
+    copy_layer.create_defn_fields(&[("thumbnail", OFT_BINARY)]).unwrap();
+    copy_layer.create_feature_from(&feature).unwrap();
+    let copy = copy_layer.features().next().unwrap();
+    assert_eq!(copy.field_as_bytes("thumbnail"), Some(&thumbnail[..]));
+}
+
+#[test]
+fn test_geodesic_area_and_length() {
+    use spatial_ref::SpatialRef;
+
+    let wgs84 = SpatialRef::from_epsg(4326).unwrap();
+    let mut square = Geometry::from_wkt("POLYGON ((0 0,1 0,1 1,0 1,0 0))").unwrap();
+    assert!(square.geodesic_area().is_err());
+    square.set_spatial_ref(&wgs84);
+    assert!((square.geodesic_area().unwrap() / 12308.464e6 - 1.).abs() < 1e-3);
+    assert!((square.geodesic_length().unwrap() - 443770.917).abs() < 1.);
+
+    let mut equator = Geometry::from_wkt("LINESTRING (0 0,1 0)").unwrap();
+    equator.set_spatial_ref(&wgs84);
+    assert!((equator.geodesic_length().unwrap() - 111319.491).abs() < 0.01);
+    assert_eq!(equator.geodesic_area().unwrap(), 0.);
+
+    let mut utm = square.clone();
+    utm.transform_to(&SpatialRef::from_epsg(32631).unwrap()).unwrap();
+    assert!((utm.geodesic_area().unwrap() / square.geodesic_area().unwrap() - 1.).abs() < 1e-3);
+
+    // a degree of latitude is shorter than one of longitude on the equator
+    let lon_lat = SpatialRef::from_proj4("+proj=longlat +datum=WGS84 +no_defs").unwrap();
+    let mut meridian = Geometry::from_wkt("LINESTRING (0 0,0 1)").unwrap();
+    meridian.set_spatial_ref(&lon_lat);
+    assert!((meridian.geodesic_length().unwrap() - 110574.389).abs() < 0.01);
+}
+
+#[test]
+fn test_sharded_writer() {
+    use std::fs;
+    use super::ShardedWriter;
+
+    let out_dir = fixture!("shards").to_path_buf();
+    fs::create_dir_all(&out_dir).unwrap();
+    let mut ds = Dataset::open(fixture!("roads.geojson")).unwrap();
+    let shards = {
+        let layer = ds.layer(0).unwrap();
+        let mut writer = ShardedWriter::new("GeoJSON", &out_dir, "roads", "geojson").unwrap()
+            .max_features(8)
+            .schema_from(layer);
+        assert_eq!(writer.write_layer(layer).unwrap(), 21);
+        assert_eq!(writer.shards().len(), 3);
+        writer.finish()
+    };
+    assert_eq!(shards.iter().map(|shard| shard.features).collect::<Vec<_>>(), vec![8, 8, 5]);
+    assert!(shards[2].path.ends_with("roads_2.geojson"));
+    for shard in &shards {
+        let mut part = Dataset::open(&shard.path).unwrap();
+        let layer = part.layer(0).unwrap();
+        assert_eq!(layer.features().count(), shard.features);
+        assert!(layer.defn().fields().any(|field| field.name() == "highway"));
+    }
+    fs::remove_dir_all(&out_dir).unwrap();
+}
+
+#[cfg(feature = "arrow")]
+#[test]
+fn test_write_arrow_batch() {
+    use std::ptr::null;
+    use libc::{c_char, c_void};
+    use super::WKB_NONE;
+    use super::arrow::{ArrowArray, ArrowSchema};
+
+    // the buffers are borrowed from the stack, nothing to free
+    unsafe extern "C" fn release_schema(schema: *mut ArrowSchema) { (*schema).release = None; }
+    unsafe extern "C" fn release_array(array: *mut ArrowArray) { (*array).release = None; }
+
+    // a batch with a nullable int32 column `value`
+    let mut value_schema = ArrowSchema{
+        format: b"i\0".as_ptr() as *const c_char,
+        name: b"value\0".as_ptr() as *const c_char,
+        flags: 2,
+        release: Some(release_schema),
+        ..ArrowSchema::empty()
+    };
+    let mut schema_children = [&mut value_schema as *mut ArrowSchema];
+    let mut schema = ArrowSchema{
+        format: b"+s\0".as_ptr() as *const c_char,
+        name: b"\0".as_ptr() as *const c_char,
+        n_children: 1,
+        children: schema_children.as_mut_ptr(),
+        release: Some(release_schema),
+        ..ArrowSchema::empty()
+    };
+    let data: [i32; 3] = [1, 2, 3];
+    let mut value_buffers = [null(), data.as_ptr() as *const c_void];
+    let mut value_array = ArrowArray{
+        length: 3,
+        n_buffers: 2,
+        buffers: value_buffers.as_mut_ptr(),
+        release: Some(release_array),
+        ..ArrowArray::empty()
+    };
+    let mut array_children = [&mut value_array as *mut ArrowArray];
+    let mut struct_buffers = [null()];
+    let mut array = ArrowArray{
+        length: 3,
+        n_buffers: 1,
+        buffers: struct_buffers.as_mut_ptr(),
+        n_children: 1,
+        children: array_children.as_mut_ptr(),
+        release: Some(release_array),
+        ..ArrowArray::empty()
+    };
+
+    let driver = Driver::get("Memory").unwrap();
+    let mut ds = driver.create(Path::new("")).unwrap();
+    let layer = ds.create_layer_ext("values", None, WKB_NONE, &[]).unwrap();
+    layer.create_fields_from_arrow_schema(&schema, &[]).unwrap();
+    layer.write_arrow_batch(&schema, &mut array, &[]).unwrap();
+    if let Some(release) = array.release {
+        unsafe { release(&mut array) };
+    }
+    unsafe { release_schema(&mut schema) };
+    let values: Vec<i32> = layer.features()
+        .map(|feature| feature.field("value").unwrap().unwrap().as_int())
+        .collect();
+    assert_eq!(values, vec!(1, 2, 3));
+}
@@ -0,0 +1,146 @@
+//! XLSX open options and writing
+//!
+//! Spreadsheets have no geometry type, so geometries are written to and
+//! read back from a WKT text column.
+//!
+//! ```no_run
+//! use std::path::Path;
+//! use gdal::vector::Geometry;
+//! use gdal::vector::xlsx::{Headers, XlsxOpenOptions, write_xlsx};
+//!
+//! let mut sheets = XlsxOpenOptions::new()
+//!     .headers(Headers::Force)
+//!     .detect_types(true)
+//!     .open(Path::new("places.xlsx"))
+//!     .unwrap();
+//! let sheet = sheets.layer(0).unwrap();
+//! for feature in sheet.features() {
+//!     let wkt = feature.field("WKT").unwrap().unwrap().as_string();
+//!     println!("{}", Geometry::from_wkt(&wkt).unwrap().wkt());
+//! }
+//! write_xlsx(sheet, Path::new("copy.xlsx"), Some("WKT")).unwrap();
+//! ```
+
+use std::path::Path;
+use libc::c_int;
+use utils::_cstring;
+use vector::{ogr, Dataset, Driver, Layer, OFT_STRING, WKB_NONE};
+use GdalError;
+
+/// Whether the first row of a sheet holds the field names, value of the
+/// `HEADERS` open option.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Headers {
+    Force,
+    Disable,
+    /// Guess from the types of the first two rows, the GDAL default.
+    Auto,
+}
+
+/// Builder for XLSX open options, rendered with `options()` to the string
+/// list expected by `Dataset::open_with_options`, or used directly with
+/// `open`. Every sheet of the file is a layer.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct XlsxOpenOptions {
+    headers: Option<Headers>,
+    detect_types: Option<bool>,
+}
+
+impl XlsxOpenOptions {
+    pub fn new() -> XlsxOpenOptions {
+        return XlsxOpenOptions::default();
+    }
+
+    pub fn headers(mut self, headers: Headers) -> XlsxOpenOptions {
+        self.headers = Some(headers);
+        return self;
+    }
+
+    /// Whether field types are detected from the cell types, which GDAL
+    /// does by default, or every field is read as a string.
+    pub fn detect_types(mut self, detect: bool) -> XlsxOpenOptions {
+        self.detect_types = Some(detect);
+        return self;
+    }
+
+    pub fn options(&self) -> Vec<String> {
+        let mut options = Vec::new();
+        if let Some(headers) = self.headers {
+            let value = match headers {
+                Headers::Force => "FORCE",
+                Headers::Disable => "DISABLE",
+                Headers::Auto => "AUTO",
+            };
+            options.push(format!("HEADERS={}", value));
+        }
+        if let Some(detect) = self.detect_types {
+            options.push(format!("FIELD_TYPES={}", if detect { "AUTO" } else { "STRING" }));
+        }
+        return options;
+    }
+
+    /// Open the XLSX file at `path` with these options.
+    pub fn open(&self, path: &Path) -> Option<Dataset> {
+        return Dataset::open_with_options(path, &["XLSX".to_string()], &self.options());
+    }
+}
+
+/// Write the features of `layer` to a sheet named like the layer in a new
+/// XLSX file at `path`, with the same fields. With `wkt_column`, the
+/// geometries are written as WKT to a text field of that name, left empty
+/// for features without a geometry; otherwise they are dropped. Features
+/// are read from the current position of the layer, like with
+/// `csv::write_csv`.
+pub fn write_xlsx(layer: &Layer, path: &Path, wkt_column: Option<&str>) -> Result<(), GdalError> {
+    let driver = match Driver::get("XLSX") {
+        Some(driver) => driver,
+        None => return Err(GdalError::CallFailed{desc: "XLSX driver not available"}),
+    };
+    let mut fields: Vec<(String, c_int)> = layer.defn().fields()
+        .map(|field| (field.name(), field.field_type()))
+        .collect();
+    if let Some(wkt_column) = wkt_column {
+        if fields.iter().any(|field| field.0 == wkt_column) {
+            return Err(GdalError::CallFailed{desc: "the WKT column clashes with a field"});
+        }
+        fields.push((wkt_column.to_string(), OFT_STRING));
+    }
+
+    let mut dataset = match driver.create(path) {
+        Some(dataset) => dataset,
+        None => return Err(GdalError::CallFailed{desc: "failed to create XLSX file"}),
+    };
+    let output = dataset.create_layer_ext(&layer.name(), None, WKB_NONE, &[])?;
+    let fields_def: Vec<(&str, c_int)> = fields.iter()
+        .map(|&(ref name, field_type)| (name.as_str(), field_type))
+        .collect();
+    output.create_defn_fields(&fields_def)?;
+
+    let wkt_id = fields.len() as c_int - 1;
+    for feature in layer.features() {
+        if wkt_column.is_none() {
+            output.create_feature_from(&feature)?;
+            continue;
+        }
+        let c_wkt = match feature.geometry() {
+            Some(geometry) => Some(_cstring(&geometry.wkt())?),
+            None => None,
+        };
+        let c_feature = unsafe { ogr::OGR_F_Create(output.defn().c_defn()) };
+        // forgiving, so the geometry is dropped without an error
+        let rv = unsafe { ogr::OGR_F_SetFrom(c_feature, feature._c_ptr(), 1) };
+        if rv != ogr::OGRERR_NONE {
+            unsafe { ogr::OGR_F_Destroy(c_feature) };
+            return Err(GdalError::CallFailed{desc: "OGR_F_SetFrom failed"});
+        }
+        if let Some(c_wkt) = c_wkt {
+            unsafe { ogr::OGR_F_SetFieldString(c_feature, wkt_id, c_wkt.as_ptr()) };
+        }
+        let rv = unsafe { ogr::OGR_L_CreateFeature(output._c_layer(), c_feature) };
+        unsafe { ogr::OGR_F_Destroy(c_feature) };
+        if rv != ogr::OGRERR_NONE {
+            return Err(GdalError::CallFailed{desc: "OGR_L_CreateFeature failed"});
+        }
+    }
+    return Ok(());
+}
@@ -1,15 +1,15 @@
 use libc::c_char;
-use std::ffi::CString;
-use utils::_string;
+use utils::{_cstring, _string};
+use GdalError;
 
 #[link(name="gdal")]
 extern {
     fn GDALVersionInfo(key: *const c_char) -> *const c_char;
 }
 
-pub fn version_info(key: &str) -> String {
-    let c_key = CString::new(key.as_bytes()).unwrap();
-    return _string(unsafe { GDALVersionInfo(c_key.as_ptr()) });
+pub fn version_info(key: &str) -> Result<String, GdalError> {
+    let c_key = _cstring(key)?;
+    return Ok(_string(unsafe { GDALVersionInfo(c_key.as_ptr()) }));
 }
 
 #[cfg(test)]
@@ -18,9 +18,9 @@ mod tests {
 
     #[test]
     fn test_version_info() {
-        let release_date = version_info("RELEASE_DATE");
-        let release_name = version_info("RELEASE_NAME");
-        let version_text = version_info("--version");
+        let release_date = version_info("RELEASE_DATE").unwrap();
+        let release_name = version_info("RELEASE_NAME").unwrap();
+        let version_text = version_info("--version").unwrap();
 
         let mut date_iter = release_date.chars();
 